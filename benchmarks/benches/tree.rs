@@ -0,0 +1,177 @@
+//! Benchmarks for big trees.
+//!
+//! Run with `cargo bench` in the `benchmarks` directory.
+//!
+//! The `layout` group measures a full ui pass with the layout cache invalidated,
+//! while the `ui` group measures the steady state where last frame's layout is reused.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use egui_tiles::{
+    Behavior, Container, ContainerKind, SimplificationOptions, Tile, TileId, Tiles, Tree,
+    UiResponse,
+};
+
+const SIZES: [usize; 4] = [10, 100, 1_000, 10_000];
+
+struct Pane;
+
+/// Does as little as possible, so that we measure the tree and not the panes.
+struct NoopBehavior;
+
+impl Behavior<Pane> for NoopBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+        "Pane".into()
+    }
+
+    fn simplification_options(&self) -> SimplificationOptions {
+        SimplificationOptions::OFF
+    }
+}
+
+/// A tree of roughly `num_tiles` tiles, with panes grouped into containers of all kinds.
+fn create_tree(num_tiles: usize) -> Tree<Pane> {
+    const GROUP_SIZE: usize = 4;
+    let kinds = [
+        ContainerKind::Horizontal,
+        ContainerKind::Tabs,
+        ContainerKind::Vertical,
+        ContainerKind::Grid,
+    ];
+
+    let mut tiles = Tiles::default();
+    let num_panes = (num_tiles * (GROUP_SIZE - 1) / GROUP_SIZE).max(1);
+    let mut level: Vec<TileId> = (0..num_panes).map(|_| tiles.insert_pane(Pane)).collect();
+
+    let mut kind_index = 0;
+    while level.len() > 1 {
+        level = level
+            .chunks(GROUP_SIZE)
+            .map(|children| {
+                kind_index += 1;
+                let kind = kinds[kind_index % kinds.len()];
+                tiles.insert_new(Tile::Container(Container::new(kind, children.to_vec())))
+            })
+            .collect();
+    }
+
+    Tree::new("bench_tree", level[0], tiles)
+}
+
+fn screen_input() -> egui::RawInput {
+    egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(1920.0, 1080.0),
+        )),
+        ..Default::default()
+    }
+}
+
+fn run_ui(ctx: &egui::Context, tree: &mut Tree<Pane>, dragged: Option<TileId>) {
+    let mut input = screen_input();
+    if dragged.is_some() {
+        input
+            .events
+            .push(egui::Event::PointerMoved(egui::pos2(600.0, 400.0)));
+    }
+
+    let _output = ctx.run(input, |ctx| {
+        if let Some(dragged) = dragged {
+            ctx.set_dragged_id(dragged.egui_id(tree.id()));
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            tree.ui(&mut NoopBehavior, ui);
+        });
+    });
+}
+
+fn bench_layout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("layout");
+    for num_tiles in SIZES {
+        let mut tree = create_tree(num_tiles);
+        let ctx = egui::Context::default();
+        run_ui(&ctx, &mut tree, None);
+
+        group.bench_function(BenchmarkId::from_parameter(num_tiles), |b| {
+            b.iter(|| {
+                // Force a full layout, instead of reusing last frame's:
+                tree.tiles.invalidate_layout();
+                run_ui(&ctx, &mut tree, None);
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_ui(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ui");
+    for num_tiles in SIZES {
+        let mut tree = create_tree(num_tiles);
+        let ctx = egui::Context::default();
+        run_ui(&ctx, &mut tree, None);
+
+        group.bench_function(BenchmarkId::from_parameter(num_tiles), |b| {
+            b.iter(|| run_ui(&ctx, &mut tree, None));
+        });
+    }
+    group.finish();
+}
+
+fn bench_drag(c: &mut Criterion) {
+    let mut group = c.benchmark_group("drag");
+    for num_tiles in SIZES {
+        let mut tree = create_tree(num_tiles);
+        let ctx = egui::Context::default();
+        run_ui(&ctx, &mut tree, None);
+
+        // Drag some pane around, which computes all the drop targets:
+        let dragged = tree
+            .tiles
+            .tile_ids()
+            .find(|&tile_id| tree.tiles.get(tile_id).is_some_and(|tile| tile.is_pane()));
+
+        group.bench_function(BenchmarkId::from_parameter(num_tiles), |b| {
+            b.iter(|| run_ui(&ctx, &mut tree, dragged));
+        });
+    }
+    group.finish();
+}
+
+fn bench_simplify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simplify");
+    for num_tiles in SIZES {
+        let mut tree = create_tree(num_tiles);
+        let options = SimplificationOptions::default();
+
+        group.bench_function(BenchmarkId::from_parameter(num_tiles), |b| {
+            b.iter(|| tree.simplify(&options));
+        });
+    }
+    group.finish();
+}
+
+fn bench_gc(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gc");
+    for num_tiles in SIZES {
+        let mut tree = create_tree(num_tiles);
+
+        group.bench_function(BenchmarkId::from_parameter(num_tiles), |b| {
+            b.iter(|| tree.gc(&mut NoopBehavior));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_layout,
+    bench_ui,
+    bench_drag,
+    bench_simplify,
+    bench_gc
+);
+criterion_main!(benches);
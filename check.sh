@@ -11,6 +11,7 @@ export RUSTDOCFLAGS="--deny warnings"
 
 cargo fmt --all -- --check
 cargo clippy --quiet --all-targets --all-features -- --deny warnings
+cargo check --quiet --all-targets --no-default-features
 cargo test --quiet --all-targets --all-features
 cargo test --quiet --doc --all-features # checks all doc-tests
 
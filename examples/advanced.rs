@@ -61,7 +61,10 @@ struct TreeBehavior {
     simplification_options: egui_tiles::SimplificationOptions,
     tab_bar_height: f32,
     gap_width: f32,
+    dim_unfocused_panes: bool,
+    drag_handle_height: f32,
     add_child_to: Option<egui_tiles::TileId>,
+    set_kind: Option<(egui_tiles::TileId, egui_tiles::ContainerKind)>,
 }
 
 impl Default for TreeBehavior {
@@ -70,7 +73,10 @@ impl Default for TreeBehavior {
             simplification_options: Default::default(),
             tab_bar_height: 24.0,
             gap_width: 2.0,
+            dim_unfocused_panes: false,
+            drag_handle_height: 0.0,
             add_child_to: None,
+            set_kind: None,
         }
     }
 }
@@ -81,7 +87,10 @@ impl TreeBehavior {
             simplification_options,
             tab_bar_height,
             gap_width,
+            dim_unfocused_panes,
+            drag_handle_height,
             add_child_to: _,
+            set_kind: _,
         } = self;
 
         egui::Grid::new("behavior_ui")
@@ -109,6 +118,18 @@ impl TreeBehavior {
                 ui.label("Gap width:");
                 ui.add(egui::DragValue::new(gap_width).range(0.0..=20.0).speed(1.0));
                 ui.end_row();
+
+                ui.label("Dim unfocused panes:");
+                ui.checkbox(dim_unfocused_panes, "");
+                ui.end_row();
+
+                ui.label("Drag handle height:");
+                ui.add(
+                    egui::DragValue::new(drag_handle_height)
+                        .range(0.0..=40.0)
+                        .speed(1.0),
+                );
+                ui.end_row();
             });
     }
 }
@@ -138,6 +159,9 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
         if ui.button("➕").clicked() {
             self.add_child_to = Some(tile_id);
         }
+        if let Some(kind) = egui_tiles::ContainerKind::Tabs.switcher_ui(ui) {
+            self.set_kind = Some((tile_id, kind));
+        }
     }
 
     // ---
@@ -155,6 +179,26 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
         self.simplification_options
     }
 
+    fn dim_unfocused_panes(&self) -> bool {
+        self.dim_unfocused_panes
+    }
+
+    fn pane_drag_handle_height(
+        &self,
+        _style: &egui::Style,
+        _tile_id: egui_tiles::TileId,
+        _pane: &Pane,
+    ) -> f32 {
+        self.drag_handle_height
+    }
+
+    fn jump_to_pane_shortcut(&self) -> Option<egui::KeyboardShortcut> {
+        Some(egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND,
+            egui::Key::P,
+        ))
+    }
+
     fn is_tab_closable(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
         true
     }
@@ -265,9 +309,7 @@ impl eframe::App for MyApp {
 
             ui.separator();
 
-            if let Some(root) = self.tree.root() {
-                tree_ui(ui, &mut self.behavior, &mut self.tree.tiles, root);
-            }
+            self.tree.inspector_ui(&mut self.behavior, ui);
 
             if let Some(parent) = self.behavior.add_child_to.take() {
                 let new_child = self.tree.tiles.insert_pane(Pane::with_nr(100));
@@ -278,6 +320,17 @@ impl eframe::App for MyApp {
                     tabs.set_active(new_child);
                 }
             }
+            if let Some((tile_id, kind)) = self.behavior.set_kind.take() {
+                if let Some(egui_tiles::Tile::Container(container)) =
+                    self.tree.tiles.get_mut(tile_id)
+                {
+                    container.set_kind(kind);
+                }
+            }
+        });
+
+        egui::TopBottomPanel::bottom("breadcrumbs").show(ctx, |ui| {
+            self.tree.breadcrumbs_ui(&mut self.behavior, ui);
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -290,59 +343,3 @@ impl eframe::App for MyApp {
         eframe::set_value(_storage, eframe::APP_KEY, &self);
     }
 }
-
-fn tree_ui(
-    ui: &mut egui::Ui,
-    behavior: &mut dyn egui_tiles::Behavior<Pane>,
-    tiles: &mut egui_tiles::Tiles<Pane>,
-    tile_id: egui_tiles::TileId,
-) {
-    // Get the name BEFORE we remove the tile below!
-    let text = format!(
-        "{} - {tile_id:?}",
-        behavior.tab_title_for_tile(tiles, tile_id).text()
-    );
-
-    // Temporarily remove the tile to circumvent the borrowchecker
-    let Some(mut tile) = tiles.remove(tile_id) else {
-        log::debug!("Missing tile {tile_id:?}");
-        return;
-    };
-
-    let default_open = true;
-    egui::collapsing_header::CollapsingState::load_with_default_open(
-        ui.ctx(),
-        ui.id().with((tile_id, "tree")),
-        default_open,
-    )
-    .show_header(ui, |ui| {
-        ui.label(text);
-        let mut visible = tiles.is_visible(tile_id);
-        ui.checkbox(&mut visible, "Visible");
-        tiles.set_visible(tile_id, visible);
-    })
-    .body(|ui| match &mut tile {
-        egui_tiles::Tile::Pane(_) => {}
-        egui_tiles::Tile::Container(container) => {
-            let mut kind = container.kind();
-            egui::ComboBox::from_label("Kind")
-                .selected_text(format!("{kind:?}"))
-                .show_ui(ui, |ui| {
-                    for typ in egui_tiles::ContainerKind::ALL {
-                        ui.selectable_value(&mut kind, typ, format!("{typ:?}"))
-                            .clicked();
-                    }
-                });
-            if kind != container.kind() {
-                container.set_kind(kind);
-            }
-
-            for &child in container.children() {
-                tree_ui(ui, behavior, tiles, child);
-            }
-        }
-    });
-
-    // Put the tile back
-    tiles.insert(tile_id, tile);
-}
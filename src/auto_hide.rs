@@ -0,0 +1,297 @@
+use egui::{pos2, vec2, Color32, Rect, Sense, Ui};
+
+use crate::{Behavior, DropContext, TabState, TileId, Tree};
+
+/// An edge of a [`Tree`], along which auto-hidden tiles are shown as a thin strip of labels.
+///
+/// See [`Tree::auto_hide`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Edge {
+    /// A vertical strip along the left side; pinning docks the tile as the first column.
+    Left,
+
+    /// A vertical strip along the right side; pinning docks the tile as the last column.
+    Right,
+
+    /// A horizontal strip along the top; pinning docks the tile as the first row.
+    Top,
+
+    /// A horizontal strip along the bottom; pinning docks the tile as the last row.
+    Bottom,
+}
+
+impl Edge {
+    pub const ALL: [Self; 4] = [Self::Left, Self::Right, Self::Top, Self::Bottom];
+
+    /// Does the strip along this edge run top-down?
+    fn is_vertical(self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+}
+
+/// Which auto-hidden tile is currently slid out.
+///
+/// Stored in egui temp storage, just like the tab scroll state.
+#[derive(Clone, Copy, Debug, Default)]
+struct FlyoutState {
+    open: Option<TileId>,
+
+    /// Was it opened by a click?
+    /// If so, it stays open until the user clicks elsewhere.
+    /// Otherwise it closes as soon as it is no longer hovered.
+    sticky: bool,
+}
+
+impl<Pane> Tree<Pane> {
+    /// The space left for the docked tiles after the auto-hide strips have been allocated.
+    pub(crate) fn rect_without_auto_hide_strips(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        style: &egui::Style,
+        mut rect: Rect,
+    ) -> Rect {
+        let thickness = behavior.auto_hide_strip_thickness(style);
+        for edge in Edge::ALL {
+            if self.auto_hidden.iter().any(|&(_, e)| e == edge) {
+                match edge {
+                    Edge::Left => rect.min.x += thickness,
+                    Edge::Right => rect.max.x -= thickness,
+                    Edge::Top => rect.min.y += thickness,
+                    Edge::Bottom => rect.max.y -= thickness,
+                }
+            }
+        }
+        rect
+    }
+
+    /// Show the auto-hide strips, and slide out the tile that is hovered or clicked.
+    pub(crate) fn auto_hide_ui(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &Ui,
+        tree_rect: Rect,
+    ) {
+        if self.auto_hidden.is_empty() {
+            return;
+        }
+
+        let content_rect = self.rect_without_auto_hide_strips(behavior, ui.style(), tree_rect);
+
+        let state_id = self.id.with("auto_hide_flyout");
+        let mut state: FlyoutState = ui
+            .ctx()
+            .data_mut(|data| data.get_temp(state_id))
+            .unwrap_or_default();
+        if state.open.is_some_and(|open| !self.is_auto_hidden(open)) {
+            state = FlyoutState::default();
+        }
+
+        let mut hovered_button = None;
+        let mut clicked_button = None;
+        let mut strip_rects = vec![];
+
+        for edge in Edge::ALL {
+            let tiles_on_edge: Vec<TileId> = self
+                .auto_hidden
+                .iter()
+                .filter(|&&(_, e)| e == edge)
+                .map(|&(tile_id, _)| tile_id)
+                .collect();
+            if tiles_on_edge.is_empty() {
+                continue;
+            }
+
+            let strip_rect = match edge {
+                Edge::Left => Rect::from_x_y_ranges(
+                    tree_rect.left()..=content_rect.left(),
+                    content_rect.y_range(),
+                ),
+                Edge::Right => Rect::from_x_y_ranges(
+                    content_rect.right()..=tree_rect.right(),
+                    content_rect.y_range(),
+                ),
+                Edge::Top => Rect::from_x_y_ranges(
+                    content_rect.x_range(),
+                    tree_rect.top()..=content_rect.top(),
+                ),
+                Edge::Bottom => Rect::from_x_y_ranges(
+                    content_rect.x_range(),
+                    content_rect.bottom()..=tree_rect.bottom(),
+                ),
+            };
+            strip_rects.push(strip_rect);
+
+            ui.painter()
+                .rect_filled(strip_rect, 0.0, behavior.tab_bar_color(ui.visuals()));
+
+            let mut cursor = if edge.is_vertical() {
+                strip_rect.top()
+            } else {
+                strip_rect.left()
+            };
+
+            for tile_id in tiles_on_edge {
+                let title = behavior.tab_title_for_tile(&self.tiles, tile_id);
+                let galley = title.into_galley(
+                    ui,
+                    Some(egui::TextWrapMode::Extend),
+                    f32::INFINITY,
+                    egui::TextStyle::Button,
+                );
+                let margin = behavior.tab_title_spacing(ui.visuals());
+                let length = galley.size().x + 2.0 * margin;
+
+                let button_rect = if edge.is_vertical() {
+                    Rect::from_min_size(
+                        pos2(strip_rect.left(), cursor),
+                        vec2(strip_rect.width(), length),
+                    )
+                } else {
+                    Rect::from_min_size(
+                        pos2(cursor, strip_rect.top()),
+                        vec2(length, strip_rect.height()),
+                    )
+                };
+                cursor += length;
+
+                let response = ui.interact(
+                    button_rect,
+                    self.id.with(("auto_hide_button", tile_id)),
+                    Sense::click(),
+                );
+                if response.clicked() {
+                    clicked_button = Some(tile_id);
+                } else if response.hovered() {
+                    hovered_button = Some(tile_id);
+                }
+
+                let tab_state = TabState {
+                    active: state.open == Some(tile_id),
+                    ..Default::default()
+                };
+                let bg_color = if tab_state.active {
+                    behavior.tab_bg_color(ui.visuals(), &self.tiles, tile_id, &tab_state)
+                } else if response.hovered() {
+                    ui.visuals().widgets.hovered.weak_bg_fill
+                } else {
+                    Color32::TRANSPARENT
+                };
+                ui.painter().rect_filled(button_rect, 0.0, bg_color);
+
+                let text_color =
+                    behavior.tab_text_color(ui.visuals(), &self.tiles, tile_id, &tab_state);
+                if edge.is_vertical() {
+                    // Rotate the text so that it reads top-down:
+                    let pos = pos2(
+                        button_rect.center().x + 0.5 * galley.size().y,
+                        button_rect.top() + margin,
+                    );
+                    ui.painter().add(
+                        egui::epaint::TextShape::new(pos, galley, text_color)
+                            .with_angle(std::f32::consts::FRAC_PI_2),
+                    );
+                } else {
+                    let pos = button_rect.left_center() + vec2(margin, -0.5 * galley.size().y);
+                    ui.painter().galley(pos, galley, text_color);
+                }
+            }
+        }
+
+        if let Some(tile_id) = clicked_button {
+            if state.open == Some(tile_id) && state.sticky {
+                state = FlyoutState::default();
+            } else {
+                state = FlyoutState {
+                    open: Some(tile_id),
+                    sticky: true,
+                };
+            }
+        } else if let Some(tile_id) = hovered_button {
+            if !state.sticky {
+                state.open = Some(tile_id);
+            }
+        }
+
+        let fraction = behavior.auto_hide_flyout_fraction();
+
+        if let Some(open) = state.open {
+            if let Some(&(_, edge)) = self.auto_hidden.iter().find(|&&(id, _)| id == open) {
+                let flyout_rect = flyout_rect(edge, content_rect, fraction);
+                let pointer_pos = ui.ctx().pointer_hover_pos();
+                let pointer_in_flyout = pointer_pos.is_some_and(|pos| flyout_rect.contains(pos));
+                let pointer_in_strips =
+                    pointer_pos.is_some_and(|pos| strip_rects.iter().any(|r| r.contains(pos)));
+
+                if state.sticky {
+                    let clicked_elsewhere = ui.input(|i| i.pointer.any_click())
+                        && !pointer_in_flyout
+                        && !pointer_in_strips;
+                    if clicked_elsewhere {
+                        state = FlyoutState::default();
+                    }
+                } else if hovered_button.is_none() && !pointer_in_flyout {
+                    state = FlyoutState::default();
+                }
+            }
+        }
+
+        ui.ctx().data_mut(|data| data.insert_temp(state_id, state));
+
+        // Show the flyouts, including the ones that are still animating closed:
+        for (tile_id, edge) in self.auto_hidden.clone() {
+            let t = ui.ctx().animate_bool_with_time(
+                self.id.with(("auto_hide_flyout", tile_id)),
+                state.open == Some(tile_id),
+                ui.style().animation_time,
+            );
+            if t <= 0.0 {
+                continue;
+            }
+
+            let rect = flyout_rect(edge, content_rect, t * fraction);
+
+            egui::Area::new(self.id.with(("auto_hide_area", tile_id)))
+                .order(egui::Order::Foreground)
+                .fixed_pos(rect.min)
+                .show(ui.ctx(), |ui| {
+                    // Claim the whole rect so nothing behind the flyout is interactive:
+                    ui.allocate_rect(rect, Sense::hover());
+                    ui.painter().rect_filled(rect, 0.0, ui.visuals().panel_fill);
+
+                    self.tiles.layout_tile(
+                        ui.style(),
+                        self.layout_params(ui.ctx().pixels_per_point()),
+                        behavior,
+                        rect,
+                        None,
+                        tile_id,
+                    );
+
+                    let mut drop_context = DropContext::disabled();
+                    self.tile_ui(behavior, &mut drop_context, ui, tile_id);
+
+                    ui.painter()
+                        .rect_stroke(rect, 0.0, ui.visuals().window_stroke);
+                });
+        }
+    }
+}
+
+/// The rect of a slid-out tile, covering the given `fraction` of the docked tiles.
+fn flyout_rect(edge: Edge, content_rect: Rect, fraction: f32) -> Rect {
+    let width = fraction * content_rect.width();
+    let height = fraction * content_rect.height();
+    match edge {
+        Edge::Left => Rect::from_min_size(content_rect.min, vec2(width, content_rect.height())),
+        Edge::Right => Rect::from_min_max(
+            pos2(content_rect.right() - width, content_rect.top()),
+            content_rect.max,
+        ),
+        Edge::Top => Rect::from_min_size(content_rect.min, vec2(content_rect.width(), height)),
+        Edge::Bottom => Rect::from_min_max(
+            pos2(content_rect.left(), content_rect.bottom() - height),
+            content_rect.max,
+        ),
+    }
+}
@@ -1,12 +1,19 @@
 use egui::{
-    vec2, Color32, Id, Rect, Response, Rgba, Sense, Stroke, TextStyle, Ui, Vec2, Visuals,
+    vec2, Color32, Id, NumExt as _, Rect, Response, Sense, Stroke, TextStyle, Ui, Vec2, Visuals,
     WidgetText,
 };
 
-use super::{ResizeState, SimplificationOptions, Tile, TileId, Tiles, UiResponse};
+use super::{
+    DividerAction, DropIndicator, DropOperation, EditPermissions, GridEdit, GridGutter,
+    PaneFactory, ResizeState, SimplificationOptions, Tile, TileId, TileTreeStyle, Tiles, TreeEvent,
+    UiResponse,
+};
 
 /// The kind of edit that triggered the call to [`Behavior::on_edit`].
+///
+/// More kinds of edits may be added in the future, so this is `#[non_exhaustive]`.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum EditAction {
     /// A tile was resized by dragging or double-clicking a boundary.
     TileResized,
@@ -20,6 +27,69 @@ pub enum EditAction {
     /// A tab was selected by a click, or by hovering a dragged tile over it,
     /// or there was no active tab and egui picked an arbitrary one.
     TabSelected,
+
+    /// A tab was closed with its close button.
+    TabClosed,
+
+    /// A row or column was inserted into or deleted from a grid, see [`GridEdit`].
+    GridEdited,
+
+    /// A section of a [`crate::Linear`] was folded or unfolded,
+    /// see [`crate::Linear::collapsible`].
+    SectionToggled,
+
+    /// A pane was created from one of the built-in "add pane" menus,
+    /// see [`Behavior::pane_factories`].
+    PaneAdded,
+
+    /// The layout was reverted with [`crate::Tree::undo`].
+    LayoutUndone,
+
+    /// The layout was re-applied with [`crate::Tree::redo`].
+    LayoutRedone,
+}
+
+/// The edits made to a [`crate::Tree`] since the last call to [`crate::Tree::take_edit_summary`].
+///
+/// Each kind of [`EditAction`] is only listed once, in the order they first happened.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditSummary {
+    actions: Vec<EditAction>,
+}
+
+impl EditSummary {
+    /// No edits were made.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// The kinds of edits that were made.
+    #[inline]
+    pub fn actions(&self) -> &[EditAction] {
+        &self.actions
+    }
+
+    /// Was this kind of edit made?
+    pub fn contains(&self, edit_action: &EditAction) -> bool {
+        self.actions.contains(edit_action)
+    }
+
+    /// Was the layout changed, i.e. is it time to save it?
+    ///
+    /// This is everything except [`EditAction::TileDragged`],
+    /// which only marks the start of a drag.
+    pub fn layout_changed(&self) -> bool {
+        self.actions
+            .iter()
+            .any(|action| action != &EditAction::TileDragged)
+    }
+
+    pub(crate) fn add(&mut self, edit_action: EditAction) {
+        if !self.contains(&edit_action) {
+            self.actions.push(edit_action);
+        }
+    }
 }
 
 /// The state of a tab, used to inform the rendering of the tab.
@@ -33,6 +103,70 @@ pub struct TabState {
 
     /// Should the tab have a close button?
     pub closable: bool,
+
+    /// Can the tab be dragged? See [`Behavior::edit_permissions`].
+    pub draggable: bool,
+
+    /// Is the tab part of a multi-selection? See [`crate::Tree::selected_tabs`].
+    pub multi_selected: bool,
+
+    /// Does the tab match [`crate::Tree::filter`]? `None` if there is no filter.
+    pub matches_filter: Option<bool>,
+}
+
+/// What happens when the user drops a tile into a container that already has
+/// [`Behavior::max_children`], see [`Behavior::container_overflow`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContainerOverflow {
+    /// Put the dropped tile in a [`crate::Tabs`] together with the child it was dropped next to.
+    #[default]
+    WrapInTabs,
+
+    /// Don't allow dropping tiles into the container.
+    Reject,
+}
+
+/// How to animate switching to another tab, see [`Behavior::tab_switch_animation`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TabSwitchAnimation {
+    /// Show the new tab right away.
+    #[default]
+    None,
+
+    /// Fade out the previous tab while fading in the new one.
+    Crossfade,
+
+    /// Slide the new tab in from the side it is on in the tab bar,
+    /// pushing the previous tab out the other side.
+    Slide,
+}
+
+/// Why a pane started or stopped being shown,
+/// see [`Behavior::on_pane_shown`] and [`Behavior::on_pane_hidden`].
+///
+/// When a pane is shown again, the reason is the one it was hidden for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VisibilityReason {
+    /// The pane is shown for the first time since it was added to the tree.
+    Inserted,
+
+    /// A [`crate::Tabs`] above the pane switched to or away from it.
+    TabSwitched,
+
+    /// A [`crate::Linear`] above the pane folded it away or unfolded it.
+    Collapsed,
+
+    /// The pane or one of its ancestors was made invisible or visible,
+    /// see [`crate::Tree::set_visible`].
+    Invisible,
+
+    /// The pane or one of its ancestors was auto-hidden or pinned,
+    /// or its flyout opened or closed, see [`crate::Tree::auto_hide`].
+    AutoHidden,
+
+    /// The pane is still on the active path, but got no room this frame,
+    /// e.g. because it was scrolled out of view (see [`Behavior::cull_off_screen_tiles`]).
+    OutOfView,
 }
 
 /// Trait defining how the [`super::Tree`] and its panes should be shown.
@@ -41,6 +175,10 @@ pub trait Behavior<Pane> {
     ///
     /// You can make the pane draggable by returning [`UiResponse::DragStarted`]
     /// when the user drags some handle.
+    ///
+    /// This is only called for panes that are actually shown:
+    /// a pane hidden behind an inactive tab gets no call,
+    /// unless [`Self::render_inactive`] returns `true` for it.
     fn pane_ui(&mut self, ui: &mut Ui, tile_id: TileId, pane: &mut Pane) -> UiResponse;
 
     /// The title of a pane tab.
@@ -51,6 +189,118 @@ pub trait Behavior<Pane> {
         false
     }
 
+    /// A keyboard shortcut for toggling [`crate::Tree::set_debug_overlay`].
+    ///
+    /// `None` by default, so no key is taken from your app.
+    fn debug_overlay_shortcut(&self) -> Option<egui::KeyboardShortcut> {
+        None
+    }
+
+    /// A keyboard shortcut for [`crate::Tree::open_jump_to_pane`], e.g. `Ctrl+P`.
+    ///
+    /// `None` by default, so no key is taken from your app.
+    fn jump_to_pane_shortcut(&self) -> Option<egui::KeyboardShortcut> {
+        None
+    }
+
+    /// Should a pane picked in [`crate::Tree::open_jump_to_pane`] flash briefly,
+    /// so the user can see where it is?
+    fn flash_jumped_pane(&self) -> bool {
+        true
+    }
+
+    /// Make a copy of a pane, for [`crate::Tree::duplicate`] and the split [`crate::Command`]s.
+    ///
+    /// Return `None` (the default) if the pane can't be copied.
+    fn clone_pane(&mut self, _pane: &Pane) -> Option<Pane> {
+        None
+    }
+
+    /// Should the default [`Self::tab_ui`] have a context menu with a "Duplicate" entry for panes?
+    ///
+    /// See [`crate::Tree::duplicate`] and [`Self::clone_pane`].
+    fn duplicate_in_tab_context_menu(&self) -> bool {
+        false
+    }
+
+    /// Which edits the user may make to the given tile (container or pane).
+    ///
+    /// Everything is allowed by default.
+    /// See also [`crate::Tree::set_edit_mode`] for locking the whole tree.
+    fn edit_permissions(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> EditPermissions {
+        EditPermissions::ALL
+    }
+
+    /// May the user make this edit from the context menu of a grid gutter?
+    ///
+    /// Right-clicking the line between two rows or columns of a resizable [`crate::Grid`]
+    /// offers to insert or delete rows and columns there. Return `false` to hide an entry.
+    fn is_grid_edit_allowed(
+        &self,
+        _tiles: &Tiles<Pane>,
+        _grid_id: TileId,
+        _edit: GridEdit,
+    ) -> bool {
+        true
+    }
+
+    /// Show handles on the top edge of each column and the left edge of each row
+    /// of the given resizable [`crate::Grid`], for dragging whole columns and rows to another place.
+    ///
+    /// The handles show up while the grid is hovered. Off by default.
+    /// The moves can be vetoed with [`Self::is_grid_edit_allowed`].
+    fn grid_reorder_handles(&self, _tiles: &Tiles<Pane>, _grid_id: TileId) -> bool {
+        false
+    }
+
+    /// Add your own entries to the context menu of a grid gutter,
+    /// below the built-in ones (see [`Self::is_grid_edit_allowed`]).
+    fn grid_gutter_context_menu(
+        &mut self,
+        _tiles: &mut Tiles<Pane>,
+        _ui: &mut Ui,
+        _grid_id: TileId,
+        _gutter: GridGutter,
+    ) {
+    }
+
+    /// May the user pick this action from the context menu of a divider?
+    ///
+    /// Right-clicking the line between two children of a [`crate::Linear`] offers to equalize,
+    /// collapse or lock them. Return `false` to hide an entry.
+    fn is_divider_action_allowed(
+        &self,
+        _tiles: &Tiles<Pane>,
+        _linear_id: TileId,
+        _action: DividerAction,
+    ) -> bool {
+        true
+    }
+
+    /// Add your own entries to the context menu of a [`crate::Linear`] divider,
+    /// below the built-in ones (see [`Self::is_divider_action_allowed`]).
+    ///
+    /// `pair` are the children on either side of the divider, left/top first.
+    fn divider_context_menu(
+        &mut self,
+        _tiles: &mut Tiles<Pane>,
+        _ui: &mut Ui,
+        _linear_id: TileId,
+        _pair: [TileId; 2],
+    ) {
+    }
+
+    /// Should the tab bar of the given [`crate::Tabs`] have a button that closes all its tabs?
+    ///
+    /// `false` by default. See [`crate::Tree::close_all_tabs`].
+    ///
+    /// The button is never shown if [`Self::edit_permissions`] of the [`crate::Tabs`]
+    /// doesn't allow closing, e.g. when the tree is [`crate::EditMode::Locked`],
+    /// nor if none of its tabs can be closed.
+    fn close_all_tabs_button(&self, _tiles: &Tiles<Pane>, _tabs_id: TileId) -> bool {
+        false
+    }
+
     /// Called when the close-button on a tab is pressed.
     ///
     /// Return `false` to abort the closing of a tab (e.g. after showing a message box).
@@ -114,9 +364,12 @@ pub trait Behavior<Pane> {
             + f32::from(state.closable) * (close_btn_left_padding + close_btn_size.x);
         let (_, tab_rect) = ui.allocate_space(vec2(button_width, ui.available_height()));
 
-        let tab_response = ui
-            .interact(tab_rect, id, Sense::click_and_drag())
-            .on_hover_cursor(egui::CursorIcon::Grab);
+        let tab_response = if state.draggable {
+            ui.interact(tab_rect, id, Sense::click_and_drag())
+                .on_hover_cursor(egui::CursorIcon::Grab)
+        } else {
+            ui.interact(tab_rect, id, Sense::click())
+        };
 
         // Show a gap when dragged
         if ui.is_rect_visible(tab_rect) && !state.is_being_dragged {
@@ -147,42 +400,17 @@ pub trait Behavior<Pane> {
             if state.closable {
                 let close_btn_rect = egui::Align2::RIGHT_CENTER
                     .align_size_within_rect(close_btn_size, tab_rect.shrink(x_margin));
+                close_button_ui(self, tiles, ui, close_btn_rect, tile_id);
+            }
+        }
 
-                // Allocate
-                let close_btn_id = ui.auto_id_with("tab_close_btn");
-                let close_btn_response = ui
-                    .interact(close_btn_rect, close_btn_id, Sense::click_and_drag())
-                    .on_hover_cursor(egui::CursorIcon::Default);
-
-                let visuals = ui.style().interact(&close_btn_response);
-
-                // Scale based on the interaction visuals
-                let rect = close_btn_rect
-                    .shrink(self.close_button_inner_margin())
-                    .expand(visuals.expansion);
-                let stroke = visuals.fg_stroke;
-
-                // paint the crossed lines
-                ui.painter() // paints \
-                    .line_segment([rect.left_top(), rect.right_bottom()], stroke);
-                ui.painter() // paints /
-                    .line_segment([rect.right_top(), rect.left_bottom()], stroke);
-
-                // Give the user a chance to react to the close button being clicked
-                // Only close if the user returns true (handled)
-                if close_btn_response.clicked() {
-                    log::debug!("Tab close requested for tile: {tile_id:?}");
-
-                    // Close the tab if the implementation wants to
-                    if self.on_tab_close(tiles, tile_id) {
-                        log::debug!("Implementation confirmed close request for tile: {tile_id:?}");
-
-                        tiles.remove(tile_id);
-                    } else {
-                        log::debug!("Implementation denied close request for tile: {tile_id:?}");
-                    }
+        if self.duplicate_in_tab_context_menu() && tiles.get_pane(&tile_id).is_some() {
+            tab_response.context_menu(|ui| {
+                if ui.button("Duplicate").clicked() {
+                    tiles.request_duplicate(tile_id);
+                    ui.close_menu();
                 }
-            }
+            });
         }
 
         self.on_tab_button(tiles, tile_id, tab_response)
@@ -214,6 +442,33 @@ pub trait Behavior<Pane> {
         true
     }
 
+    /// Should [`crate::Tree::normalize`] give this pane a [`crate::Tabs`] container of its own?
+    ///
+    /// Return `false` for kinds of panes that should never get a tab, e.g. toolbars.
+    fn wrap_pane_in_tabs(&self, _tile_id: TileId, _pane: &Pane) -> bool {
+        true
+    }
+
+    /// Should this pane keep getting [`Self::pane_ui`] calls while it is hidden behind an inactive tab?
+    ///
+    /// Return `true` for panes that need to keep updating in the background.
+    /// They are shown in an invisible [`Ui`] the size of the active tab.
+    /// This also applies to panes nested in a container behind an inactive tab.
+    fn render_inactive(&self, _tile_id: TileId, _pane: &Pane) -> bool {
+        false
+    }
+
+    /// Skip the ui of tiles that are entirely outside the clip rectangle,
+    /// e.g. when the tree is inside a [`egui::ScrollArea`].
+    ///
+    /// Culled panes get no [`Self::pane_ui`] call, and count as hidden for [`Self::on_pane_hidden`].
+    /// Grids go one step further and don't even lay out the descendants of cells
+    /// outside the clip rectangle, which keeps grids with thousands of cells fast.
+    /// Return `false` to show all tiles regardless.
+    fn cull_off_screen_tiles(&self) -> bool {
+        true
+    }
+
     /// Adds some UI to the top right of each tab bar.
     ///
     /// You can use this to, for instance, add a button for adding new tabs.
@@ -234,15 +489,260 @@ pub trait Behavior<Pane> {
         // }
     }
 
+    /// The most children the given container may get from drag-and-drop, if limited.
+    ///
+    /// Use this to keep users from splitting a container into unusable slivers,
+    /// e.g. return `Some(4)` for [`crate::Linear`] containers.
+    /// What happens when a tile is dropped into a full container is up to
+    /// [`Self::container_overflow`]. Tiles inserted from code are not limited.
+    fn max_children(&self, _tiles: &Tiles<Pane>, _container_id: TileId) -> Option<usize> {
+        None
+    }
+
+    /// What to do with a tile dropped into a container that has [`Self::max_children`].
+    ///
+    /// A full [`crate::Tabs`] always rejects drops, since wrapping a tab in tabs doesn't help.
+    fn container_overflow(&self, _tiles: &Tiles<Pane>, _container_id: TileId) -> ContainerOverflow {
+        ContainerOverflow::WrapInTabs
+    }
+
+    /// How much of a [`crate::Linear`] container a pane should get when the user drops it there.
+    ///
+    /// A fraction in the 0.0 - 1.0 range, e.g. a console could ask for `0.2`
+    /// and a viewer for `0.5`. See [`crate::Linear::set_fraction`].
+    /// `None` (the default) gives it the same share as any other child.
+    fn preferred_share(&self, _pane: &Pane) -> Option<f32> {
+        None
+    }
+
+    /// The kinds of panes the user can create from the built-in "add pane" menus.
+    ///
+    /// If there are any, a "➕" button listing them is shown on each tab bar,
+    /// in the middle of empty containers, and in empty grid cells,
+    /// as long as [`Self::edit_permissions`] allows dropping into the container.
+    /// The pane is created with [`Self::create_pane`].
+    /// Empty by default, which hides the menus.
+    fn pane_factories(&self) -> Vec<PaneFactory> {
+        vec![]
+    }
+
+    /// Create a pane of the kind the user picked from [`Self::pane_factories`].
+    ///
+    /// Return `None` to not add anything after all.
+    fn create_pane(&mut self, _factory: &PaneFactory) -> Option<Pane> {
+        None
+    }
+
+    /// How far in from the outer edges of a tab bar a dropped tile splits the [`crate::Tabs`]
+    /// in that direction, instead of becoming another tab.
+    ///
+    /// Return zero to only allow dropping tiles as tabs there.
+    fn tab_bar_split_margin(&self, _style: &egui::Style) -> f32 {
+        6.0
+    }
+
     /// The height of the bar holding tab titles.
-    fn tab_bar_height(&self, _style: &egui::Style) -> f32 {
-        24.0
+    fn tab_bar_height(&self, style: &egui::Style) -> f32 {
+        self.tile_tree_style(&style.visuals).tab_bar_height
+    }
+
+    /// The height of the title bar above each child of a [`crate::Linear`] or [`crate::Grid`].
+    ///
+    /// Title bars give the children of split layouts a place to be dragged and closed from,
+    /// like the tabs of [`crate::Tabs`]. Zero (the default) means no title bars.
+    /// The title bars are shown with [`Self::title_bar_ui`].
+    /// If this changes at runtime, call [`Tiles::invalidate_layout`].
+    fn title_bar_height(&self, _style: &egui::Style) -> f32 {
+        0.0
+    }
+
+    /// Show the title bar above a child of a [`crate::Linear`] or [`crate::Grid`],
+    /// filling all of `ui`. See [`Self::title_bar_height`].
+    ///
+    /// Just like with [`Self::tab_ui`], the returned [`Response`] must use the given `id`
+    /// and sense drags for the user to be able to drag the tile by its title bar.
+    /// `state.active` is always `true`.
+    ///
+    /// The default implementation shows the title from [`Self::tab_title_for_tile`],
+    /// and a close button if `state.closable`.
+    fn title_bar_ui(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        ui: &mut Ui,
+        id: Id,
+        tile_id: TileId,
+        state: &TabState,
+    ) -> Response {
+        let rect = ui.max_rect();
+        let response = if state.draggable {
+            ui.interact(rect, id, Sense::click_and_drag())
+                .on_hover_cursor(egui::CursorIcon::Grab)
+        } else {
+            ui.interact(rect, id, Sense::click())
+        };
+        if state.is_being_dragged || !ui.is_rect_visible(rect) {
+            return response;
+        }
+
+        ui.painter()
+            .rect_filled(rect, 0.0, self.tab_bar_color(ui.visuals()));
+
+        let mut text_rect = rect.shrink2(vec2(self.tab_title_spacing(ui.visuals()), 0.0));
+        if state.closable {
+            let close_btn_rect = egui::Align2::RIGHT_CENTER
+                .align_size_within_rect(Vec2::splat(self.close_button_outer_size()), text_rect);
+            text_rect.max.x = close_btn_rect.left() - 4.0;
+            close_button_ui(self, tiles, ui, close_btn_rect, tile_id);
+        }
+
+        let text = self.tab_title_for_tile(tiles, tile_id);
+        let font_id = TextStyle::Button.resolve(ui.style());
+        let galley = text.into_galley(
+            ui,
+            Some(egui::TextWrapMode::Truncate),
+            text_rect.width(),
+            font_id,
+        );
+        let text_pos = egui::Align2::LEFT_CENTER
+            .align_size_within_rect(galley.size(), text_rect)
+            .min;
+        let text_color = self.tab_text_color(ui.visuals(), tiles, tile_id, state);
+        ui.painter().galley(text_pos, galley, text_color);
+
+        response
+    }
+
+    /// The height of the section headers of a [`crate::Linear`] with
+    /// [`crate::Linear::collapsible`] set. These replace the title bars.
+    ///
+    /// Defaults to [`Self::tab_bar_height`].
+    /// If this changes at runtime, call [`Tiles::invalidate_layout`].
+    fn section_header_height(&self, style: &egui::Style) -> f32 {
+        self.tab_bar_height(style)
+    }
+
+    /// Show the header of a collapsible section, filling all of `ui`.
+    /// See [`crate::Linear::collapsible`].
+    ///
+    /// Clicking the returned [`Response`] folds or unfolds the section.
+    /// The default implementation shows a collapse arrow
+    /// and the title from [`Self::tab_title_for_tile`].
+    fn section_header_ui(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        ui: &mut Ui,
+        id: Id,
+        tile_id: TileId,
+        collapsed: bool,
+    ) -> Response {
+        let rect = ui.max_rect();
+        let response = ui.interact(rect, id, Sense::click());
+        if !ui.is_rect_visible(rect) {
+            return response;
+        }
+
+        ui.painter()
+            .rect_filled(rect, 0.0, self.tab_bar_color(ui.visuals()));
+
+        let spacing = self.tab_title_spacing(ui.visuals());
+        let icon_width = ui.spacing().icon_width;
+        let icon_rect = Rect::from_center_size(
+            egui::pos2(rect.left() + spacing + 0.5 * icon_width, rect.center().y),
+            Vec2::splat(icon_width),
+        );
+        let openness = if collapsed { 0.0 } else { 1.0 };
+        egui::collapsing_header::paint_default_icon(
+            ui,
+            openness,
+            &response.clone().with_new_rect(icon_rect),
+        );
+
+        let mut text_rect = rect.shrink2(vec2(spacing, 0.0));
+        text_rect.min.x = icon_rect.right() + spacing;
+        let text = self.tab_title_for_tile(tiles, tile_id);
+        let font_id = TextStyle::Button.resolve(ui.style());
+        let galley = text.into_galley(
+            ui,
+            Some(egui::TextWrapMode::Truncate),
+            text_rect.width(),
+            font_id,
+        );
+        let text_pos = egui::Align2::LEFT_CENTER
+            .align_size_within_rect(galley.size(), text_rect)
+            .min;
+        ui.painter().galley(
+            text_pos,
+            galley,
+            ui.style().interact(&response).text_color(),
+        );
+
+        response
+    }
+
+    /// How to animate switching to another tab.
+    fn tab_switch_animation(&self) -> TabSwitchAnimation {
+        TabSwitchAnimation::None
+    }
+
+    /// How long the [`Self::tab_switch_animation`] lasts, in seconds.
+    fn tab_switch_duration(&self, style: &egui::Style) -> f32 {
+        style.animation_time
+    }
+
+    /// Maps the linear progress `t` of an animation (0-1) to the shown progress (0-1).
+    ///
+    /// Used by [`Self::tab_switch_animation`], [`Self::animate_drops`],
+    /// and [`Self::animate_insertions_and_removals`].
+    /// See [`egui::emath::easing`] for some alternatives.
+    fn animation_easing(&self, t: f32) -> f32 {
+        egui::emath::easing::cubic_out(t)
     }
 
     /// Width of the gap between tiles in a horizontal or vertical layout,
     /// and between rows/columns in a grid layout.
-    fn gap_width(&self, _style: &egui::Style) -> f32 {
-        1.0
+    fn gap_width(&self, style: &egui::Style) -> f32 {
+        self.tile_tree_style(&style.visuals).gap_width
+    }
+
+    /// Width of the gap between the children of a container at the given nesting depth,
+    /// where the root is at depth 0.
+    ///
+    /// Use this to e.g. have wide gaps at the top of the tree and narrow ones deep inside it.
+    /// Defaults to [`Self::gap_width`] at every depth.
+    fn gap_width_at_depth(&self, style: &egui::Style, _depth: usize) -> f32 {
+        self.gap_width(style)
+    }
+
+    /// The color of the gaps between the children of a container at the given nesting depth.
+    ///
+    /// Defaults to the color of [`Self::resize_stroke`] when idle.
+    fn gap_color(&self, style: &egui::Style, _depth: usize) -> Color32 {
+        self.resize_stroke(style, ResizeState::Idle).color
+    }
+
+    /// The background of a container at the given nesting depth,
+    /// painted over its whole rect before any of its children.
+    ///
+    /// Use this to group tiles visually, e.g. with a slightly different shade for each tool area.
+    /// The gaps between the children are painted on top with [`Self::gap_color`],
+    /// so make that transparent if the background should show through them.
+    ///
+    /// Transparent (the default) paints nothing.
+    fn container_bg_color(
+        &self,
+        _visuals: &Visuals,
+        _tiles: &Tiles<Pane>,
+        _tile_id: TileId,
+        _depth: usize,
+    ) -> Color32 {
+        Color32::TRANSPARENT
+    }
+
+    /// The fill of the given pane in [`crate::Tree::minimap_ui`].
+    ///
+    /// Use this to e.g. color panes by what they show, so they are easy to find at a glance.
+    fn minimap_color(&self, visuals: &Visuals, _tiles: &Tiles<Pane>, _tile_id: TileId) -> Color32 {
+        visuals.widgets.inactive.bg_fill
     }
 
     /// No child should shrink below this width nor height.
@@ -250,6 +750,27 @@ pub trait Behavior<Pane> {
         32.0
     }
 
+    /// When a dropped tile changes the layout, animate all tiles from where they were
+    /// to where they end up, instead of moving them there instantly.
+    fn animate_drops(&self) -> bool {
+        false
+    }
+
+    /// When tiles are added or removed between frames (e.g. by your code),
+    /// animate the other tiles growing into the freed space or shrinking to make room,
+    /// instead of moving them there instantly.
+    ///
+    /// Return `false` to turn this off.
+    fn animate_insertions_and_removals(&self) -> bool {
+        true
+    }
+
+    /// How long the [`Self::animate_drops`] and [`Self::animate_insertions_and_removals`]
+    /// animations last, in seconds.
+    fn layout_animation_duration(&self, style: &egui::Style) -> f32 {
+        style.animation_time
+    }
+
     /// Show we preview panes that are being dragged,
     /// i.e. show their ui in the region where they will end up?
     fn preview_dragged_panes(&self) -> bool {
@@ -258,7 +779,7 @@ pub trait Behavior<Pane> {
 
     /// Cover the tile that is being dragged with this color.
     fn dragged_overlay_color(&self, visuals: &Visuals) -> Color32 {
-        visuals.panel_fill.gamma_multiply(0.5)
+        self.tile_tree_style(visuals).dragged_overlay_color
     }
 
     /// What are the rules for simplifying the tree?
@@ -266,6 +787,87 @@ pub trait Behavior<Pane> {
         SimplificationOptions::default()
     }
 
+    /// How rounded the corners of a tile (container or pane) are, at the given nesting depth.
+    ///
+    /// The root is at depth 0, its children at depth 1, and so on.
+    /// The content of a rounded tile is clipped to the rounded shape by covering
+    /// the corners with the [`Self::gap_color`] of its parent.
+    fn tile_rounding(&self, _style: &egui::Style, _depth: usize) -> egui::Rounding {
+        egui::Rounding::ZERO
+    }
+
+    /// The shadow around a tile (container or pane), at the given nesting depth.
+    ///
+    /// A tile with a shadow gets a [`Visuals::panel_fill`] background,
+    /// so that the shadow doesn't show through it.
+    /// See also [`Self::tile_rounding`].
+    fn tile_shadow(&self, _style: &egui::Style, _depth: usize) -> egui::Shadow {
+        egui::Shadow::NONE
+    }
+
+    /// Paint the background of a pane, right before its [`Self::pane_ui`].
+    ///
+    /// Use this to give individual panes a distinct background color or pattern,
+    /// e.g. to show that the data behind a pane is disconnected,
+    /// without each pane having to paint its own background.
+    fn paint_pane_background(
+        &self,
+        _painter: &egui::Painter,
+        _rect: Rect,
+        _tile_id: TileId,
+        _pane: &Pane,
+    ) {
+    }
+
+    /// The frame around the [`Ui`] of a pane.
+    ///
+    /// Use this for padding, borders, or a fill that should be the same for all panes,
+    /// instead of adding them in each [`Self::pane_ui`].
+    /// The frame is painted after [`Self::paint_pane_background`].
+    fn pane_frame(&self, _style: &egui::Style, _tile_id: TileId, _pane: &Pane) -> egui::Frame {
+        egui::Frame::none()
+    }
+
+    /// The height of a strip along the top of a pane that the user can drag the pane by.
+    ///
+    /// With a strip, [`Self::pane_ui`] only gets the rest of the pane,
+    /// so e.g. a plot in it can keep its own drags without moving the pane around.
+    /// Zero (the default) means no strip, so only [`UiResponse::DragStarted`] starts a drag.
+    ///
+    /// Deferred panes (see [`crate::Tree::ui_deferred`]) get no strip.
+    fn pane_drag_handle_height(&self, _style: &egui::Style, _tile_id: TileId, _pane: &Pane) -> f32 {
+        0.0
+    }
+
+    /// Paint the strip from [`Self::pane_drag_handle_height`].
+    ///
+    /// `response` covers the whole strip; the library starts the drag when it is dragged.
+    /// The default implementation shows the title of the pane.
+    fn pane_drag_handle_ui(
+        &mut self,
+        ui: &mut Ui,
+        _tile_id: TileId,
+        pane: &mut Pane,
+        response: &Response,
+    ) {
+        let rect = ui.max_rect();
+        let visuals = ui.style().interact(response);
+        ui.painter().rect_filled(rect, 0.0, visuals.weak_bg_fill);
+
+        let text_rect = rect.shrink2(vec2(ui.spacing().item_spacing.x, 0.0));
+        let font_id = TextStyle::Button.resolve(ui.style());
+        let galley = self.tab_title_for_pane(pane).into_galley(
+            ui,
+            Some(egui::TextWrapMode::Truncate),
+            text_rect.width(),
+            font_id,
+        );
+        let text_pos = egui::Align2::LEFT_CENTER
+            .align_size_within_rect(galley.size(), text_rect)
+            .min;
+        ui.painter().galley(text_pos, galley, visuals.text_color());
+    }
+
     /// Add some custom painting on top of a tile (container or pane), e.g. draw an outline on top of it.
     fn paint_on_top_of_tile(
         &self,
@@ -276,29 +878,137 @@ pub trait Behavior<Pane> {
     ) {
     }
 
+    /// Should the container holding the [`crate::Tree::focused_pane`] get an accent?
+    ///
+    /// See [`Self::paint_focused_group_accent`].
+    fn highlight_focused_group(&self) -> bool {
+        true
+    }
+
+    /// Paint the accent on the container holding the [`crate::Tree::focused_pane`].
+    ///
+    /// `tab_bar_rect` is set if the container is a [`crate::Tabs`].
+    /// The default draws a thin border around the container,
+    /// and underlines the tab bar with [`TileTreeStyle::focused_group_underline`].
+    fn paint_focused_group_accent(
+        &self,
+        painter: &egui::Painter,
+        visuals: &Visuals,
+        rect: Rect,
+        tab_bar_rect: Option<Rect>,
+    ) {
+        let style = self.tile_tree_style(visuals);
+        let border = style.focused_group_border;
+        if 0.0 < border.width {
+            painter.rect_stroke(rect.shrink(border.width / 2.0), 0.0, border);
+        }
+        if let Some(tab_bar_rect) = tab_bar_rect {
+            let underline = style.focused_group_underline;
+            let y = tab_bar_rect.bottom() - underline.width / 2.0;
+            painter.hline(tab_bar_rect.x_range(), y, underline);
+        }
+    }
+
+    /// Spotlight mode: dim every pane except the [`crate::Tree::focused_pane`].
+    ///
+    /// This is checked each frame, so you can toggle it at runtime.
+    /// Nothing is dimmed while no pane has focus.
+    fn dim_unfocused_panes(&self) -> bool {
+        false
+    }
+
+    /// Does the given pane match the text of [`crate::Tree::set_filter`]?
+    ///
+    /// The default looks for the text in [`Self::tab_title_for_tile`], ignoring case.
+    fn matches_filter(&mut self, tiles: &Tiles<Pane>, tile_id: TileId, filter: &str) -> bool {
+        let title = self
+            .tab_title_for_tile(tiles, tile_id)
+            .text()
+            .to_lowercase();
+        title.contains(&filter.to_lowercase())
+    }
+
+    /// Hide the tabs of panes that don't match [`crate::Tree::filter`]
+    /// (except for the active tab), instead of just greying them out.
+    fn hide_filtered_tabs(&self) -> bool {
+        false
+    }
+
+    /// Covers the panes that don't have focus, when [`Self::dim_unfocused_panes`] is on,
+    /// and the panes that don't match [`crate::Tree::filter`].
+    fn unfocused_pane_overlay_color(&self, visuals: &Visuals) -> Color32 {
+        self.tile_tree_style(visuals).unfocused_pane_overlay_color
+    }
+
+    /// Is the pane busy, e.g. loading or streaming data?
+    ///
+    /// A busy pane is still shown, but disabled, so the user can't interact with it,
+    /// and [`Self::busy_pane_ui`] is shown on top of it.
+    fn is_pane_busy(&self, _tile_id: TileId, _pane: &Pane) -> bool {
+        false
+    }
+
+    /// Shown on top of a pane while [`Self::is_pane_busy`].
+    ///
+    /// The `ui` covers the pane (below any drag handle), and is clipped to it.
+    /// The default dims the pane and shows a spinner in the middle of it.
+    fn busy_pane_ui(&mut self, ui: &mut Ui, _tile_id: TileId) {
+        let rect = ui.max_rect();
+        let color = self.unfocused_pane_overlay_color(ui.visuals());
+        ui.painter().rect_filled(rect, 0.0, color);
+
+        let spinner_size = 24.0_f32.at_most(rect.width().min(rect.height()));
+        let spinner_rect = Rect::from_center_size(rect.center(), Vec2::splat(spinner_size));
+        ui.put(spinner_rect, egui::Spinner::new().size(spinner_size));
+    }
+
     /// The stroke used for the lines in horizontal, vertical, and grid layouts.
+    ///
+    /// When idle, the lines are painted with [`Self::gap_width_at_depth`] and [`Self::gap_color`] instead,
+    /// which default to this.
     fn resize_stroke(&self, style: &egui::Style, resize_state: ResizeState) -> Stroke {
         match resize_state {
             ResizeState::Idle => {
                 Stroke::new(self.gap_width(style), self.tab_bar_color(&style.visuals))
             }
-            ResizeState::Hovering => style.visuals.widgets.hovered.fg_stroke,
-            ResizeState::Dragging => style.visuals.widgets.active.fg_stroke,
+            ResizeState::Hovering => self.tile_tree_style(&style.visuals).resize_hovered_stroke,
+            ResizeState::Dragging => self.tile_tree_style(&style.visuals).resize_dragging_stroke,
         }
     }
 
+    /// Hold these and scroll over the line between two tiles to resize them in small steps,
+    /// without having to grab the line precisely.
+    ///
+    /// Defaults to [`egui::Modifiers::CTRL`]. Return `None` to turn this off.
+    fn scroll_resize_modifiers(&self) -> Option<egui::Modifiers> {
+        Some(egui::Modifiers::CTRL)
+    }
+
+    /// All the colors and sizes used to paint the tree.
+    ///
+    /// Override this to theme the whole tree at once,
+    /// e.g. with [`TileTreeStyle::dark`], [`TileTreeStyle::light`], or your own.
+    /// The default picks colors that go with the given egui visuals.
+    fn tile_tree_style(&self, visuals: &Visuals) -> TileTreeStyle {
+        TileTreeStyle::from_visuals(visuals)
+    }
+
     /// Extra spacing to left and right of tab titles.
-    fn tab_title_spacing(&self, _visuals: &Visuals) -> f32 {
-        8.0
+    fn tab_title_spacing(&self, visuals: &Visuals) -> f32 {
+        self.tile_tree_style(visuals).tab_title_spacing
+    }
+
+    /// How wide the fade is at an edge of the tab bar where tabs are scrolled out of view.
+    ///
+    /// The fade goes from [`Self::tab_bar_color`] to transparent,
+    /// so that users can tell there are more tabs. Return zero to turn it off.
+    fn tab_bar_fade_width(&self, visuals: &Visuals) -> f32 {
+        self.tile_tree_style(visuals).tab_bar_fade_width
     }
 
     /// The background color of the tab bar.
     fn tab_bar_color(&self, visuals: &Visuals) -> Color32 {
-        if visuals.dark_mode {
-            visuals.extreme_bg_color
-        } else {
-            (Rgba::from(visuals.panel_fill) * Rgba::from_gray(0.8)).into()
-        }
+        self.tile_tree_style(visuals).tab_bar_color
     }
 
     /// The background color of a tab.
@@ -309,10 +1019,13 @@ pub trait Behavior<Pane> {
         _tile_id: TileId,
         state: &TabState,
     ) -> Color32 {
+        let style = self.tile_tree_style(visuals);
         if state.active {
-            visuals.panel_fill // same as the tab contents
+            style.active_tab_bg_color
+        } else if state.multi_selected {
+            style.selected_tab_bg_color
         } else {
-            Color32::TRANSPARENT // fade into background
+            style.inactive_tab_bg_color
         }
     }
 
@@ -324,16 +1037,19 @@ pub trait Behavior<Pane> {
         _tile_id: TileId,
         state: &TabState,
     ) -> Stroke {
-        if state.active {
-            Stroke::new(1.0, visuals.widgets.active.bg_fill)
+        let style = self.tile_tree_style(visuals);
+        if state.matches_filter == Some(true) {
+            visuals.selection.stroke
+        } else if state.active {
+            style.active_tab_outline
         } else {
-            Stroke::NONE
+            style.inactive_tab_outline
         }
     }
 
     /// Stroke of the line separating the tab title bar and the content of the active tab.
     fn tab_bar_hline_stroke(&self, visuals: &Visuals) -> Stroke {
-        Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color)
+        self.tile_tree_style(visuals).tab_bar_hline
     }
 
     /// The color of the title text of the tab.
@@ -347,21 +1063,48 @@ pub trait Behavior<Pane> {
         _tile_id: TileId,
         state: &TabState,
     ) -> Color32 {
-        if state.active {
-            visuals.widgets.active.text_color()
+        let style = self.tile_tree_style(visuals);
+        let color = if state.active {
+            style.active_tab_text_color
+        } else {
+            style.inactive_tab_text_color
+        };
+        if state.matches_filter == Some(false) {
+            color.gamma_multiply(0.4)
         } else {
-            visuals.widgets.noninteractive.text_color()
+            color
         }
     }
 
     /// When drag-and-dropping a tile, the candidate area is drawn with this stroke.
     fn drag_preview_stroke(&self, visuals: &Visuals) -> Stroke {
-        visuals.selection.stroke
+        self.tile_tree_style(visuals).drag_preview_stroke
     }
 
     /// When drag-and-dropping a tile, the candidate area is drawn with this background color.
     fn drag_preview_color(&self, visuals: &Visuals) -> Color32 {
-        visuals.selection.stroke.color.gamma_multiply(0.5)
+        self.tile_tree_style(visuals).drag_preview_fill
+    }
+
+    /// How long the drag preview takes to move (most of the way) to a new drop target, in seconds.
+    ///
+    /// Return `0.0` to make it jump there right away.
+    fn drag_preview_move_time(&self) -> f32 {
+        0.05
+    }
+
+    /// How long the drag preview takes to fade in when the pointer reaches a drop target,
+    /// and to fade out when it leaves it, in seconds.
+    fn drag_preview_fade_time(&self, style: &egui::Style) -> f32 {
+        style.animation_time
+    }
+
+    /// How to show where a dragged tile will land, for each kind of drop.
+    ///
+    /// The shape is passed to [`Self::paint_drag_preview`] as its `preview_rect`.
+    /// Defaults to [`TileTreeStyle::drop_indicator`] for everything.
+    fn drop_indicator(&self, visuals: &Visuals, _operation: DropOperation) -> DropIndicator {
+        self.tile_tree_style(visuals).drop_indicator
     }
 
     /// When drag-and-dropping a tile, how do we preview what is about to happen?
@@ -374,13 +1117,97 @@ pub trait Behavior<Pane> {
     ) {
         let preview_stroke = self.drag_preview_stroke(visuals);
         let preview_color = self.drag_preview_color(visuals);
+        let rounding = self.tile_tree_style(visuals).drag_preview_rounding;
 
         if let Some(parent_rect) = parent_rect {
             // Show which parent we will be dropped into
-            painter.rect_stroke(parent_rect, 1.0, preview_stroke);
+            painter.rect_stroke(parent_rect, rounding, preview_stroke);
+        }
+
+        painter.rect(preview_rect, rounding, preview_color, preview_stroke);
+    }
+
+    /// The icon shown in the middle of the drop preview, telling what a drop will do.
+    ///
+    /// Return `None` to show no icon for that operation.
+    fn drop_operation_icon(&self, operation: DropOperation) -> Option<&'static str> {
+        Some(match operation {
+            DropOperation::SplitLeft => "⬅",
+            DropOperation::SplitRight => "➡",
+            DropOperation::SplitUp => "⬆",
+            DropOperation::SplitDown => "⬇",
+            DropOperation::Tab => "🗀",
+            DropOperation::Grid => "⊞",
+        })
+    }
+
+    /// Paint the [`Self::drop_operation_icon`] in the middle of the drop preview.
+    fn paint_drop_operation_icon(
+        &self,
+        visuals: &Visuals,
+        painter: &egui::Painter,
+        preview_rect: Rect,
+        operation: DropOperation,
+    ) {
+        let Some(icon) = self.drop_operation_icon(operation) else {
+            return;
+        };
+        let size = (preview_rect.size().min_elem() / 3.0).at_most(32.0);
+        if size < 8.0 {
+            return; // too small to make out
         }
+        painter.text(
+            preview_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            icon,
+            egui::FontId::proportional(size),
+            self.drag_preview_stroke(visuals).color,
+        );
+    }
+
+    /// The thickness of the strips along the edges of the tree that hold
+    /// the labels of auto-hidden tiles (see [`crate::Tree::auto_hide`]).
+    fn auto_hide_strip_thickness(&self, style: &egui::Style) -> f32 {
+        self.tab_bar_height(style)
+    }
+
+    /// How large of a fraction of the tree an auto-hidden tile covers when slid out.
+    fn auto_hide_flyout_fraction(&self) -> f32 {
+        0.3
+    }
+
+    /// Where to show the modal tile (see [`crate::Tree::enter_modal`]),
+    /// given the rect of the whole tree.
+    fn modal_rect(&self, tree_rect: Rect) -> Rect {
+        Rect::from_center_size(tree_rect.center(), 0.75 * tree_rect.size())
+    }
+
+    /// Paint the backdrop behind a modal tile, dimming the rest of the tree.
+    ///
+    /// This is painted before the modal tile itself.
+    fn paint_modal_backdrop(
+        &self,
+        visuals: &Visuals,
+        painter: &egui::Painter,
+        tree_rect: Rect,
+        modal_rect: Rect,
+    ) {
+        painter.rect_filled(
+            tree_rect,
+            0.0,
+            self.tile_tree_style(visuals).modal_backdrop_color,
+        );
+        painter.rect(
+            modal_rect,
+            visuals.window_rounding,
+            visuals.panel_fill,
+            visuals.window_stroke,
+        );
+    }
 
-        painter.rect(preview_rect, 1.0, preview_color, preview_stroke);
+    /// Should clicking the backdrop outside of the modal tile close the modal?
+    fn close_modal_on_backdrop_click(&self) -> bool {
+        true
     }
 
     /// How many columns should we use for a [`crate::Grid`] put into [`crate::GridLayout::Auto`]?
@@ -409,6 +1236,84 @@ pub trait Behavior<Pane> {
     /// Called if the user edits the tree somehow, e.g. changes the size of some container,
     /// clicks a tab, or drags a tile.
     fn on_edit(&mut self, _edit_action: EditAction) {}
+
+    /// Called once for each tile that shows up in the tree, with the container it is in.
+    ///
+    /// This covers tiles inserted from code, the containers created by drag-and-drop,
+    /// and every tile of a freshly deserialized tree, so it is a good place to set up
+    /// application state that goes with a tile.
+    /// It is called by [`crate::Tree::ui`] before the tile is shown, parents first.
+    /// `parent` is `None` for the root and auto-hidden tiles.
+    fn on_tile_inserted(
+        &mut self,
+        _tiles: &mut Tiles<Pane>,
+        _tile_id: TileId,
+        _parent: Option<TileId>,
+    ) {
+    }
+
+    /// Called when a pane becomes shown, e.g. because its tab was selected.
+    ///
+    /// This is called at the end of the frame in which the pane was first shown.
+    /// `reason` is why it was hidden before, or [`VisibilityReason::Inserted`] for new panes.
+    fn on_pane_shown(&mut self, _tile_id: TileId, _pane: &mut Pane, _reason: VisibilityReason) {}
+
+    /// Called when a pane that was shown is no longer shown, e.g. because another tab was selected.
+    ///
+    /// Use this to pause expensive background work (streaming, GPU uploads, …)
+    /// until the matching [`Self::on_pane_shown`].
+    /// This is not called for panes that are removed from the tree.
+    fn on_pane_hidden(&mut self, _tile_id: TileId, _pane: &mut Pane, _reason: VisibilityReason) {}
+}
+
+/// The close button of the default [`Behavior::tab_ui`] and [`Behavior::title_bar_ui`].
+///
+/// Closes the tile when clicked, unless [`Behavior::on_tab_close`] says no.
+fn close_button_ui<Pane, B: Behavior<Pane> + ?Sized>(
+    behavior: &mut B,
+    tiles: &mut Tiles<Pane>,
+    ui: &Ui,
+    close_btn_rect: Rect,
+    tile_id: TileId,
+) {
+    // Allocate
+    let close_btn_id = ui.auto_id_with("tab_close_btn");
+    let close_btn_response = ui
+        .interact(close_btn_rect, close_btn_id, Sense::click_and_drag())
+        .on_hover_cursor(egui::CursorIcon::Default);
+
+    let visuals = ui.style().interact(&close_btn_response);
+
+    // Scale based on the interaction visuals
+    let rect = close_btn_rect
+        .shrink(behavior.close_button_inner_margin())
+        .expand(visuals.expansion);
+    let stroke = visuals.fg_stroke;
+
+    // paint the crossed lines
+    ui.painter() // paints \
+        .line_segment([rect.left_top(), rect.right_bottom()], stroke);
+    ui.painter() // paints /
+        .line_segment([rect.right_top(), rect.left_bottom()], stroke);
+
+    // Give the user a chance to react to the close button being clicked
+    // Only close if the user returns true (handled)
+    if close_btn_response.clicked() {
+        log::debug!("Tab close requested for tile: {tile_id:?}");
+
+        // Close the tab if the implementation wants to
+        if behavior.on_tab_close(tiles, tile_id) {
+            log::debug!("Implementation confirmed close request for tile: {tile_id:?}");
+
+            tiles.on_edit(behavior, EditAction::TabClosed);
+            tiles.push_event(TreeEvent::TileClosed(tile_id));
+            if let Some(tile) = tiles.remove(tile_id) {
+                tiles.bury([(tile_id, tile)]);
+            }
+        } else {
+            log::debug!("Implementation denied close request for tile: {tile_id:?}");
+        }
+    }
 }
 
 /// How many columns should we use to fit `n` children in a grid?
@@ -0,0 +1,53 @@
+use egui::Ui;
+
+use crate::{Behavior, TileId, Tree};
+
+/// Shown between the segments of [`Tree::breadcrumbs_ui`].
+const SEPARATOR: &str = "▸";
+
+impl<Pane> Tree<Pane> {
+    /// The path from the root to the [`Self::focused_pane`], e.g. "Main ▸ Right split ▸ Logs",
+    /// for a bar above or below the tree.
+    ///
+    /// Each segment is titled with [`Behavior::tab_title_for_tile`],
+    /// so override that to give your containers readable names.
+    /// Clicking a segment shows that tile (e.g. switching to its tab),
+    /// and focuses it if it is a pane.
+    ///
+    /// Shows nothing if no pane has focus.
+    /// Returns the tile whose segment was clicked, if any.
+    pub fn breadcrumbs_ui(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+    ) -> Option<TileId> {
+        let focused = self.focused?;
+
+        let mut path: Vec<TileId> = self.tiles.ancestors(focused).collect();
+        path.reverse();
+        path.push(focused);
+
+        let mut clicked = None;
+        ui.horizontal(|ui| {
+            for (i, &tile_id) in path.iter().enumerate() {
+                if 0 < i {
+                    ui.weak(SEPARATOR);
+                }
+                let mut title = behavior.tab_title_for_tile(&self.tiles, tile_id);
+                if tile_id == focused {
+                    title = title.strong();
+                }
+                if ui.add(egui::Button::new(title).frame(false)).clicked() {
+                    clicked = Some(tile_id);
+                }
+            }
+        });
+
+        if let Some(tile_id) = clicked {
+            if !self.focus_pane(tile_id) {
+                self.make_active(|id, _| id == tile_id);
+            }
+        }
+        clicked
+    }
+}
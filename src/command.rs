@@ -0,0 +1,383 @@
+use crate::{
+    Behavior, Container, ContainerInsertion, Edge, EditAction, InsertionPoint, Linear, LinearDir,
+    SimplifyAction, Tile, TileId, Tree, TreeEvent,
+};
+
+/// The smallest part of a linear container a tile can be shrunk to with [`Command::ResizeShrink`].
+const MIN_RESIZE_FRACTION: f32 = 0.05;
+
+/// A layout operation on the [`Tree::focused_pane`], in the spirit of i3 and tmux.
+///
+/// Run it with [`Tree::execute`]. This gives command palettes, scripts, and key bindings
+/// one uniform way to drive the layout.
+///
+/// Commands can also be parsed from text, see [`Self::parse`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// Focus the closest pane to the left of the focused one.
+    FocusLeft,
+
+    /// Focus the closest pane to the right of the focused one.
+    FocusRight,
+
+    /// Focus the closest pane above the focused one.
+    FocusUp,
+
+    /// Focus the closest pane below the focused one.
+    FocusDown,
+
+    /// Move the focused pane to the left of its neighbor on the left.
+    MoveLeft,
+
+    /// Move the focused pane to the right of its neighbor on the right.
+    MoveRight,
+
+    /// Move the focused pane above its neighbor above.
+    MoveUp,
+
+    /// Move the focused pane below its neighbor below.
+    MoveDown,
+
+    /// Show a copy of the focused pane to the right of it, see [`Behavior::clone_pane`].
+    SplitHorizontal,
+
+    /// Show a copy of the focused pane below it, see [`Behavior::clone_pane`].
+    SplitVertical,
+
+    /// Close the focused pane, asking [`Behavior::on_tab_close`] first.
+    CloseFocused,
+
+    /// Grow the focused pane by this fraction of its closest horizontal or vertical container,
+    /// e.g. `0.1` for 10%.
+    ResizeGrow(f32),
+
+    /// Shrink the focused pane by this fraction of its closest horizontal or vertical container.
+    ResizeShrink(f32),
+}
+
+impl Command {
+    /// Parse a command like `focus left`, `move right`, `split vertical`, `close`,
+    /// or `resize grow 10%`.
+    ///
+    /// This is the same format as [`Self`]'s `Display`. Case and extra whitespace are ignored.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.to_ascii_lowercase();
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let command = match words.as_slice() {
+            ["focus", "left"] => Self::FocusLeft,
+            ["focus", "right"] => Self::FocusRight,
+            ["focus", "up"] => Self::FocusUp,
+            ["focus", "down"] => Self::FocusDown,
+            ["move", "left"] => Self::MoveLeft,
+            ["move", "right"] => Self::MoveRight,
+            ["move", "up"] => Self::MoveUp,
+            ["move", "down"] => Self::MoveDown,
+            ["split", "horizontal"] => Self::SplitHorizontal,
+            ["split", "vertical"] => Self::SplitVertical,
+            ["close"] => Self::CloseFocused,
+            ["resize", "grow", amount] => Self::ResizeGrow(parse_percent(amount)?),
+            ["resize", "shrink", amount] => Self::ResizeShrink(parse_percent(amount)?),
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// `10%` is `0.1`.
+fn parse_percent(text: &str) -> Option<f32> {
+    let percent: f32 = text.strip_suffix('%')?.parse().ok()?;
+    (percent.is_finite() && 0.0 <= percent).then_some(percent / 100.0)
+}
+
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FocusLeft => f.write_str("focus left"),
+            Self::FocusRight => f.write_str("focus right"),
+            Self::FocusUp => f.write_str("focus up"),
+            Self::FocusDown => f.write_str("focus down"),
+            Self::MoveLeft => f.write_str("move left"),
+            Self::MoveRight => f.write_str("move right"),
+            Self::MoveUp => f.write_str("move up"),
+            Self::MoveDown => f.write_str("move down"),
+            Self::SplitHorizontal => f.write_str("split horizontal"),
+            Self::SplitVertical => f.write_str("split vertical"),
+            Self::CloseFocused => f.write_str("close"),
+            Self::ResizeGrow(amount) => write!(f, "resize grow {}%", amount * 100.0),
+            Self::ResizeShrink(amount) => write!(f, "resize shrink {}%", amount * 100.0),
+        }
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Run a layout command on the [`Self::focused_pane`].
+    ///
+    /// Directions are resolved using where the panes were shown last frame,
+    /// so call this between calls to [`Self::ui`].
+    /// Commands that change the layout respect [`Self::edit_permissions`],
+    /// so they do nothing while the tree is locked.
+    ///
+    /// Returns `false` if the command had no effect,
+    /// e.g. because there is no pane in that direction.
+    pub fn execute(&mut self, behavior: &mut dyn Behavior<Pane>, command: Command) -> bool {
+        match command {
+            Command::FocusLeft => self.focus_towards(Edge::Left),
+            Command::FocusRight => self.focus_towards(Edge::Right),
+            Command::FocusUp => self.focus_towards(Edge::Top),
+            Command::FocusDown => self.focus_towards(Edge::Bottom),
+            Command::MoveLeft => self.move_towards(behavior, Edge::Left),
+            Command::MoveRight => self.move_towards(behavior, Edge::Right),
+            Command::MoveUp => self.move_towards(behavior, Edge::Top),
+            Command::MoveDown => self.move_towards(behavior, Edge::Bottom),
+            Command::SplitHorizontal => self.split_focused(behavior, LinearDir::Horizontal),
+            Command::SplitVertical => self.split_focused(behavior, LinearDir::Vertical),
+            Command::CloseFocused => self.close_focused(behavior),
+            Command::ResizeGrow(amount) => self.resize_focused(behavior, amount),
+            Command::ResizeShrink(amount) => self.resize_focused(behavior, -amount),
+        }
+    }
+
+    fn focus_towards(&mut self, edge: Edge) -> bool {
+        let next = if let Some(focused) = self.focused {
+            self.pane_towards(focused, edge)
+        } else {
+            // Nothing to move from yet, so start somewhere:
+            self.shown_panes().next()
+        };
+        next.is_some_and(|next| self.focus_pane(next))
+    }
+
+    fn move_towards(&mut self, behavior: &mut dyn Behavior<Pane>, edge: Edge) -> bool {
+        let Some(focused) = self.focused else {
+            return false;
+        };
+        let Some(target) = self.pane_towards(focused, edge) else {
+            return false;
+        };
+        if !self.edit_permissions(behavior, focused).drag
+            || !self.edit_permissions(behavior, target).drop_into
+        {
+            return false;
+        }
+
+        let dir = if matches!(edge, Edge::Left | Edge::Right) {
+            LinearDir::Horizontal
+        } else {
+            LinearDir::Vertical
+        };
+        let after = matches!(edge, Edge::Right | Edge::Bottom);
+
+        let insertion_point = match self.parent_linear(target, dir) {
+            Some((parent_id, index)) => {
+                InsertionPoint::new(parent_id, linear_insertion(dir, index + usize::from(after)))
+            }
+            // Put the two side by side, like dropping the pane on the edge of the target:
+            None => InsertionPoint::new(target, linear_insertion(dir, usize::from(after))),
+        };
+
+        self.move_tile(focused, insertion_point, false);
+        self.tiles.on_edit(behavior, EditAction::TileDropped);
+        true
+    }
+
+    fn split_focused(&mut self, behavior: &mut dyn Behavior<Pane>, dir: LinearDir) -> bool {
+        let Some(focused) = self.focused else {
+            return false;
+        };
+        if !self.edit_permissions(behavior, focused).drop_into {
+            return false;
+        }
+        let Some(copy) = self
+            .tiles
+            .get_pane(&focused)
+            .and_then(|pane| behavior.clone_pane(pane))
+        else {
+            return false;
+        };
+        let copy_id = self.tiles.insert_pane(copy);
+
+        if let Some((parent_id, index)) = self.parent_linear(focused, dir) {
+            self.tiles.insert_at(
+                InsertionPoint::new(parent_id, linear_insertion(dir, index + 1)),
+                copy_id,
+            );
+        } else {
+            // Wrap the pane, so that it keeps its id:
+            let parent_id = self.tiles.parent_of(focused);
+            let wrapper = self
+                .tiles
+                .insert_container(Linear::new(dir, vec![focused, copy_id]));
+            if let Some(Tile::Container(parent)) = parent_id.and_then(|id| self.tiles.get_mut(id)) {
+                parent.simplify_children(|child| {
+                    if child == focused {
+                        SimplifyAction::Replace(wrapper)
+                    } else {
+                        SimplifyAction::Keep
+                    }
+                });
+            } else if self.is_root(focused) {
+                self.root = Some(wrapper);
+            }
+            self.tiles.push_event(TreeEvent::ContainerCreated(wrapper));
+        }
+
+        self.tiles.push_event(TreeEvent::TileDuplicated {
+            original: focused,
+            copy: copy_id,
+        });
+        self.focus_pane(copy_id)
+    }
+
+    fn close_focused(&mut self, behavior: &mut dyn Behavior<Pane>) -> bool {
+        let Some(focused) = self.focused else {
+            return false;
+        };
+        if !self.edit_permissions(behavior, focused).close
+            || !behavior.on_tab_close(&mut self.tiles, focused)
+        {
+            return false;
+        }
+
+        // Pick the next focus while we still know where the closed pane was:
+        let next = self.closest_pane(focused);
+
+        self.tiles.on_edit(behavior, EditAction::TabClosed);
+        self.tiles.push_event(TreeEvent::TileClosed(focused));
+        self.close_recursively(focused);
+
+        self.focused = None;
+        if let Some(next) = next {
+            self.focus_pane(next);
+        }
+        true
+    }
+
+    /// Change the share of the focused pane, or of the ancestor of it which is
+    /// in the closest horizontal or vertical container.
+    fn resize_focused(&mut self, behavior: &mut dyn Behavior<Pane>, amount: f32) -> bool {
+        let Some(mut child) = self.focused else {
+            return false;
+        };
+        let parent_id = loop {
+            let Some(parent_id) = self.tiles.parent_of(child) else {
+                return false;
+            };
+            if matches!(
+                self.tiles.get(parent_id),
+                Some(Tile::Container(Container::Linear(_)))
+            ) {
+                break parent_id;
+            }
+            child = parent_id;
+        };
+        if !self.edit_permissions(behavior, parent_id).resize
+            || !self.edit_permissions(behavior, child).resize
+        {
+            return false;
+        }
+
+        let Some(Tile::Container(Container::Linear(linear))) = self.tiles.get_mut(parent_id) else {
+            return false;
+        };
+        let num_children = linear.children.len();
+        if num_children < 2 {
+            return false;
+        }
+        let total: f32 = linear.children.iter().map(|&id| linear.shares[id]).sum();
+        let old_share = linear.shares[child];
+        let rest = total - old_share;
+        let min_share = total * MIN_RESIZE_FRACTION;
+        let max_share = (total - min_share * (num_children - 1) as f32).max(min_share);
+        let new_share = (old_share + amount * total).clamp(min_share, max_share);
+        if new_share == old_share || rest <= 0.0 {
+            return false;
+        }
+
+        // Take the space from (or give it to) the siblings, in proportion to their size:
+        let scale = (total - new_share) / rest;
+        for &sibling in &linear.children {
+            if sibling != child {
+                let share = linear.shares[sibling];
+                linear.shares.set_share(sibling, share * scale);
+            }
+        }
+        linear.shares.set_share(child, new_share);
+
+        self.tiles.on_edit(behavior, EditAction::TileResized);
+        self.tiles.push_event(TreeEvent::Resized(parent_id));
+        true
+    }
+
+    /// The parent of the tile and the index of the tile in it,
+    /// if the parent is a linear container in the given direction.
+    fn parent_linear(&self, tile_id: TileId, dir: LinearDir) -> Option<(TileId, usize)> {
+        let parent_id = self.tiles.parent_of(tile_id)?;
+        match self.tiles.get(parent_id)? {
+            Tile::Container(Container::Linear(linear)) if linear.dir == dir => {
+                let index = linear.children.iter().position(|&child| child == tile_id)?;
+                Some((parent_id, index))
+            }
+            _ => None,
+        }
+    }
+
+    /// The panes that were shown last frame.
+    fn shown_panes(&self) -> impl Iterator<Item = TileId> + '_ {
+        self.active_tiles().into_iter().filter(|&tile_id| {
+            self.tiles.get(tile_id).is_some_and(Tile::is_pane) && self.tiles.rect(tile_id).is_some()
+        })
+    }
+
+    /// The closest shown pane in the given direction,
+    /// preferring panes that line up with the given one.
+    fn pane_towards(&self, from: TileId, edge: Edge) -> Option<TileId> {
+        let from_rect = self.tiles.rect(from)?;
+        self.shown_panes()
+            .filter(|&tile_id| tile_id != from)
+            .filter_map(|tile_id| {
+                let rect = self.tiles.rect(tile_id)?;
+                let (distance, offset) = match edge {
+                    Edge::Left => (
+                        from_rect.left() - rect.right(),
+                        rect.center().y - from_rect.center().y,
+                    ),
+                    Edge::Right => (
+                        rect.left() - from_rect.right(),
+                        rect.center().y - from_rect.center().y,
+                    ),
+                    Edge::Top => (
+                        from_rect.top() - rect.bottom(),
+                        rect.center().x - from_rect.center().x,
+                    ),
+                    Edge::Bottom => (
+                        rect.top() - from_rect.bottom(),
+                        rect.center().x - from_rect.center().x,
+                    ),
+                };
+                // Allow for rounding, but not for overlap:
+                (-0.5 <= distance).then_some((tile_id, distance.max(0.0) + offset.abs()))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(tile_id, _)| tile_id)
+    }
+
+    /// The shown pane closest to the given one, in any direction.
+    fn closest_pane(&self, from: TileId) -> Option<TileId> {
+        let from_center = self.tiles.rect(from)?.center();
+        self.shown_panes()
+            .filter(|&tile_id| tile_id != from)
+            .filter_map(|tile_id| {
+                let distance = self.tiles.rect(tile_id)?.center().distance_sq(from_center);
+                Some((tile_id, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(tile_id, _)| tile_id)
+    }
+}
+
+fn linear_insertion(dir: LinearDir, index: usize) -> ContainerInsertion {
+    match dir {
+        LinearDir::Horizontal => ContainerInsertion::Horizontal(index),
+        LinearDir::Vertical => ContainerInsertion::Vertical(index),
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{ContainerKind, LayoutDescription, Tree};
+
+/// Bumped whenever the encoding changes, so that old links are rejected rather than misread.
+const VERSION: u8 = 1;
+
+/// Deeper layouts are rejected when decoding, so that a hostile link can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// The URL-safe base64 alphabet of RFC 4648.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+impl<Key: AsRef<str>> LayoutDescription<Key> {
+    /// Encode the layout as a short string that is safe to put in a URL, e.g. as a query parameter.
+    ///
+    /// Only the container kinds, the nesting and the pane keys are kept,
+    /// and the sizes of horizontal and vertical children are rounded to 1/255 of the largest sibling.
+    /// This makes it a lot shorter than the JSON of the same layout.
+    ///
+    /// Decode it with [`LayoutDescription::decode_compact`].
+    pub fn encode_compact(&self) -> String {
+        let mut bytes = vec![VERSION];
+        encode_group(&mut bytes, self);
+        base64_encode(&bytes)
+    }
+}
+
+impl LayoutDescription<String> {
+    /// Decode a layout encoded with [`LayoutDescription::encode_compact`].
+    ///
+    /// Returns `None` if the string is malformed, e.g. because a link was truncated.
+    pub fn decode_compact(code: &str) -> Option<Self> {
+        let Some(bytes) = base64_decode(code) else {
+            log::warn!("Compact layout is not valid base64");
+            return None;
+        };
+        let (&version, mut input) = bytes.split_first()?;
+        if version != VERSION {
+            log::warn!("Unsupported compact layout version {version}");
+            return None;
+        }
+        let description = decode_group(&mut input, 0);
+        if description.is_none() || !input.is_empty() {
+            log::warn!("Malformed compact layout");
+            return None;
+        }
+        description
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Encode the layout of this tree as a short, URL-safe string, identifying each pane by a key.
+    ///
+    /// Returns `None` for an empty tree.
+    /// See [`LayoutDescription::encode_compact`] for what is kept.
+    pub fn to_compact_layout(&self, key_of: impl FnMut(&Pane) -> String) -> Option<String> {
+        Some(self.to_layout_description(key_of)?.encode_compact())
+    }
+
+    /// Instantiate a tree from a string made by [`Self::to_compact_layout`],
+    /// creating each pane from its key.
+    ///
+    /// Returns `None` if the string is malformed.
+    pub fn from_compact_layout(
+        id: impl Into<egui::Id>,
+        code: &str,
+        mut create: impl FnMut(&str) -> Pane,
+    ) -> Option<Self> {
+        let description = LayoutDescription::decode_compact(code)?;
+        Some(Self::from_layout_description(id, &description, |key| {
+            create(key)
+        }))
+    }
+}
+
+// Each group is a tag byte: 0 for a pane, followed by its key,
+// or 1 + the container kind, followed by the number of children and the children.
+// Children of horizontal and vertical containers are preceded by their quantized size.
+
+fn kind_tag(kind: ContainerKind) -> u8 {
+    match kind {
+        ContainerKind::Tabs => 1,
+        ContainerKind::Horizontal => 2,
+        ContainerKind::Vertical => 3,
+        ContainerKind::Grid => 4,
+    }
+}
+
+fn has_sizes(kind: ContainerKind) -> bool {
+    matches!(kind, ContainerKind::Horizontal | ContainerKind::Vertical)
+}
+
+fn encode_group<Key: AsRef<str>>(bytes: &mut Vec<u8>, group: &LayoutDescription<Key>) {
+    if let Some(key) = &group.pane {
+        let key = key.as_ref().as_bytes();
+        bytes.push(0);
+        write_len(bytes, key.len());
+        bytes.extend_from_slice(key);
+        return;
+    }
+
+    bytes.push(kind_tag(group.kind));
+    write_len(bytes, group.groups.len());
+    let largest = group
+        .groups
+        .iter()
+        .map(|child| child.size)
+        .filter(|size| size.is_finite())
+        .fold(0.0, f32::max);
+    for child in &group.groups {
+        if has_sizes(group.kind) {
+            let size = if 0.0 < largest && child.size.is_finite() {
+                (255.0 * child.size / largest).round().clamp(1.0, 255.0) as u8
+            } else {
+                255
+            };
+            bytes.push(size);
+        }
+        encode_group(bytes, child);
+    }
+}
+
+fn decode_group(input: &mut &[u8], depth: usize) -> Option<LayoutDescription<String>> {
+    if MAX_DEPTH < depth {
+        return None;
+    }
+
+    match read_byte(input)? {
+        0 => {
+            let len = read_len(input)?;
+            if input.len() < len {
+                return None;
+            }
+            let (key, rest) = input.split_at(len);
+            *input = rest;
+            Some(LayoutDescription::pane(
+                String::from_utf8(key.to_vec()).ok()?,
+            ))
+        }
+        tag @ 1..=4 => {
+            let kind = ContainerKind::ALL[tag as usize - 1];
+            let num_groups = read_len(input)?;
+            let mut groups = vec![];
+            for _ in 0..num_groups {
+                let size = if has_sizes(kind) {
+                    f32::from(read_byte(input)?)
+                } else {
+                    1.0
+                };
+                groups.push(decode_group(input, depth + 1)?.with_size(size));
+            }
+            Some(LayoutDescription::group(kind, groups))
+        }
+        _ => None,
+    }
+}
+
+fn read_byte(input: &mut &[u8]) -> Option<u8> {
+    let (&byte, rest) = input.split_first()?;
+    *input = rest;
+    Some(byte)
+}
+
+/// LEB128: seven bits at a time, least significant first.
+fn write_len(bytes: &mut Vec<u8>, mut len: usize) {
+    while 0x80 <= len {
+        bytes.push((len & 0x7f) as u8 | 0x80);
+        len >>= 7;
+    }
+    bytes.push(len as u8);
+}
+
+fn read_len(input: &mut &[u8]) -> Option<usize> {
+    let mut len = 0;
+    for shift in (0..28).step_by(7) {
+        let byte = read_byte(input)?;
+        len |= usize::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(len);
+        }
+    }
+    None
+}
+
+/// Base64 without padding.
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut triple = [0; 3];
+        triple[..chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes([0, triple[0], triple[1], triple[2]]);
+        for i in 0..=chunk.len() {
+            let index = (n >> (18 - 6 * i)) & 0x3f;
+            out.push(ALPHABET[index as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64_decode(code: &str) -> Option<Vec<u8>> {
+    let code = code.as_bytes();
+    if code.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(code.len() * 3 / 4);
+    for chunk in code.chunks(4) {
+        let mut n = 0;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        let [_, a, b, c] = n.to_be_bytes();
+        bytes.extend_from_slice(&[a, b, c][..chunk.len() - 1]);
+    }
+    Some(bytes)
+}
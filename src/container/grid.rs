@@ -1,12 +1,21 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
 use egui::{emath::Rangef, pos2, vec2, NumExt as _, Rect};
 use itertools::Itertools as _;
 
 use crate::behavior::EditAction;
+use crate::tiles::LayoutContext;
 use crate::{
-    Behavior, ContainerInsertion, DropContext, InsertionPoint, ResizeState, SimplifyAction, TileId,
-    Tiles, Tree,
+    Behavior, ContainerInsertion, DropContext, InsertionPoint, Linear, LinearDir, ResizeState,
+    SimplifyAction, TileId, Tiles, Tree,
 };
 
+use super::grid_areas::GridTemplate;
+
+/// A child placed in a [`GridTemplate`], as `(child, columns, rows)`.
+type Placement = (TileId, Range<usize>, Range<usize>);
+
 /// How to lay out the children of a grid.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -21,6 +30,40 @@ pub enum GridLayout {
     Columns(usize),
 }
 
+/// A row or column to insert or delete with [`Grid::apply_edit`].
+///
+/// Indices count all cells in row-major order, holes included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridEdit {
+    /// Insert an empty column before the given column, shifting the later ones right.
+    InsertColumn(usize),
+
+    /// Delete the given column. Its children move to the end of the grid.
+    DeleteColumn(usize),
+
+    /// Insert an empty row before the given row, shifting the later ones down.
+    InsertRow(usize),
+
+    /// Delete the given row. Its children move to the end of the grid.
+    DeleteRow(usize),
+
+    /// Move a whole column, so that it ends up at index `to`.
+    MoveColumn { from: usize, to: usize },
+
+    /// Move a whole row, so that it ends up at index `to`.
+    MoveRow { from: usize, to: usize },
+}
+
+/// The gap between two columns or rows of a [`Grid`], as shown in its context menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridGutter {
+    /// Between the given column and the next.
+    AfterColumn(usize),
+
+    /// Between the given row and the next.
+    AfterRow(usize),
+}
+
 /// A grid of tiles.
 #[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -29,17 +72,35 @@ pub struct Grid {
     ///
     /// We allow holes (for easier drag-dropping).
     /// We collapse all holes if they become too numerous.
+    #[cfg_attr(feature = "serde", serde(default))]
     children: Vec<Option<TileId>>,
 
     /// Determines the number of columns.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub layout: GridLayout,
 
     /// Share of the available width assigned to each column.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub col_shares: Vec<f32>,
 
     /// Share of the available height assigned to each row.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub row_shares: Vec<f32>,
 
+    /// Named areas, one string per row. See [`Self::set_template_areas`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    template_areas: Vec<String>,
+
+    /// The child shown in each named area. See [`Self::set_area_child`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    area_children: BTreeMap<String, TileId>,
+
     /// ui point x ranges for each column, recomputed during layout
     #[cfg_attr(feature = "serde", serde(skip))]
     col_ranges: Vec<Rangef>,
@@ -56,6 +117,8 @@ impl PartialEq for Grid {
             layout,
             col_shares,
             row_shares,
+            template_areas,
+            area_children,
             col_ranges: _, // ignored because they are recomputed each frame
             row_ranges: _, // ignored because they are recomputed each frame
         } = self;
@@ -64,6 +127,8 @@ impl PartialEq for Grid {
             && children == &other.children
             && col_shares == &other.col_shares
             && row_shares == &other.row_shares
+            && template_areas == &other.template_areas
+            && area_children == &other.area_children
     }
 }
 
@@ -75,6 +140,36 @@ impl Grid {
         }
     }
 
+    /// Swap rows and columns, see [`crate::Tree::transpose`].
+    ///
+    /// With `mirror`, the new columns are in reverse order,
+    /// which turns it into a clockwise rotation.
+    pub(crate) fn transpose(&mut self, mirror: bool) {
+        if !self.template_areas.is_empty() {
+            return; // The areas assume the current rows and columns
+        }
+        let mut col_shares = std::mem::take(&mut self.row_shares);
+        if mirror {
+            col_shares.reverse();
+        }
+        self.row_shares = std::mem::replace(&mut self.col_shares, col_shares);
+
+        // `Auto` picks the number of columns itself, so there is nothing more to do:
+        let GridLayout::Columns(num_cols) = self.layout else {
+            return;
+        };
+        let num_cols = num_cols.at_least(1);
+        let num_rows = self.children.len().div_ceil(num_cols);
+        let mut children = vec![None; num_cols * num_rows];
+        for (i, child) in self.children.drain(..).enumerate() {
+            let (row, col) = (i / num_cols, i % num_cols);
+            let new_col = if mirror { num_rows - 1 - row } else { row };
+            children[col * num_rows + new_col] = child;
+        }
+        self.children = children;
+        self.layout = GridLayout::Columns(num_rows.at_least(1));
+    }
+
     pub fn num_children(&self) -> usize {
         self.children().count()
     }
@@ -105,6 +200,32 @@ impl Grid {
         }
     }
 
+    /// Replace every [`TileId`] this refers to, e.g. when copying a whole subtree.
+    pub(crate) fn map_tile_ids(&mut self, f: impl Fn(TileId) -> TileId) {
+        let Self {
+            children,
+            layout: _,
+            col_shares: _,
+            row_shares: _,
+            template_areas: _,
+            area_children,
+            col_ranges: _,
+            row_ranges: _,
+        } = self;
+
+        for child in children.iter_mut().flatten() {
+            *child = f(*child);
+        }
+        for child in area_children.values_mut() {
+            *child = f(*child);
+        }
+    }
+
+    /// The index of the slot holding the given child, counting holes.
+    pub(crate) fn slot_of(&self, child: TileId) -> Option<usize> {
+        self.children.iter().position(|&slot| slot == Some(child))
+    }
+
     /// Returns the child already at the given index, if any.
     #[must_use]
     pub fn replace_at(&mut self, index: usize, child: TileId) -> Option<TileId> {
@@ -117,24 +238,290 @@ impl Grid {
         }
     }
 
+    /// The [`Linear`] container that lays out the children just like this grid,
+    /// if they fill exactly one row or one column.
+    ///
+    /// Grids with [`GridLayout::Auto`], holes, or template areas never qualify,
+    /// see [`crate::SimplificationOptions::convert_single_line_grids`].
+    pub(crate) fn to_linear(&self) -> Option<Linear> {
+        let GridLayout::Columns(num_cols) = self.layout else {
+            return None;
+        };
+        if self.children.iter().any(Option::is_none) || !self.template_areas.is_empty() {
+            return None;
+        }
+
+        let children: Vec<TileId> = self.children().copied().collect();
+        let (dir, shares) = if children.len() == num_cols.at_least(1) {
+            (LinearDir::Horizontal, &self.col_shares)
+        } else if num_cols <= 1 {
+            (LinearDir::Vertical, &self.row_shares)
+        } else {
+            return None;
+        };
+
+        let mut linear = Linear::new(dir, children);
+        // Shares that were never laid out are missing, and default to 1.0 in both:
+        for (&child, &share) in linear.children.iter().zip(shares) {
+            linear.shares.set_share(child, share);
+        }
+        Some(linear)
+    }
+
+    /// Lay out the grid with named areas, like `grid-template-areas` in CSS.
+    ///
+    /// Each string is one row of space-separated area names, e.g.
+    /// `["header header", "sidebar main"]` for a header spanning two columns above a sidebar
+    /// and a main area. A `.` is a cell outside any area.
+    /// Every row must have the same number of cells, and each area must be a rectangle.
+    ///
+    /// Put children in the areas with [`Self::set_area_child`].
+    /// The other children fill the `.` cells in order, and then rows added below the template.
+    /// While there is a template, it decides the number of columns instead of the [`GridLayout`].
+    ///
+    /// Returns `false` (and changes nothing) if the template is invalid.
+    /// An empty template removes all areas.
+    pub fn set_template_areas(&mut self, rows: &[&str]) -> bool {
+        if rows.is_empty() {
+            self.template_areas.clear();
+            self.area_children.clear();
+            return true;
+        }
+        let Some(template) = GridTemplate::parse(rows) else {
+            log::warn!("Invalid grid template areas: {rows:?}");
+            return false;
+        };
+        self.template_areas = rows.iter().map(|&row| row.to_owned()).collect();
+        self.area_children
+            .retain(|name, _| template.area(name).is_some());
+        true
+    }
+
+    /// See [`Self::set_template_areas`].
+    pub fn template_areas(&self) -> &[String] {
+        &self.template_areas
+    }
+
+    /// Show the given child in the named area of [`Self::set_template_areas`],
+    /// adding it to the grid if needed.
+    ///
+    /// Any child already in that area goes back to filling the free cells.
+    /// Returns `false` (and changes nothing) if there is no such area.
+    pub fn set_area_child(&mut self, area: &str, child: TileId) -> bool {
+        let has_area = self
+            .template()
+            .is_some_and(|template| template.area(area).is_some());
+        if !has_area {
+            log::warn!("Grid has no area called {area:?}");
+            return false;
+        }
+        if self.slot_of(child).is_none() {
+            self.add_child(child);
+        }
+        self.area_children.retain(|_, &mut other| other != child);
+        self.area_children.insert(area.to_owned(), child);
+        true
+    }
+
+    /// The child in the named area, if any.
+    pub fn area_child(&self, area: &str) -> Option<TileId> {
+        self.area_children.get(area).copied()
+    }
+
+    /// The name of the area the given child is in, if any.
+    pub fn area_of(&self, child: TileId) -> Option<&str> {
+        self.area_children
+            .iter()
+            .find(|&(_, &other)| other == child)
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn template(&self) -> Option<GridTemplate> {
+        if self.template_areas.is_empty() {
+            None
+        } else {
+            GridTemplate::parse(&self.template_areas)
+        }
+    }
+
+    /// Where each visible child goes in the template,
+    /// and how many rows that takes (more than the template has if the free cells run out).
+    fn place_in_template(
+        &self,
+        template: &GridTemplate,
+        is_visible: impl Fn(TileId) -> bool,
+    ) -> (Vec<Placement>, usize) {
+        let mut placed = Vec::new();
+        let mut free_cells = template.free_cells();
+        let mut num_rows = template.num_rows;
+        let mut num_overflowing = 0;
+        for &child in self.children() {
+            if !is_visible(child) {
+                continue;
+            }
+            if let Some(area) = self.area_of(child).and_then(|name| template.area(name)) {
+                placed.push((child, area.cols.clone(), area.rows.clone()));
+            } else if let Some((col, row)) = free_cells.next() {
+                placed.push((child, col..col + 1, row..row + 1));
+            } else {
+                let col = num_overflowing % template.num_cols;
+                let row = template.num_rows + num_overflowing / template.num_cols;
+                num_overflowing += 1;
+                num_rows = row + 1;
+                placed.push((child, col..col + 1, row..row + 1));
+            }
+        }
+        (placed, num_rows)
+    }
+
+    /// The rect of the given cells, as of the last layout.
+    fn cells_rect(&self, cols: &Range<usize>, rows: &Range<usize>) -> Rect {
+        let x_range = Rangef::new(
+            self.col_ranges[cols.start].min,
+            self.col_ranges[cols.end - 1].max,
+        );
+        let y_range = Rangef::new(
+            self.row_ranges[rows.start].min,
+            self.row_ranges[rows.end - 1].max,
+        );
+        Rect::from_x_y_ranges(x_range, y_range)
+    }
+
+    /// The number of columns and rows the visible children take up,
+    /// as of the last layout for [`GridLayout::Auto`].
+    pub(crate) fn dimensions(&self, is_visible: impl Fn(TileId) -> bool) -> (usize, usize) {
+        if let Some(template) = self.template() {
+            let (_, num_rows) = self.place_in_template(&template, is_visible);
+            return (template.num_cols, num_rows);
+        }
+        let num_cells = self.visible_children_and_holes(is_visible).len();
+        let num_cols = self.num_columns();
+        (num_cols, num_cells.div_ceil(num_cols))
+    }
+
+    /// The number of columns, as of the last layout for [`GridLayout::Auto`].
+    fn num_columns(&self) -> usize {
+        match self.layout {
+            GridLayout::Auto => self.col_ranges.len(),
+            GridLayout::Columns(num_cols) => num_cols,
+        }
+        .at_least(1)
+    }
+
+    /// Insert, delete or move a row or column, shifting the other children accordingly.
+    ///
+    /// This fixes the number of columns with [`GridLayout::Columns`],
+    /// since [`GridLayout::Auto`] would just reflow the children.
+    ///
+    /// Returns `false` (and changes nothing) if the index is out of range,
+    /// the last column would be deleted, or the grid uses [`Self::set_template_areas`].
+    pub fn apply_edit(&mut self, edit: GridEdit) -> bool {
+        if !self.template_areas.is_empty() {
+            return false;
+        }
+        let num_cols = self.num_columns();
+        let num_rows = self.children.len().div_ceil(num_cols);
+        let valid = match edit {
+            GridEdit::InsertColumn(col) => col <= num_cols,
+            GridEdit::DeleteColumn(col) => col < num_cols && 1 < num_cols,
+            GridEdit::InsertRow(row) => row <= num_rows,
+            GridEdit::DeleteRow(row) => row < num_rows,
+            GridEdit::MoveColumn { from, to } => from != to && from.max(to) < num_cols,
+            GridEdit::MoveRow { from, to } => from != to && from.max(to) < num_rows,
+        };
+        if !valid {
+            return false;
+        }
+
+        // Make every row whole, so we can index cells by column and row:
+        self.children.resize(num_rows * num_cols, None);
+
+        let mut removed = vec![];
+        let num_cols = match edit {
+            GridEdit::InsertColumn(col) => {
+                for row in (0..num_rows).rev() {
+                    self.children.insert(row * num_cols + col, None);
+                }
+                insert_share(&mut self.col_shares, col);
+                num_cols + 1
+            }
+            GridEdit::DeleteColumn(col) => {
+                for row in (0..num_rows).rev() {
+                    removed.push(self.children.remove(row * num_cols + col));
+                }
+                removed.reverse();
+                remove_share(&mut self.col_shares, col);
+                num_cols - 1
+            }
+            GridEdit::InsertRow(row) => {
+                let index = row * num_cols;
+                self.children
+                    .splice(index..index, std::iter::repeat(None).take(num_cols));
+                insert_share(&mut self.row_shares, row);
+                num_cols
+            }
+            GridEdit::DeleteRow(row) => {
+                removed.extend(self.children.drain(row * num_cols..(row + 1) * num_cols));
+                remove_share(&mut self.row_shares, row);
+                num_cols
+            }
+            GridEdit::MoveColumn { from, to } => {
+                for row in self.children.chunks_mut(num_cols) {
+                    move_item(row, from, to, 1);
+                }
+                if from.max(to) < self.col_shares.len() {
+                    move_item(&mut self.col_shares, from, to, 1);
+                }
+                num_cols
+            }
+            GridEdit::MoveRow { from, to } => {
+                move_item(&mut self.children, from, to, num_cols);
+                if from.max(to) < self.row_shares.len() {
+                    move_item(&mut self.row_shares, from, to, 1);
+                }
+                num_cols
+            }
+        };
+        self.children
+            .extend(removed.into_iter().flatten().map(Some));
+        self.layout = GridLayout::Columns(num_cols);
+        true
+    }
+
     fn collapse_holes(&mut self) {
         log::trace!("Collaping grid holes");
         self.children.retain(|child| child.is_some());
     }
 
-    fn visible_children_and_holes<Pane>(&self, tiles: &Tiles<Pane>) -> Vec<Option<TileId>> {
+    fn visible_children_and_holes(
+        &self,
+        is_visible: impl Fn(TileId) -> bool,
+    ) -> Vec<Option<TileId>> {
         self.children
             .iter()
-            .filter(|id| id.map_or(true, |id| tiles.is_visible(id)))
+            .filter(|id| id.map_or(true, &is_visible))
             .copied()
             .collect()
     }
 
+    pub(super) fn hash_layout(&self, state: &mut impl std::hash::Hasher) {
+        use std::hash::Hash as _;
+
+        self.children.hash(state);
+        self.layout.hash(state);
+        self.template_areas.hash(state);
+        self.area_children.hash(state);
+        self.col_shares.len().hash(state);
+        for share in self.col_shares.iter().chain(&self.row_shares) {
+            share.to_bits().hash(state);
+        }
+    }
+
     pub(super) fn layout<Pane>(
         &mut self,
-        tiles: &mut Tiles<Pane>,
+        ctx: &mut LayoutContext<'_>,
         style: &egui::Style,
-        behavior: &mut dyn Behavior<Pane>,
+        behavior: &dyn Behavior<Pane>,
         rect: Rect,
     ) {
         // clean up any empty holes at the end
@@ -142,9 +529,15 @@ impl Grid {
             self.children.pop();
         }
 
-        let gap = behavior.gap_width(style);
+        let gap = behavior.gap_width_at_depth(style, ctx.depth());
 
-        let visible_children_and_holes = self.visible_children_and_holes(tiles);
+        if let Some(template) = self.template() {
+            self.layout_template(ctx, style, behavior, rect, gap, &template);
+            return;
+        }
+
+        let visible_children_and_holes =
+            self.visible_children_and_holes(|tile_id| ctx.is_visible(tile_id));
 
         // Calculate grid dimensions:
         let (num_cols, num_rows) = {
@@ -152,7 +545,7 @@ impl Grid {
 
             let num_cols = match self.layout {
                 GridLayout::Auto => {
-                    behavior.grid_auto_column_count(num_visible_children, rect, gap)
+                    ctx.grid_auto_column_count(behavior, num_visible_children, rect, gap)
                 }
                 GridLayout::Columns(num_columns) => num_columns,
             };
@@ -166,7 +559,69 @@ impl Grid {
             "Bug in egui_tiles::Grid::layout"
         );
 
-        // Figure out where each column and row goes:
+        self.update_ranges(ctx, rect, gap, num_cols, num_rows);
+
+        // Layout each child, below its title bar (if any):
+        let title_bar_height = behavior.title_bar_height(style);
+        for (i, &child) in visible_children_and_holes.iter().enumerate() {
+            if let Some(child) = child {
+                let col = i % num_cols;
+                let row = i / num_cols;
+                let mut child_rect =
+                    Rect::from_x_y_ranges(self.col_ranges[col], self.row_ranges[row]);
+                child_rect.min.y = (child_rect.min.y + title_bar_height).at_most(child_rect.max.y);
+                // Grids can have thousands of children, so skip the ones that are scrolled away:
+                ctx.layout_child_in_viewport(child, child_rect);
+            }
+        }
+
+        // Check if we should collapse some holes.
+        // A fixed number of columns is left alone, since the holes may come from `apply_edit`.
+        if self.layout == GridLayout::Auto {
+            let num_holes = visible_children_and_holes
+                .iter()
+                .filter(|c| c.is_none())
+                .count()
+                + (num_cols * num_rows - visible_children_and_holes.len());
+
+            if num_cols.min(num_rows) <= num_holes {
+                // More holes than there are columns or rows - let's collapse all holes
+                // so that we can shrink for next frame:
+                self.collapse_holes();
+            }
+        }
+    }
+
+    fn layout_template<Pane>(
+        &mut self,
+        ctx: &mut LayoutContext<'_>,
+        style: &egui::Style,
+        behavior: &dyn Behavior<Pane>,
+        rect: Rect,
+        gap: f32,
+        template: &GridTemplate,
+    ) {
+        let (placed, num_rows) =
+            self.place_in_template(template, |tile_id| ctx.is_visible(tile_id));
+        self.update_ranges(ctx, rect, gap, template.num_cols, num_rows);
+
+        let title_bar_height = behavior.title_bar_height(style);
+        for (child, cols, rows) in placed {
+            let mut child_rect = self.cells_rect(&cols, &rows);
+            child_rect.min.y = (child_rect.min.y + title_bar_height).at_most(child_rect.max.y);
+            ctx.layout_child_in_viewport(child, child_rect);
+        }
+    }
+
+    /// Figure out where each column and row goes.
+    fn update_ranges(
+        &mut self,
+        ctx: &LayoutContext<'_>,
+        rect: Rect,
+        gap: f32,
+        num_cols: usize,
+        num_rows: usize,
+    ) {
         self.col_shares.resize(num_cols, 1.0);
         self.row_shares.resize(num_rows, 1.0);
 
@@ -188,7 +643,8 @@ impl Grid {
             let mut x = rect.left();
             self.col_ranges.clear();
             for &width in &col_widths {
-                self.col_ranges.push(Rangef::new(x, x + width));
+                let (left, right) = (ctx.round_to_pixels(x), ctx.round_to_pixels(x + width));
+                self.col_ranges.push(Rangef::new(left, right));
                 x += width + gap;
             }
         }
@@ -196,7 +652,8 @@ impl Grid {
             let mut y = rect.top();
             self.row_ranges.clear();
             for &height in &row_heights {
-                self.row_ranges.push(Rangef::new(y, y + height));
+                let (top, bottom) = (ctx.round_to_pixels(y), ctx.round_to_pixels(y + height));
+                self.row_ranges.push(Rangef::new(top, bottom));
                 y += height + gap;
             }
         }
@@ -211,150 +668,568 @@ impl Grid {
             num_rows,
             "Bug in egui_tiles::Grid::layout"
         );
+    }
 
-        // Layout each child:
-        for (i, &child) in visible_children_and_holes.iter().enumerate() {
+    pub(super) fn ui<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        drop_context: &mut DropContext,
+        ui: &mut egui::Ui,
+        tile_id: TileId,
+    ) {
+        // Only cells that intersect this will be shown:
+        let viewport = if behavior.cull_off_screen_tiles() {
+            ui.clip_rect()
+        } else {
+            Rect::EVERYTHING
+        };
+
+        for &child in &self.children {
             if let Some(child) = child {
-                let col = i % num_cols;
-                let row = i / num_cols;
-                let child_rect = Rect::from_x_y_ranges(self.col_ranges[col], self.row_ranges[row]);
-                tiles.layout_tile(style, behavior, child_rect, child);
+                let in_view = tree
+                    .tiles
+                    .rect(child)
+                    .is_some_and(|rect| viewport.intersects(rect));
+                if in_view && tree.is_visible(child) {
+                    super::title_bar_ui(tree, behavior, ui, child);
+                    tree.tile_ui(behavior, drop_context, ui, child);
+                    crate::cover_tile_if_dragged(tree, behavior, ui, child);
+                }
             }
         }
 
-        // Check if we should collapse some holes:
+        let template = self.template();
+        let has_children = self.num_children() > 0;
+        if template.is_none() && has_children && tree.edit_permissions(behavior, tile_id).drop_into
         {
-            let num_holes = visible_children_and_holes
-                .iter()
-                .filter(|c| c.is_none())
-                .count()
-                + (num_cols * num_rows - visible_children_and_holes.len());
+            self.empty_cells_ui(tree, behavior, ui, viewport);
+        }
 
-            if num_cols.min(num_rows) <= num_holes {
-                // More holes than there are columns or rows - let's collapse all holes
-                // so that we can shrink for next frame:
-                self.collapse_holes();
+        // Register drop-zones:
+        if let (true, Some(template)) = (drop_context.enabled, &template) {
+            self.template_drop_zones(tree, drop_context, template, viewport, tile_id);
+        } else if drop_context.enabled {
+            for i in 0..(self.col_ranges.len() * self.row_ranges.len()) {
+                let col = i % self.col_ranges.len();
+                let row = i / self.col_ranges.len();
+                let child_rect = Rect::from_x_y_ranges(self.col_ranges[col], self.row_ranges[row]);
+                if !viewport.intersects(child_rect) {
+                    continue;
+                }
+                drop_context.suggest_rect(
+                    InsertionPoint::new(tile_id, ContainerInsertion::Grid(i)),
+                    child_rect,
+                );
             }
         }
+
+        let resizable = tree.edit_permissions(behavior, tile_id).resize;
+        let template = template.as_ref();
+        self.resize_columns(&mut tree.tiles, behavior, ui, tile_id, resizable, template);
+        self.resize_rows(&mut tree.tiles, behavior, ui, tile_id, resizable, template);
+        if resizable {
+            self.resize_corners(&mut tree.tiles, behavior, ui, tile_id, template);
+        }
+        if resizable && template.is_none() && behavior.grid_reorder_handles(&tree.tiles, tile_id) {
+            self.reorder_handles_ui(&mut tree.tiles, behavior, ui, tile_id);
+        }
     }
 
-    pub(super) fn ui<Pane>(
+    /// Offer to add a pane to each empty cell, see [`Behavior::pane_factories`].
+    fn empty_cells_ui<Pane>(
         &mut self,
         tree: &mut Tree<Pane>,
         behavior: &mut dyn Behavior<Pane>,
+        ui: &mut egui::Ui,
+        viewport: Rect,
+    ) {
+        let num_cols = self.col_ranges.len();
+        let cells = self.visible_children_and_holes(|child| tree.is_visible(child));
+        for i in 0..(num_cols * self.row_ranges.len()) {
+            if cells.get(i).copied().flatten().is_some() {
+                continue;
+            }
+            let cell_rect =
+                Rect::from_x_y_ranges(self.col_ranges[i % num_cols], self.row_ranges[i / num_cols]);
+            if !viewport.intersects(cell_rect) {
+                continue;
+            }
+            let added =
+                crate::pane_factory::add_pane_placeholder(&mut tree.tiles, behavior, ui, cell_rect);
+            if let Some(pane) = added {
+                self.put_in_cell(i, pane, |child| tree.is_visible(child));
+            }
+        }
+    }
+
+    /// Put a child in the given empty cell of the layout.
+    ///
+    /// Cells are counted like [`Self::visible_children_and_holes`],
+    /// so this skips over the slots of invisible children.
+    fn put_in_cell(&mut self, cell: usize, child: TileId, is_visible: impl Fn(TileId) -> bool) {
+        let mut cells = self
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| id.map_or(true, &is_visible))
+            .map(|(slot, _)| slot);
+        if let Some(slot) = cells.nth(cell) {
+            self.children[slot] = Some(child);
+        } else {
+            let num_cells = self.visible_children_and_holes(&is_visible).len();
+            let num_holes = cell.saturating_sub(num_cells);
+            self.children
+                .extend(std::iter::repeat(None).take(num_holes));
+            self.children.push(Some(child));
+        }
+    }
+
+    /// Dropping on a child outside the areas puts the dropped tile before it,
+    /// and dropping on a free cell puts it last.
+    fn template_drop_zones<Pane>(
+        &self,
+        tree: &Tree<Pane>,
         drop_context: &mut DropContext,
-        ui: &egui::Ui,
+        template: &GridTemplate,
+        viewport: Rect,
         tile_id: TileId,
     ) {
-        for &child in &self.children {
-            if let Some(child) = child {
-                if tree.is_visible(child) {
-                    tree.tile_ui(behavior, drop_context, ui, child);
-                    crate::cover_tile_if_dragged(tree, behavior, ui, child);
+        let (placed, _) = self.place_in_template(template, |child| tree.is_visible(child));
+        let mut free_cells: Vec<(usize, usize)> = template.free_cells().collect();
+        for (child, cols, rows) in placed {
+            if self.area_of(child).is_some() {
+                continue;
+            }
+            free_cells.retain(|&(col, row)| !cols.contains(&col) || !rows.contains(&row));
+            if let Some(index) = self.slot_of(child) {
+                let rect = self.cells_rect(&cols, &rows);
+                if viewport.intersects(rect) {
+                    drop_context.suggest_rect(
+                        InsertionPoint::new(tile_id, ContainerInsertion::Grid(index)),
+                        rect,
+                    );
                 }
             }
         }
+        for (col, row) in free_cells {
+            let rect = self.cells_rect(&(col..col + 1), &(row..row + 1));
+            if viewport.intersects(rect) {
+                drop_context.suggest_rect(
+                    InsertionPoint::new(tile_id, ContainerInsertion::Grid(self.children.len())),
+                    rect,
+                );
+            }
+        }
+    }
 
-        // Register drop-zones:
-        for i in 0..(self.col_ranges.len() * self.row_ranges.len()) {
-            let col = i % self.col_ranges.len();
-            let row = i / self.col_ranges.len();
-            let child_rect = Rect::from_x_y_ranges(self.col_ranges[col], self.row_ranges[row]);
-            drop_context.suggest_rect(
-                InsertionPoint::new(tile_id, ContainerInsertion::Grid(i)),
-                child_rect,
-            );
+    /// Right-clicking a gutter offers to insert or delete the rows or columns next to it.
+    fn gutter_context_menu<Pane>(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        response: &egui::Response,
+        grid_id: TileId,
+        gutter: GridGutter,
+    ) -> bool {
+        if !self.template_areas.is_empty() {
+            return false;
+        }
+        let entries = match gutter {
+            GridGutter::AfterColumn(i) => [
+                ("Insert column", GridEdit::InsertColumn(i + 1)),
+                ("Delete column to the left", GridEdit::DeleteColumn(i)),
+                ("Delete column to the right", GridEdit::DeleteColumn(i + 1)),
+            ],
+            GridGutter::AfterRow(i) => [
+                ("Insert row", GridEdit::InsertRow(i + 1)),
+                ("Delete row above", GridEdit::DeleteRow(i)),
+                ("Delete row below", GridEdit::DeleteRow(i + 1)),
+            ],
+        };
+
+        let mut chosen = None;
+        response.context_menu(|ui| {
+            for (label, edit) in entries {
+                if behavior.is_grid_edit_allowed(tiles, grid_id, edit) && ui.button(label).clicked()
+                {
+                    chosen = Some(edit);
+                    ui.close_menu();
+                }
+            }
+            behavior.grid_gutter_context_menu(tiles, ui, grid_id, gutter);
+        });
+
+        let edited = chosen.is_some_and(|edit| self.apply_edit(edit));
+        if edited {
+            tiles.on_edit(behavior, EditAction::GridEdited);
         }
+        edited
+    }
 
-        self.resize_columns(&tree.tiles, behavior, ui, tile_id);
-        self.resize_rows(&tree.tiles, behavior, ui, tile_id);
+    /// The parts of the line after column (or row) `i` that don't cross an area,
+    /// along the full `range` of the grid.
+    fn divider_segments(
+        &self,
+        template: Option<&GridTemplate>,
+        i: usize,
+        is_column: bool,
+        range: Rangef,
+    ) -> Vec<Rangef> {
+        let Some(template) = template else {
+            return vec![range];
+        };
+        let cross_ranges = if is_column {
+            &self.row_ranges
+        } else {
+            &self.col_ranges
+        };
+
+        let mut segments: Vec<Rangef> = Vec::new();
+        for (j, cross) in cross_ranges.iter().enumerate() {
+            let crosses_area = if is_column {
+                template
+                    .area_at(i, j)
+                    .is_some_and(|area| area.cols.contains(&(i + 1)))
+            } else {
+                template
+                    .area_at(j, i)
+                    .is_some_and(|area| area.rows.contains(&(i + 1)))
+            };
+            if crosses_area {
+                continue;
+            }
+            // Reach halfway into the gaps, so that the segments of neighboring cells meet:
+            let min = match j.checked_sub(1) {
+                Some(prev) => egui::lerp(cross_ranges[prev].max..=cross.min, 0.5),
+                None => range.min,
+            };
+            let max = match cross_ranges.get(j + 1) {
+                Some(next) => egui::lerp(cross.max..=next.min, 0.5),
+                None => range.max,
+            };
+            match segments.last_mut() {
+                Some(last) if last.max == min => last.max = max,
+                _ => segments.push(Rangef::new(min, max)),
+            }
+        }
+        segments
     }
 
     fn resize_columns<Pane>(
         &mut self,
-        tiles: &Tiles<Pane>,
+        tiles: &mut Tiles<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         ui: &egui::Ui,
         parent_id: TileId,
+        resizable: bool,
+        template: Option<&GridTemplate>,
     ) {
         let parent_rect = tiles.rect_or_die(parent_id);
-        for (i, (left, right)) in self.col_ranges.iter().copied().tuple_windows().enumerate() {
+        let depth = tiles.ancestors(parent_id).count();
+        let col_ranges = self.col_ranges.clone();
+        for (i, (left, right)) in col_ranges.iter().copied().tuple_windows().enumerate() {
             let resize_id = ui.id().with((parent_id, "resize_col", i));
 
             let x = egui::lerp(left.max..=right.min, 0.5);
 
             let mut resize_state = ResizeState::Idle;
-            let line_rect = Rect::from_center_size(
-                pos2(x, parent_rect.center().y),
-                vec2(
-                    2.0 * ui.style().interaction.resize_grab_radius_side,
-                    parent_rect.height(),
-                ),
-            );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
+            if resizable {
+                let line_rect = Rect::from_center_size(
+                    pos2(x, parent_rect.center().y),
+                    vec2(
+                        2.0 * ui.style().interaction.resize_grab_radius_side,
+                        parent_rect.height(),
+                    ),
+                );
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                if self.gutter_context_menu(
+                    tiles,
                     behavior,
-                    &self.col_ranges,
-                    &mut self.col_shares,
                     &response,
-                    ui.painter().round_to_pixel(pointer.x) - x,
-                    i,
-                );
-
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+                    parent_id,
+                    GridGutter::AfterColumn(i),
+                ) {
+                    return; // The columns are out of date until the next layout
+                }
+                if let Some(steps) = super::scroll_resize_steps(behavior, ui, line_rect) {
+                    let shares = [self.col_shares[i], self.col_shares[i + 1]];
+                    [self.col_shares[i], self.col_shares[i + 1]] =
+                        super::shift_shares(shares, steps);
+                    tiles.on_edit(behavior, EditAction::TileResized);
+                    tiles.on_resized(parent_id);
+                }
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                    resize_state = resize_interaction(
+                        behavior,
+                        tiles,
+                        &self.col_ranges,
+                        &mut self.col_shares,
+                        &response,
+                        ui.painter().round_to_pixel(pointer.x) - x,
+                        i,
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+                    }
+                }
+                if response.drag_stopped() || response.double_clicked() {
+                    tiles.on_resized(parent_id);
                 }
             }
 
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
-            ui.painter().vline(x, parent_rect.y_range(), stroke);
+            let stroke = super::resize_stroke(behavior, ui.style(), resize_state, depth);
+            for segment in self.divider_segments(template, i, true, parent_rect.y_range()) {
+                ui.painter().vline(x, segment, stroke);
+            }
         }
     }
 
     fn resize_rows<Pane>(
         &mut self,
-        tiles: &Tiles<Pane>,
+        tiles: &mut Tiles<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         ui: &egui::Ui,
         parent_id: TileId,
+        resizable: bool,
+        template: Option<&GridTemplate>,
     ) {
         let parent_rect = tiles.rect_or_die(parent_id);
-        for (i, (top, bottom)) in self.row_ranges.iter().copied().tuple_windows().enumerate() {
+        let depth = tiles.ancestors(parent_id).count();
+        let row_ranges = self.row_ranges.clone();
+        for (i, (top, bottom)) in row_ranges.iter().copied().tuple_windows().enumerate() {
             let resize_id = ui.id().with((parent_id, "resize_row", i));
 
             let y = egui::lerp(top.max..=bottom.min, 0.5);
 
             let mut resize_state = ResizeState::Idle;
-            let line_rect = Rect::from_center_size(
-                pos2(parent_rect.center().x, y),
-                vec2(
-                    parent_rect.width(),
-                    2.0 * ui.style().interaction.resize_grab_radius_side,
-                ),
-            );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
+            if resizable {
+                let line_rect = Rect::from_center_size(
+                    pos2(parent_rect.center().x, y),
+                    vec2(
+                        parent_rect.width(),
+                        2.0 * ui.style().interaction.resize_grab_radius_side,
+                    ),
+                );
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                if self.gutter_context_menu(
+                    tiles,
+                    behavior,
+                    &response,
+                    parent_id,
+                    GridGutter::AfterRow(i),
+                ) {
+                    return; // The rows are out of date until the next layout
+                }
+                if let Some(steps) = super::scroll_resize_steps(behavior, ui, line_rect) {
+                    let shares = [self.row_shares[i], self.row_shares[i + 1]];
+                    [self.row_shares[i], self.row_shares[i + 1]] =
+                        super::shift_shares(shares, steps);
+                    tiles.on_edit(behavior, EditAction::TileResized);
+                    tiles.on_resized(parent_id);
+                }
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                    resize_state = resize_interaction(
+                        behavior,
+                        tiles,
+                        &self.row_ranges,
+                        &mut self.row_shares,
+                        &response,
+                        ui.painter().round_to_pixel(pointer.y) - y,
+                        i,
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+                    }
+                }
+                if response.drag_stopped() || response.double_clicked() {
+                    tiles.on_resized(parent_id);
+                }
+            }
+
+            let stroke = super::resize_stroke(behavior, ui.style(), resize_state, depth);
+            for segment in self.divider_segments(template, i, false, parent_rect.x_range()) {
+                ui.painter().hline(segment, y, stroke);
+            }
+        }
+    }
+
+    /// Handles on the top edge of each column and the left edge of each row,
+    /// for dragging them to another place. See [`Behavior::grid_reorder_handles`].
+    fn reorder_handles_ui<Pane>(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &egui::Ui,
+        parent_id: TileId,
+    ) {
+        const HANDLE_SIZE: egui::Vec2 = vec2(24.0, 6.0);
+
+        let parent_rect = tiles.rect_or_die(parent_id);
+        let grid_hovered = ui.rect_contains_pointer(parent_rect);
+        let mut chosen = None;
+        for (is_column, ranges) in [(true, &self.col_ranges), (false, &self.row_ranges)] {
+            let band = |range: Rangef| {
+                if is_column {
+                    Rect::from_x_y_ranges(range, parent_rect.y_range())
+                } else {
+                    Rect::from_x_y_ranges(parent_rect.x_range(), range)
+                }
+            };
+            for (i, &range) in ranges.iter().enumerate() {
+                let handle_rect = if is_column {
+                    let center = pos2(range.center(), parent_rect.top() + 0.5 * HANDLE_SIZE.y);
+                    Rect::from_center_size(center, HANDLE_SIZE)
+                } else {
+                    let center = pos2(parent_rect.left() + 0.5 * HANDLE_SIZE.y, range.center());
+                    Rect::from_center_size(center, vec2(HANDLE_SIZE.y, HANDLE_SIZE.x))
+                };
+                let id = ui.id().with((parent_id, "reorder", is_column, i));
+                let response = ui.interact(handle_rect, id, egui::Sense::drag());
+
+                if response.dragged() || response.drag_stopped() {
+                    let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+                        continue;
+                    };
+                    let pointer = if is_column { pointer.x } else { pointer.y };
+                    let target = ranges
+                        .iter()
+                        .position(|range| pointer <= range.max)
+                        .unwrap_or(ranges.len() - 1);
+
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+                    let painter = ui.painter();
+                    painter.rect_filled(
+                        band(range),
+                        0.0,
+                        behavior.drag_preview_color(ui.visuals()),
+                    );
+                    painter.rect_stroke(
+                        band(ranges[target]),
+                        0.0,
+                        behavior.drag_preview_stroke(ui.visuals()),
+                    );
+
+                    if response.drag_stopped() {
+                        chosen = Some(if is_column {
+                            GridEdit::MoveColumn {
+                                from: i,
+                                to: target,
+                            }
+                        } else {
+                            GridEdit::MoveRow {
+                                from: i,
+                                to: target,
+                            }
+                        });
+                    }
+                } else if grid_hovered {
+                    let visuals = ui.style().interact(&response);
+                    ui.painter()
+                        .rect_filled(handle_rect, HANDLE_SIZE.y / 2.0, visuals.bg_fill);
+                    if response.hovered() {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+                    }
+                }
+            }
+        }
+
+        if let Some(edit) = chosen {
+            if behavior.is_grid_edit_allowed(tiles, parent_id, edit) && self.apply_edit(edit) {
+                tiles.on_edit(behavior, EditAction::GridEdited);
+            }
+        }
+    }
+
+    /// Where a column gutter crosses a row gutter, both can be dragged at once.
+    ///
+    /// Interacted with after the gutters themselves, so the corners are on top of them.
+    fn resize_corners<Pane>(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &egui::Ui,
+        parent_id: TileId,
+        template: Option<&GridTemplate>,
+    ) {
+        let parent_rect = tiles.rect_or_die(parent_id);
+        let depth = tiles.ancestors(parent_id).count();
+        // No thicker than the gutters, or egui would give the drag to the (thinner) gutters:
+        let grab_size = egui::Vec2::splat(2.0 * ui.style().interaction.resize_grab_radius_side);
+        let gutter_centers = |ranges: &[Rangef]| -> Vec<f32> {
+            ranges
+                .iter()
+                .tuple_windows()
+                .map(|(before, after)| egui::lerp(before.max..=after.min, 0.5))
+                .collect()
+        };
+        let xs = gutter_centers(&self.col_ranges);
+        let ys = gutter_centers(&self.row_ranges);
+
+        for (i, &x) in xs.iter().enumerate() {
+            let col_segments = self.divider_segments(template, i, true, parent_rect.y_range());
+            for (j, &y) in ys.iter().enumerate() {
+                let row_segments = self.divider_segments(template, j, false, parent_rect.x_range());
+                let on_col = col_segments.iter().any(|segment| segment.contains(y));
+                let on_row = row_segments.iter().any(|segment| segment.contains(x));
+                if !on_col || !on_row {
+                    continue; // One of the gutters stops at an area
+                }
+
+                let corner_rect = Rect::from_center_size(pos2(x, y), grab_size);
+                let resize_id = ui.id().with((parent_id, "resize_corner", i, j));
+                let response = ui.interact(corner_rect, resize_id, egui::Sense::click_and_drag());
+                let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+                    continue;
+                };
+
+                let col_state = resize_interaction(
+                    behavior,
+                    tiles,
+                    &self.col_ranges,
+                    &mut self.col_shares,
+                    &response,
+                    ui.painter().round_to_pixel(pointer.x) - x,
+                    i,
+                );
+                let row_state = resize_interaction(
                     behavior,
+                    tiles,
                     &self.row_ranges,
                     &mut self.row_shares,
                     &response,
                     ui.painter().round_to_pixel(pointer.y) - y,
-                    i,
+                    j,
                 );
+                let resize_state = if col_state == ResizeState::Idle {
+                    row_state
+                } else {
+                    col_state
+                };
+                if response.drag_stopped() || response.double_clicked() {
+                    tiles.on_resized(parent_id);
+                }
+                if resize_state == ResizeState::Idle {
+                    continue;
+                }
 
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+                ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeNwSe);
+
+                // Highlight both gutters:
+                let stroke = super::resize_stroke(behavior, ui.style(), resize_state, depth);
+                for segment in col_segments.iter().copied() {
+                    ui.painter().vline(x, segment, stroke);
+                }
+                for segment in row_segments {
+                    ui.painter().hline(segment, y, stroke);
                 }
             }
-
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
-            ui.painter().hline(parent_rect.x_range(), y, stroke);
         }
     }
 
@@ -368,10 +1243,24 @@ impl Grid {
                     SimplifyAction::Keep => {}
                     SimplifyAction::Replace(new) => {
                         *child_opt = Some(new);
+                        // The replacement takes over the area:
+                        for area_child in self.area_children.values_mut() {
+                            if *area_child == child {
+                                *area_child = new;
+                            }
+                        }
                     }
                 }
             }
         }
+        self.retain_area_children();
+    }
+
+    /// Forget the areas of children that were removed.
+    fn retain_area_children(&mut self) {
+        let children = &self.children;
+        self.area_children
+            .retain(|_, child| children.contains(&Some(*child)));
     }
 
     pub(super) fn retain(&mut self, mut retain: impl FnMut(TileId) -> bool) {
@@ -382,6 +1271,7 @@ impl Grid {
                 }
             }
         }
+        self.retain_area_children();
     }
 
     /// Returns child index, if found.
@@ -391,12 +1281,14 @@ impl Grid {
             .iter()
             .position(|&child| child == Some(needle))?;
         self.children[index] = None;
+        self.retain_area_children();
         Some(index)
     }
 }
 
 fn resize_interaction<Pane>(
     behavior: &mut dyn Behavior<Pane>,
+    tiles: &mut Tiles<Pane>,
     ranges: &[Rangef],
     shares: &mut [f32],
     splitter_response: &egui::Response,
@@ -411,7 +1303,7 @@ fn resize_interaction<Pane>(
     let right = i + 1;
 
     if splitter_response.double_clicked() {
-        behavior.on_edit(EditAction::TileResized);
+        tiles.on_edit(behavior, EditAction::TileResized);
 
         // double-click to center the split between left and right:
         let mean = 0.5 * (shares[left] + shares[right]);
@@ -419,7 +1311,7 @@ fn resize_interaction<Pane>(
         shares[right] = mean;
         ResizeState::Hovering
     } else if splitter_response.dragged() {
-        behavior.on_edit(EditAction::TileResized);
+        tiles.on_edit(behavior, EditAction::TileResized);
 
         if dx < 0.0 {
             // Expand right, shrink stuff to the left:
@@ -488,6 +1380,33 @@ fn shrink_shares<Pane>(
     total_shares_lost
 }
 
+/// Move the `stride` items at `from * stride` so that they end up at `to * stride`.
+fn move_item<T>(items: &mut [T], from: usize, to: usize, stride: usize) {
+    if from < to {
+        items[from * stride..(to + 1) * stride].rotate_left(stride);
+    } else {
+        items[to * stride..(from + 1) * stride].rotate_right(stride);
+    }
+}
+
+/// Give a new column or row the average share of the others.
+fn insert_share(shares: &mut Vec<f32>, index: usize) {
+    if index <= shares.len() {
+        let share = if shares.is_empty() {
+            1.0
+        } else {
+            shares.iter().sum::<f32>() / shares.len() as f32
+        };
+        shares.insert(index, share);
+    }
+}
+
+fn remove_share(shares: &mut Vec<f32>, index: usize) {
+    if index < shares.len() {
+        shares.remove(index);
+    }
+}
+
 fn sizes_from_shares(shares: &[f32], available_size: f32, gap_width: f32) -> Vec<f32> {
     if shares.is_empty() {
         return vec![];
@@ -513,6 +1432,99 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_grid_edits() {
+        let [a, b, c, d] = [1, 2, 3, 4].map(TileId::from_u64);
+        let mut grid = Grid::new(vec![a, b, c, d]);
+        grid.layout = GridLayout::Columns(2);
+
+        assert!(grid.apply_edit(GridEdit::InsertColumn(1)));
+        assert_eq!(grid.layout, GridLayout::Columns(3));
+        assert_eq!(
+            grid.children,
+            vec![Some(a), None, Some(b), Some(c), None, Some(d)]
+        );
+
+        assert!(grid.apply_edit(GridEdit::InsertRow(1)));
+        assert_eq!(grid.children.len(), 9);
+        assert_eq!(grid.children[6..], [Some(c), None, Some(d)]);
+
+        // The children of a deleted row or column move to the end:
+        assert!(grid.apply_edit(GridEdit::DeleteColumn(0)));
+        assert_eq!(grid.layout, GridLayout::Columns(2));
+        assert_eq!(
+            grid.children,
+            vec![None, Some(b), None, None, None, Some(d), Some(a), Some(c)]
+        );
+        assert!(grid.apply_edit(GridEdit::DeleteRow(1)));
+        assert_eq!(
+            grid.children,
+            vec![None, Some(b), None, Some(d), Some(a), Some(c)]
+        );
+
+        assert!(!grid.apply_edit(GridEdit::DeleteRow(3)));
+        assert!(!grid.apply_edit(GridEdit::InsertColumn(3)));
+    }
+
+    #[test]
+    fn test_put_in_cell() {
+        let [a, b, c, d, e] = [1, 2, 3, 4, 5].map(TileId::from_u64);
+        let mut grid = Grid::new(vec![]);
+        grid.children = vec![Some(a), Some(b), None, Some(c)];
+        let is_visible = |child: TileId| child != b;
+
+        // The hole is the second cell, since `b` takes up no cell:
+        grid.put_in_cell(1, d, is_visible);
+        assert_eq!(grid.children, [a, b, d, c].map(Some).to_vec());
+
+        // Cells past the end are padded with holes:
+        grid.put_in_cell(4, e, is_visible);
+        assert_eq!(
+            grid.children,
+            vec![Some(a), Some(b), Some(d), Some(c), None, Some(e)]
+        );
+    }
+
+    #[test]
+    fn test_grid_move_edits() {
+        let [a, b, c, d, e, f] = [1, 2, 3, 4, 5, 6].map(TileId::from_u64);
+        let mut grid = Grid::new(vec![a, b, c, d, e, f]);
+        grid.layout = GridLayout::Columns(3);
+        grid.col_shares = vec![1.0, 2.0, 3.0];
+        grid.row_shares = vec![4.0, 5.0];
+
+        assert!(grid.apply_edit(GridEdit::MoveColumn { from: 0, to: 2 }));
+        assert_eq!(grid.children, [b, c, a, e, f, d].map(Some).to_vec());
+        assert_eq!(grid.col_shares, vec![2.0, 3.0, 1.0]);
+
+        assert!(grid.apply_edit(GridEdit::MoveRow { from: 1, to: 0 }));
+        assert_eq!(grid.children, [e, f, d, b, c, a].map(Some).to_vec());
+        assert_eq!(grid.row_shares, vec![5.0, 4.0]);
+
+        assert!(!grid.apply_edit(GridEdit::MoveColumn { from: 1, to: 1 }));
+        assert!(!grid.apply_edit(GridEdit::MoveRow { from: 0, to: 2 }));
+    }
+
+    #[test]
+    fn test_grid_transpose() {
+        let [a, b, c, d, e, f] = [1, 2, 3, 4, 5, 6].map(TileId::from_u64);
+        let mut grid = Grid::new(vec![a, b, c, d, e, f]);
+        grid.layout = GridLayout::Columns(3);
+        grid.col_shares = vec![1.0, 2.0, 3.0];
+        grid.row_shares = vec![4.0, 5.0];
+
+        let mut transposed = grid.clone();
+        transposed.transpose(false);
+        assert_eq!(transposed.layout, GridLayout::Columns(2));
+        assert_eq!(transposed.children, [a, d, b, e, c, f].map(Some).to_vec());
+        assert_eq!(transposed.col_shares, vec![4.0, 5.0]);
+        assert_eq!(transposed.row_shares, vec![1.0, 2.0, 3.0]);
+
+        grid.transpose(true);
+        assert_eq!(grid.children, [d, a, e, b, f, c].map(Some).to_vec());
+        assert_eq!(grid.col_shares, vec![5.0, 4.0]);
+    }
+
     #[test]
     fn test_grid_with_chaos_monkey() {
         #[derive(Debug)]
@@ -559,7 +1571,14 @@ mod tests {
 
         for _ in 0..1000 {
             let root = tree.root.unwrap();
-            tree.tiles.layout_tile(&style, &mut behavior, area, root);
+            tree.tiles.layout_tile(
+                &style,
+                tree.layout_params(1.0),
+                &mut behavior,
+                area,
+                None,
+                root,
+            );
 
             // Add some tiles:
             for _ in 0..rng.rand_u64() % 3 {
@@ -590,7 +1609,7 @@ mod tests {
             for _ in 0..rng.rand_u64() % 2 {
                 let children =
                     if let Some(Tile::Container(Container::Grid(grid))) = tree.tiles.get(root) {
-                        grid.visible_children_and_holes(&tree.tiles)
+                        grid.visible_children_and_holes(|tile_id| tree.tiles.is_visible(tile_id))
                             .iter()
                             .copied()
                             .flatten()
@@ -609,7 +1628,7 @@ mod tests {
             for _ in 0..rng.rand_u64() % 2 {
                 let children =
                     if let Some(Tile::Container(Container::Grid(grid))) = tree.tiles.get(root) {
-                        grid.visible_children_and_holes(&tree.tiles)
+                        grid.visible_children_and_holes(|tile_id| tree.tiles.is_visible(tile_id))
                             .iter()
                             .copied()
                             .flatten()
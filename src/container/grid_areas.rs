@@ -0,0 +1,107 @@
+use std::ops::Range;
+
+/// A named area of a [`super::Grid`], parsed from its template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct GridArea {
+    pub name: String,
+    pub cols: Range<usize>,
+    pub rows: Range<usize>,
+}
+
+/// The parsed form of [`super::Grid::set_template_areas`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct GridTemplate {
+    pub num_cols: usize,
+    pub num_rows: usize,
+    pub areas: Vec<GridArea>,
+}
+
+impl GridTemplate {
+    /// Parse rows like `"header header"`, `"sidebar main"`.
+    ///
+    /// Each row must have the same number of cells, and each area must be a rectangle.
+    /// A `.` marks a cell that is not part of any area.
+    pub fn parse(rows: &[impl AsRef<str>]) -> Option<Self> {
+        let cells: Vec<Vec<&str>> = rows
+            .iter()
+            .map(|row| row.as_ref().split_whitespace().collect())
+            .collect();
+        let num_cols = cells.first()?.len();
+        if num_cols == 0 || cells.iter().any(|row| row.len() != num_cols) {
+            return None;
+        }
+
+        let mut areas: Vec<GridArea> = Vec::new();
+        for (row, names) in cells.iter().enumerate() {
+            for (col, &name) in names.iter().enumerate() {
+                if name == "." {
+                    continue;
+                }
+                if let Some(area) = areas.iter_mut().find(|area| area.name == name) {
+                    area.cols = area.cols.start.min(col)..area.cols.end.max(col + 1);
+                    area.rows = area.rows.start.min(row)..area.rows.end.max(row + 1);
+                } else {
+                    areas.push(GridArea {
+                        name: name.to_owned(),
+                        cols: col..col + 1,
+                        rows: row..row + 1,
+                    });
+                }
+            }
+        }
+
+        // Every cell inside the bounds of an area must belong to it:
+        let is_rectangle = |area: &GridArea| {
+            area.rows.clone().all(|row| {
+                area.cols
+                    .clone()
+                    .all(|col| cells[row][col] == area.name.as_str())
+            })
+        };
+        if !areas.iter().all(is_rectangle) {
+            return None;
+        }
+
+        Some(Self {
+            num_cols,
+            num_rows: cells.len(),
+            areas,
+        })
+    }
+
+    pub fn area(&self, name: &str) -> Option<&GridArea> {
+        self.areas.iter().find(|area| area.name == name)
+    }
+
+    /// The area covering the given cell, if any.
+    pub fn area_at(&self, col: usize, row: usize) -> Option<&GridArea> {
+        self.areas
+            .iter()
+            .find(|area| area.cols.contains(&col) && area.rows.contains(&row))
+    }
+
+    /// The cells that are not part of any area, in row-major order.
+    pub fn free_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.num_rows)
+            .flat_map(move |row| (0..self.num_cols).map(move |col| (col, row)))
+            .filter(|&(col, row)| self.area_at(col, row).is_none())
+    }
+}
+
+#[test]
+fn test_parse_grid_template() {
+    let template =
+        GridTemplate::parse(&["header header", "sidebar main", ". main"]).expect("valid template");
+    assert_eq!(template.num_cols, 2);
+    assert_eq!(template.num_rows, 3);
+    let main = template.area("main").expect("main area");
+    assert_eq!((main.cols.clone(), main.rows.clone()), (1..2, 1..3));
+    assert_eq!(template.free_cells().collect::<Vec<_>>(), vec![(0, 2)]);
+
+    assert!(GridTemplate::parse(&["a b", "a"]).is_none(), "ragged rows");
+    assert!(GridTemplate::parse(&["a b a"]).is_none(), "split area");
+    assert!(
+        GridTemplate::parse(&["a a", "a b"]).is_none(),
+        "not a rectangle"
+    );
+}
@@ -4,6 +4,8 @@ use egui::{pos2, vec2, NumExt, Rect};
 use itertools::Itertools as _;
 
 use crate::behavior::EditAction;
+use crate::tile_id_hash::{TileIdMap, TileIdSet};
+use crate::tiles::LayoutContext;
 use crate::{
     is_being_dragged, Behavior, ContainerInsertion, DropContext, InsertionPoint, ResizeState,
     SimplifyAction, TileId, Tiles, Tree,
@@ -11,6 +13,15 @@ use crate::{
 
 // ----------------------------------------------------------------------------
 
+/// Fields with their default values are left out when serializing,
+/// so that adding one doesn't change the serialized layout of existing trees.
+#[cfg(feature = "serde")]
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+// ----------------------------------------------------------------------------
+
 /// How large of a share of space each child has, on a 1D axis.
 ///
 /// Used for [`Linear`] containers (horizontal and vertical).
@@ -23,7 +34,11 @@ pub struct Shares {
     ///
     /// For instance, the shares `[1, 2, 3]` means that the first child gets 1/6 of the space,
     /// the second gets 2/6 and the third gets 3/6.
-    shares: ahash::HashMap<TileId, f32>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::tile_id_hash::serialize_sorted_map")
+    )]
+    shares: TileIdMap<f32>,
 }
 
 impl Shares {
@@ -59,6 +74,11 @@ impl Shares {
     pub fn retain(&mut self, keep: impl Fn(TileId) -> bool) {
         self.shares.retain(|&child, _| keep(child));
     }
+
+    /// Forget all shares for which `is_valid` returns `false`, so that they get the default share.
+    pub(crate) fn retain_valid(&mut self, is_valid: impl Fn(f32) -> bool) {
+        self.shares.retain(|_, &mut share| is_valid(share));
+    }
 }
 
 impl<'a> IntoIterator for &'a Shares {
@@ -98,13 +118,187 @@ pub enum LinearDir {
     Vertical,
 }
 
+/// What happens to the share of a child that is removed from a [`Linear`] container.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ShareRedistribution {
+    /// The remaining children grow in proportion to their shares.
+    #[default]
+    Proportional,
+
+    /// The previous sibling gets all of it (or the next one, if the first child was removed).
+    PreviousSibling,
+
+    /// The next sibling gets all of it (or the previous one, if the last child was removed).
+    NextSibling,
+}
+
+/// What a [`Linear`] container does when its children don't fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LinearOverflow {
+    /// Squish the children into the available space, however small it is.
+    #[default]
+    Shrink,
+
+    /// Keep each child at least at its minimum size (see [`Linear::set_min_size`]),
+    /// and scroll the children when they don't fit, e.g. for a long stack of inspector panes.
+    Scroll,
+}
+
+/// A built-in entry in the context menu of a [`Linear`] divider,
+/// see [`Linear::apply_divider_action`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DividerAction {
+    /// Give all expanded children the same share.
+    Equalize,
+
+    /// Shrink the child left of (or above) the divider to its minimum size.
+    CollapseBefore,
+
+    /// Shrink the child right of (or below) the divider to its minimum size.
+    CollapseAfter,
+
+    /// Lock or unlock the divider, see [`Linear::set_divider_locked`].
+    ToggleLock,
+}
+
 /// Horizontal or vertical container.
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Linear {
     pub children: Vec<TileId>,
     pub dir: LinearDir,
+
+    #[cfg_attr(feature = "serde", serde(default))]
     pub shares: Shares,
+
+    /// Who gets the space of a child when it is removed, e.g. because its last tab was closed.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_default"))]
+    pub share_redistribution: ShareRedistribution,
+
+    /// Give each child of a vertical container a header that folds it away when clicked,
+    /// like the sections of an inspector panel.
+    ///
+    /// The expanded children share the space left by the folded ones,
+    /// and can still be resized against each other.
+    /// See [`Self::set_collapsed`] and [`Behavior::section_header_ui`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub collapsible: bool,
+
+    /// The folded children, see [`Self::collapsible`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "TileIdSet::is_empty",
+            serialize_with = "crate::tile_id_hash::serialize_sorted_set"
+        )
+    )]
+    collapsed: TileIdSet,
+
+    /// What to do when the children don't fit at their minimum sizes.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_default"))]
+    pub overflow: LinearOverflow,
+
+    /// See [`Self::set_min_size`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "TileIdMap::is_empty",
+            serialize_with = "crate::tile_id_hash::serialize_sorted_map"
+        )
+    )]
+    min_sizes: TileIdMap<f32>,
+
+    /// See [`Self::set_divider_locked`]. Keyed by the first of the two children.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "TileIdSet::is_empty",
+            serialize_with = "crate::tile_id_hash::serialize_sorted_set"
+        )
+    )]
+    locked_dividers: TileIdSet,
+
+    /// How far the children are scrolled with [`LinearOverflow::Scroll`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scroll_offset: f32,
+
+    /// How much longer the children are than the container, recomputed during layout.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    overflow_size: f32,
+}
+
+impl PartialEq for Linear {
+    fn eq(&self, other: &Self) -> bool {
+        // The scroll offset decides where the children go, so the layout cache must see it:
+        self.layout_eq(other) && self.scroll_offset == other.scroll_offset
+    }
+}
+
+impl Linear {
+    /// Like `==`, but ignoring how far the children are scrolled,
+    /// which is view state rather than part of the layout, e.g. for the undo history.
+    pub(crate) fn layout_eq(&self, other: &Self) -> bool {
+        let Self {
+            children,
+            dir,
+            shares,
+            share_redistribution,
+            collapsible,
+            collapsed,
+            overflow,
+            min_sizes,
+            locked_dividers,
+            scroll_offset: _, // ignored because it is view state
+            overflow_size: _, // ignored because it is recomputed each frame
+        } = self;
+
+        children == &other.children
+            && dir == &other.dir
+            && shares == &other.shares
+            && share_redistribution == &other.share_redistribution
+            && collapsible == &other.collapsible
+            && collapsed == &other.collapsed
+            && overflow == &other.overflow
+            && min_sizes == &other.min_sizes
+            && locked_dividers == &other.locked_dividers
+    }
+
+    /// Replace every [`TileId`] this refers to, e.g. when copying a whole subtree.
+    pub(crate) fn map_tile_ids(&mut self, f: impl Fn(TileId) -> TileId) {
+        let Self {
+            children,
+            dir: _,
+            shares,
+            share_redistribution: _,
+            collapsible: _,
+            collapsed,
+            overflow: _,
+            min_sizes,
+            locked_dividers,
+            scroll_offset: _,
+            overflow_size: _,
+        } = self;
+
+        for child in children {
+            *child = f(*child);
+        }
+        shares.shares = shares
+            .shares
+            .iter()
+            .map(|(&id, &share)| (f(id), share))
+            .collect();
+        *collapsed = collapsed.iter().map(|&id| f(id)).collect();
+        *min_sizes = min_sizes.iter().map(|(&id, &size)| (f(id), size)).collect();
+        *locked_dividers = locked_dividers.iter().map(|&id| f(id)).collect();
+    }
 }
 
 impl Linear {
@@ -116,11 +310,246 @@ impl Linear {
         }
     }
 
-    fn visible_children<Pane>(&self, tiles: &Tiles<Pane>) -> Vec<TileId> {
+    /// Is the given child folded down to its section header?
+    ///
+    /// Always `false` unless [`Self::collapsible`] is set and the container is vertical.
+    pub fn is_collapsed(&self, child: TileId) -> bool {
+        self.has_sections() && self.collapsed.contains(&child)
+    }
+
+    /// Fold or unfold the section of the given child, see [`Self::collapsible`].
+    pub fn set_collapsed(&mut self, child: TileId, collapsed: bool) {
+        if collapsed {
+            self.collapsed.insert(child);
+        } else {
+            self.collapsed.remove(&child);
+        }
+    }
+
+    /// The smallest width (or height, if vertical) the given child may get.
+    ///
+    /// Defaults to [`Behavior::min_size`].
+    /// Only [`LinearOverflow::Scroll`] keeps children from shrinking below this when out of space.
+    /// Returns `None` if the child has no minimum size of its own.
+    pub fn min_size(&self, child: TileId) -> Option<f32> {
+        self.min_sizes.get(&child).copied()
+    }
+
+    /// See [`Self::min_size`]. `None` goes back to the default.
+    pub fn set_min_size(&mut self, child: TileId, min_size: Option<f32>) {
+        if let Some(min_size) = min_size {
+            self.min_sizes.insert(child, min_size);
+        } else {
+            self.min_sizes.remove(&child);
+        }
+    }
+
+    /// Give `child` the given fraction (0.0 - 1.0) of the space by changing only its own share.
+    ///
+    /// The other children keep their shares, and so their sizes relative to each other.
+    /// Invisible children count too.
+    pub fn set_fraction(&mut self, child: TileId, fraction: f32) {
+        let fraction = fraction.clamp(0.01, 0.99);
+        let others: f32 = self
+            .children
+            .iter()
+            .filter(|&&other| other != child)
+            .map(|&other| self.shares[other])
+            .sum();
+        if others > 0.0 {
+            self.shares[child] = others * fraction / (1.0 - fraction);
+        }
+    }
+
+    /// Is the divider between these two neighboring children locked?
+    ///
+    /// A locked divider can't be dragged, but it still moves when other dividers are dragged.
+    pub fn is_divider_locked(&self, pair: [TileId; 2]) -> bool {
+        self.divider_key(pair)
+            .is_some_and(|key| self.locked_dividers.contains(&key))
+    }
+
+    /// Lock or unlock the divider between these two neighboring children.
+    ///
+    /// The user can also do this from the context menu of the divider.
+    pub fn set_divider_locked(&mut self, pair: [TileId; 2], locked: bool) {
+        let Some(key) = self.divider_key(pair) else {
+            return;
+        };
+        if locked {
+            self.locked_dividers.insert(key);
+        } else {
+            self.locked_dividers.remove(&key);
+        }
+    }
+
+    /// Whichever of the two children comes first in [`Self::children`].
+    fn divider_key(&self, [a, b]: [TileId; 2]) -> Option<TileId> {
+        let position = |needle: TileId| self.children.iter().position(|&child| child == needle);
+        let (a_index, b_index) = (position(a)?, position(b)?);
+        Some(if a_index < b_index { a } else { b })
+    }
+
+    /// Apply an entry from the context menu of the divider between `pair`,
+    /// given left/top first.
+    ///
+    /// Collapsing uses the current rects of the two children, so the tree must have been laid out.
+    /// Returns `false` if nothing changed.
+    pub fn apply_divider_action<Pane>(
+        &mut self,
+        tiles: &Tiles<Pane>,
+        behavior: &dyn Behavior<Pane>,
+        action: DividerAction,
+        pair: [TileId; 2],
+    ) -> bool {
+        match action {
+            DividerAction::Equalize => {
+                let expanded = self
+                    .visible_children(|child| tiles.is_visible(child) && !self.is_collapsed(child));
+                if expanded.is_empty() {
+                    return false;
+                }
+                let mean = expanded
+                    .iter()
+                    .map(|&child| self.shares[child])
+                    .sum::<f32>()
+                    / expanded.len() as f32;
+                for child in expanded {
+                    self.shares[child] = mean;
+                }
+                true
+            }
+            DividerAction::CollapseBefore | DividerAction::CollapseAfter => {
+                let [before, after] = pair;
+                let (shrink, grow) = if action == DividerAction::CollapseBefore {
+                    (before, after)
+                } else {
+                    (after, before)
+                };
+                let length = |tile_id: TileId| {
+                    tiles.rect(tile_id).map(|rect| match self.dir {
+                        LinearDir::Horizontal => rect.width(),
+                        LinearDir::Vertical => rect.height(),
+                    })
+                };
+                let (Some(shrink_length), Some(grow_length)) = (length(shrink), length(grow))
+                else {
+                    return false;
+                };
+                let total_length = shrink_length + grow_length;
+                if total_length <= 0.0 {
+                    return false;
+                }
+                let total_shares = self.shares[shrink] + self.shares[grow];
+                let min_size = self
+                    .min_size(shrink)
+                    .unwrap_or_else(|| behavior.min_size())
+                    .at_most(total_length);
+                self.shares[shrink] = total_shares * min_size / total_length;
+                self.shares[grow] = total_shares - self.shares[shrink];
+                true
+            }
+            DividerAction::ToggleLock => {
+                let locked = self.is_divider_locked(pair);
+                self.set_divider_locked(pair, !locked);
+                self.divider_key(pair).is_some()
+            }
+        }
+    }
+
+    /// Split the available length between the given children, according to their shares.
+    ///
+    /// With [`LinearOverflow::Scroll`] no child gets less than its minimum size,
+    /// so the total may be more than `available`.
+    fn split_length<Pane>(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        children: &[TileId],
+        available: f32,
+    ) -> Vec<f32> {
+        if self.overflow == LinearOverflow::Shrink {
+            return self.shares.split(children, available);
+        }
+        let min_size = |child: TileId| self.min_size(child).unwrap_or_else(|| behavior.min_size());
+
+        // Pin the children that would get too little to their minimum, and split the rest:
+        let mut pinned = vec![false; children.len()];
+        loop {
+            let pinned_length: f32 = children
+                .iter()
+                .zip(&pinned)
+                .filter(|(_, &pinned)| pinned)
+                .map(|(&child, _)| min_size(child))
+                .sum();
+            let free: Vec<TileId> = children
+                .iter()
+                .zip(&pinned)
+                .filter(|(_, &pinned)| !pinned)
+                .map(|(&child, _)| child)
+                .collect();
+            let mut free_lengths = self
+                .shares
+                .split(&free, (available - pinned_length).at_least(0.0))
+                .into_iter();
+
+            let lengths: Vec<f32> = children
+                .iter()
+                .zip(&pinned)
+                .map(|(&child, &pinned)| {
+                    if pinned {
+                        min_size(child)
+                    } else {
+                        free_lengths.next().unwrap_or_default()
+                    }
+                })
+                .collect();
+
+            let mut pinned_more = false;
+            for (i, (&child, &length)) in children.iter().zip(&lengths).enumerate() {
+                if !pinned[i] && length < min_size(child) {
+                    pinned[i] = true;
+                    pinned_more = true;
+                }
+            }
+            if !pinned_more {
+                return lengths;
+            }
+        }
+    }
+
+    /// Remember how far the children stick out of `length`,
+    /// and keep the scroll offset within that.
+    fn set_content_length(&mut self, content_length: f32, length: f32) {
+        self.overflow_size = if self.overflow == LinearOverflow::Scroll {
+            (content_length - length).at_least(0.0)
+        } else {
+            0.0
+        };
+        self.scroll_offset = self.scroll_offset.clamp(0.0, self.overflow_size);
+    }
+
+    fn has_sections(&self) -> bool {
+        self.collapsible && self.dir == LinearDir::Vertical
+    }
+
+    /// The height of the title bar or section header above each child.
+    pub(crate) fn header_height<Pane>(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        style: &egui::Style,
+    ) -> f32 {
+        if self.has_sections() {
+            behavior.section_header_height(style)
+        } else {
+            behavior.title_bar_height(style)
+        }
+    }
+
+    fn visible_children(&self, is_visible: impl Fn(TileId) -> bool) -> Vec<TileId> {
         self.children
             .iter()
             .copied()
-            .filter(|&child_id| tiles.is_visible(child_id))
+            .filter(|&child_id| is_visible(child_id))
             .collect()
     }
 
@@ -148,70 +577,127 @@ impl Linear {
         self.children.push(child);
     }
 
-    pub fn layout<Pane>(
+    pub(super) fn hash_layout(&self, state: &mut impl std::hash::Hasher) {
+        use std::hash::Hash as _;
+
+        self.dir.hash(state);
+        self.children.hash(state);
+        for &child in &self.children {
+            self.shares[child].to_bits().hash(state);
+            self.is_collapsed(child).hash(state);
+            self.min_size(child).map(f32::to_bits).hash(state);
+        }
+        self.overflow.hash(state);
+        self.scroll_offset.to_bits().hash(state);
+    }
+
+    pub(super) fn layout<Pane>(
         &mut self,
-        tiles: &mut Tiles<Pane>,
+        ctx: &mut LayoutContext<'_>,
         style: &egui::Style,
-        behavior: &mut dyn Behavior<Pane>,
+        behavior: &dyn Behavior<Pane>,
         rect: Rect,
     ) {
         // GC:
-        let child_set: ahash::HashSet<TileId> = self.children.iter().copied().collect();
+        let child_set: TileIdSet = self.children.iter().copied().collect();
         self.shares.retain(|id| child_set.contains(&id));
+        self.collapsed.retain(|id| child_set.contains(id));
+        self.min_sizes.retain(|id, _| child_set.contains(id));
+        self.locked_dividers.retain(|id| child_set.contains(id));
 
         match self.dir {
             LinearDir::Horizontal => {
-                self.layout_horizontal(tiles, style, behavior, rect);
+                self.layout_horizontal(ctx, style, behavior, rect);
             }
-            LinearDir::Vertical => self.layout_vertical(tiles, style, behavior, rect),
+            LinearDir::Vertical => self.layout_vertical(ctx, style, behavior, rect),
         }
     }
 
     fn layout_horizontal<Pane>(
-        &self,
-        tiles: &mut Tiles<Pane>,
+        &mut self,
+        ctx: &mut LayoutContext<'_>,
         style: &egui::Style,
-        behavior: &mut dyn Behavior<Pane>,
+        behavior: &dyn Behavior<Pane>,
         rect: Rect,
     ) {
-        let visible_children = self.visible_children(tiles);
+        let visible_children = self.visible_children(|tile_id| ctx.is_visible(tile_id));
 
         let num_gaps = visible_children.len().saturating_sub(1);
-        let gap_width = behavior.gap_width(style);
+        let gap_width = behavior.gap_width_at_depth(style, ctx.depth());
         let total_gap_width = gap_width * num_gaps as f32;
         let available_width = (rect.width() - total_gap_width).at_least(0.0);
 
-        let widths = self.shares.split(&visible_children, available_width);
+        let widths = self.split_length(behavior, &visible_children, available_width);
+        let content_width = widths.iter().sum::<f32>() + total_gap_width;
+        self.set_content_length(content_width, rect.width());
+
+        // Each child goes below its title bar (if any):
+        let title_bar_height = behavior.title_bar_height(style).at_most(rect.height());
+        let child_height = rect.height() - title_bar_height;
 
-        let mut x = rect.min.x;
+        let mut x = rect.min.x - self.scroll_offset;
         for (child, width) in visible_children.iter().zip(widths) {
-            let child_rect = Rect::from_min_size(pos2(x, rect.min.y), vec2(width, rect.height()));
-            tiles.layout_tile(style, behavior, child_rect, *child);
+            // Snap to physical pixels, so the gaps (and the dividers in them) stay crisp:
+            let (mut left, mut right) = (ctx.round_to_pixels(x), ctx.round_to_pixels(x + width));
+            if ctx.right_to_left() {
+                // Mirror, so the first child is rightmost:
+                (left, right) = (
+                    rect.left() + rect.right() - right,
+                    rect.left() + rect.right() - left,
+                );
+            }
+            let child_rect = Rect::from_min_size(
+                pos2(left, rect.min.y + title_bar_height),
+                vec2(right - left, child_height),
+            );
+            ctx.layout_child(*child, child_rect);
             x += width + gap_width;
         }
     }
 
     fn layout_vertical<Pane>(
-        &self,
-        tiles: &mut Tiles<Pane>,
+        &mut self,
+        ctx: &mut LayoutContext<'_>,
         style: &egui::Style,
-        behavior: &mut dyn Behavior<Pane>,
+        behavior: &dyn Behavior<Pane>,
         rect: Rect,
     ) {
-        let visible_children = self.visible_children(tiles);
+        let visible_children = self.visible_children(|tile_id| ctx.is_visible(tile_id));
 
         let num_gaps = visible_children.len().saturating_sub(1);
-        let gap_height = behavior.gap_width(style);
+        let gap_height = behavior.gap_width_at_depth(style, ctx.depth());
         let total_gap_height = gap_height * num_gaps as f32;
-        let available_height = (rect.height() - total_gap_height).at_least(0.0);
 
-        let heights = self.shares.split(&visible_children, available_height);
+        // Each child goes below its title bar or section header (if any):
+        let header_height = self.header_height(behavior, style);
+        let total_header_height = header_height * visible_children.len() as f32;
+
+        let available_height =
+            (rect.height() - total_gap_height - total_header_height).at_least(0.0);
 
-        let mut y = rect.min.y;
-        for (child, height) in visible_children.iter().zip(heights) {
-            let child_rect = Rect::from_min_size(pos2(rect.min.x, y), vec2(rect.width(), height));
-            tiles.layout_tile(style, behavior, child_rect, *child);
-            y += height + gap_height;
+        // Folded sections are just their header, and are not laid out at all:
+        let expanded_children: Vec<TileId> = visible_children
+            .iter()
+            .copied()
+            .filter(|&child| !self.is_collapsed(child))
+            .collect();
+        let heights = self.split_length(behavior, &expanded_children, available_height);
+        let content_height = heights.iter().sum::<f32>() + total_gap_height + total_header_height;
+        self.set_content_length(content_height, rect.height());
+        let mut heights = heights.into_iter();
+
+        let mut y = rect.min.y - self.scroll_offset;
+        for &child in &visible_children {
+            y += header_height;
+            if !self.is_collapsed(child) {
+                let height = heights.next().unwrap_or_default();
+                let (top, bottom) = (ctx.round_to_pixels(y), ctx.round_to_pixels(y + height));
+                let child_rect =
+                    Rect::from_min_max(pos2(rect.min.x, top), pos2(rect.max.x, bottom));
+                ctx.layout_child(child, child_rect);
+                y += height;
+            }
+            y += gap_height;
         }
     }
 
@@ -220,13 +706,83 @@ impl Linear {
         tree: &mut Tree<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         drop_context: &mut DropContext,
-        ui: &egui::Ui,
+        ui: &mut egui::Ui,
         tile_id: TileId,
     ) {
+        if self.overflow == LinearOverflow::Shrink {
+            match self.dir {
+                LinearDir::Horizontal => {
+                    self.horizontal_ui(tree, behavior, drop_context, ui, tile_id);
+                }
+                LinearDir::Vertical => self.vertical_ui(tree, behavior, drop_context, ui, tile_id),
+            }
+            return;
+        }
+
+        // The children may stick out, so keep them inside the container:
+        let rect = tree.tiles.rect_or_die(tile_id);
+        let mut scroll_ui = ui.new_child(
+            egui::UiBuilder::new()
+                .id_salt((tile_id, "scroll"))
+                .max_rect(rect),
+        );
+        scroll_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
         match self.dir {
-            LinearDir::Horizontal => self.horizontal_ui(tree, behavior, drop_context, ui, tile_id),
-            LinearDir::Vertical => self.vertical_ui(tree, behavior, drop_context, ui, tile_id),
+            LinearDir::Horizontal => {
+                self.horizontal_ui(tree, behavior, drop_context, &mut scroll_ui, tile_id);
+            }
+            LinearDir::Vertical => {
+                self.vertical_ui(tree, behavior, drop_context, &mut scroll_ui, tile_id);
+            }
+        }
+        self.scroll_ui(&scroll_ui, rect);
+    }
+
+    /// Scroll with the mouse wheel (unless a child already did), and show where we are.
+    fn scroll_ui(&mut self, ui: &egui::Ui, rect: Rect) {
+        if self.overflow_size <= 0.0 {
+            return;
+        }
+
+        if ui.rect_contains_pointer(rect) {
+            let delta = ui.input(|i| i.smooth_scroll_delta);
+            // Most mice only have a vertical wheel, so use that to scroll sideways too:
+            let delta = match self.dir {
+                LinearDir::Horizontal if delta.x != 0.0 => delta.x,
+                LinearDir::Horizontal | LinearDir::Vertical => delta.y,
+            };
+            let scroll_offset = (self.scroll_offset - delta).clamp(0.0, self.overflow_size);
+            if scroll_offset != self.scroll_offset {
+                self.scroll_offset = scroll_offset;
+                ui.input_mut(|i| i.smooth_scroll_delta = egui::Vec2::ZERO);
+                ui.ctx().request_repaint();
+            }
         }
+
+        // A thin bar along the far edge, showing which part of the children is in view:
+        let (length, thickness) = match self.dir {
+            LinearDir::Horizontal => (rect.width(), rect.height()),
+            LinearDir::Vertical => (rect.height(), rect.width()),
+        };
+        let content_length = length + self.overflow_size;
+        let bar_start = length * self.scroll_offset / content_length;
+        let bar_length = length * length / content_length;
+        let bar_thickness = 4.0_f32.at_most(thickness);
+        let bar_rect = match self.dir {
+            LinearDir::Horizontal => Rect::from_min_size(
+                pos2(rect.left() + bar_start, rect.bottom() - bar_thickness),
+                vec2(bar_length, bar_thickness),
+            ),
+            LinearDir::Vertical => Rect::from_min_size(
+                pos2(rect.right() - bar_thickness, rect.top() + bar_start),
+                vec2(bar_thickness, bar_length),
+            ),
+        };
+        ui.painter().rect_filled(
+            bar_rect,
+            0.5 * bar_thickness,
+            ui.visuals().widgets.inactive.bg_fill,
+        );
     }
 
     fn horizontal_ui<Pane>(
@@ -234,27 +790,37 @@ impl Linear {
         tree: &mut Tree<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         drop_context: &mut DropContext,
-        ui: &egui::Ui,
+        ui: &mut egui::Ui,
         parent_id: TileId,
     ) {
-        let visible_children = self.visible_children(&tree.tiles);
+        let visible_children = self.visible_children(|tile_id| tree.tiles.is_visible(tile_id));
 
         for &child in &visible_children {
+            super::title_bar_ui(tree, behavior, ui, child);
             tree.tile_ui(behavior, drop_context, ui, child);
             crate::cover_tile_if_dragged(tree, behavior, ui, child);
         }
 
-        linear_drop_zones(ui.ctx(), tree, &self.children, self.dir, |rect, i| {
-            drop_context.suggest_rect(
-                InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(i)),
-                rect,
-            );
-        });
+        if drop_context.enabled {
+            linear_drop_zones(ui.ctx(), tree, &self.children, self.dir, |rect, i| {
+                drop_context.suggest_rect(
+                    InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(i)),
+                    rect,
+                );
+            });
+        }
 
         // ------------------------
         // resizing:
 
+        // In the order they are shown, left to right:
+        let mut visible_children = visible_children;
+        if tree.right_to_left {
+            visible_children.reverse();
+        }
+
         let parent_rect = tree.tiles.rect_or_die(parent_id);
+        let depth = tree.tiles.ancestors(parent_id).count();
         for (i, (left, right)) in visible_children.iter().copied().tuple_windows().enumerate() {
             let resize_id = ui.id().with((parent_id, "resize", i));
 
@@ -263,34 +829,59 @@ impl Linear {
             let x = egui::lerp(left_rect.right()..=right_rect.left(), 0.5);
 
             let mut resize_state = ResizeState::Idle;
-            let line_rect = Rect::from_center_size(
-                pos2(x, parent_rect.center().y),
-                vec2(
-                    2.0 * ui.style().interaction.resize_grab_radius_side,
-                    parent_rect.height(),
-                ),
-            );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
+            if [parent_id, left, right]
+                .iter()
+                .all(|&tile_id| tree.edit_permissions(behavior, tile_id).resize)
+            {
+                let line_rect = Rect::from_center_size(
+                    pos2(x, parent_rect.center().y),
+                    vec2(
+                        2.0 * ui.style().interaction.resize_grab_radius_side,
+                        parent_rect.height(),
+                    ),
+                );
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                self.divider_context_menu(
+                    &mut tree.tiles,
                     behavior,
-                    &mut self.shares,
-                    &visible_children,
                     &response,
+                    parent_id,
                     [left, right],
-                    ui.painter().round_to_pixel(pointer.x) - x,
-                    i,
-                    |tile_id: TileId| tree.tiles.rect_or_die(tile_id).width(),
                 );
-
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+                let locked = self.is_divider_locked([left, right]);
+                if let Some(steps) =
+                    super::scroll_resize_steps(behavior, ui, line_rect).filter(|_| !locked)
+                {
+                    let shares = [self.shares[left], self.shares[right]];
+                    [self.shares[left], self.shares[right]] = super::shift_shares(shares, steps);
+                    tree.tiles.on_edit(behavior, EditAction::TileResized);
+                    tree.tiles.on_resized(parent_id);
+                }
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos().filter(|_| !locked) {
+                    resize_state = resize_interaction(
+                        behavior,
+                        &mut tree.tiles,
+                        &mut self.shares,
+                        &visible_children,
+                        &response,
+                        [left, right],
+                        ui.painter().round_to_pixel(pointer.x) - x,
+                        i,
+                        Rect::width,
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+                    }
+                }
+                if response.drag_stopped() || response.double_clicked() {
+                    tree.tiles.on_resized(parent_id);
                 }
             }
 
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
+            let stroke = super::resize_stroke(behavior, ui.style(), resize_state, depth);
             ui.painter().vline(x, parent_rect.y_range(), stroke);
         }
     }
@@ -300,100 +891,274 @@ impl Linear {
         tree: &mut Tree<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         drop_context: &mut DropContext,
-        ui: &egui::Ui,
+        ui: &mut egui::Ui,
         parent_id: TileId,
     ) {
-        let visible_children = self.visible_children(&tree.tiles);
+        let visible_children = self.visible_children(|tile_id| tree.tiles.is_visible(tile_id));
 
+        if self.has_sections() {
+            self.section_headers_ui(tree, behavior, ui, parent_id, &visible_children);
+        }
         for &child in &visible_children {
+            if self.is_collapsed(child) {
+                continue;
+            }
+            if !self.has_sections() {
+                super::title_bar_ui(tree, behavior, ui, child);
+            }
             tree.tile_ui(behavior, drop_context, ui, child);
             crate::cover_tile_if_dragged(tree, behavior, ui, child);
         }
 
-        linear_drop_zones(ui.ctx(), tree, &self.children, self.dir, |rect, i| {
-            drop_context.suggest_rect(
-                InsertionPoint::new(parent_id, ContainerInsertion::Vertical(i)),
-                rect,
-            );
-        });
+        if drop_context.enabled {
+            linear_drop_zones(ui.ctx(), tree, &self.children, self.dir, |rect, i| {
+                drop_context.suggest_rect(
+                    InsertionPoint::new(parent_id, ContainerInsertion::Vertical(i)),
+                    rect,
+                );
+            });
+        }
 
         // ------------------------
         // resizing:
 
+        // Only neighboring expanded sections can be resized against each other:
+        let expanded_children: Vec<TileId> = visible_children
+            .iter()
+            .copied()
+            .filter(|&child| !self.is_collapsed(child))
+            .collect();
+        let header_height = self.header_height(behavior, ui.style());
+
         let parent_rect = tree.tiles.rect_or_die(parent_id);
-        for (i, (top, bottom)) in visible_children.iter().copied().tuple_windows().enumerate() {
+        let depth = tree.tiles.ancestors(parent_id).count();
+        for (i, (top, bottom)) in expanded_children
+            .iter()
+            .copied()
+            .tuple_windows()
+            .enumerate()
+        {
+            let are_neighbors = visible_children
+                .iter()
+                .tuple_windows()
+                .any(|(&a, &b)| a == top && b == bottom);
+            if !are_neighbors {
+                continue;
+            }
+
             let resize_id = ui.id().with((parent_id, "resize", i));
 
             let top_rect = tree.tiles.rect_or_die(top);
             let bottom_rect = tree.tiles.rect_or_die(bottom);
-            let y = egui::lerp(top_rect.bottom()..=bottom_rect.top(), 0.5);
+            let bottom_title_bar_top = bottom_rect.top() - header_height;
+            let y = egui::lerp(top_rect.bottom()..=bottom_title_bar_top, 0.5);
 
             let mut resize_state = ResizeState::Idle;
-            let line_rect = Rect::from_center_size(
-                pos2(parent_rect.center().x, y),
-                vec2(
-                    parent_rect.width(),
-                    2.0 * ui.style().interaction.resize_grab_radius_side,
-                ),
-            );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
+            if [parent_id, top, bottom]
+                .iter()
+                .all(|&tile_id| tree.edit_permissions(behavior, tile_id).resize)
+            {
+                let line_rect = Rect::from_center_size(
+                    pos2(parent_rect.center().x, y),
+                    vec2(
+                        parent_rect.width(),
+                        2.0 * ui.style().interaction.resize_grab_radius_side,
+                    ),
+                );
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                self.divider_context_menu(
+                    &mut tree.tiles,
                     behavior,
-                    &mut self.shares,
-                    &visible_children,
                     &response,
+                    parent_id,
                     [top, bottom],
-                    ui.painter().round_to_pixel(pointer.y) - y,
-                    i,
-                    |tile_id: TileId| tree.tiles.rect_or_die(tile_id).height(),
                 );
-
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+                let locked = self.is_divider_locked([top, bottom]);
+                if let Some(steps) =
+                    super::scroll_resize_steps(behavior, ui, line_rect).filter(|_| !locked)
+                {
+                    let shares = [self.shares[top], self.shares[bottom]];
+                    [self.shares[top], self.shares[bottom]] = super::shift_shares(shares, steps);
+                    tree.tiles.on_edit(behavior, EditAction::TileResized);
+                    tree.tiles.on_resized(parent_id);
+                }
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos().filter(|_| !locked) {
+                    resize_state = resize_interaction(
+                        behavior,
+                        &mut tree.tiles,
+                        &mut self.shares,
+                        &expanded_children,
+                        &response,
+                        [top, bottom],
+                        ui.painter().round_to_pixel(pointer.y) - y,
+                        i,
+                        Rect::height,
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+                    }
+                }
+                if response.drag_stopped() || response.double_clicked() {
+                    tree.tiles.on_resized(parent_id);
                 }
             }
 
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
+            let stroke = super::resize_stroke(behavior, ui.style(), resize_state, depth);
             ui.painter().hline(parent_rect.x_range(), y, stroke);
         }
     }
 
-    pub(super) fn simplify_children(&mut self, mut simplify: impl FnMut(TileId) -> SimplifyAction) {
-        self.children.retain_mut(|child| match simplify(*child) {
-            SimplifyAction::Remove => false,
-            SimplifyAction::Keep => true,
-            SimplifyAction::Replace(new) => {
-                self.shares.replace_with(*child, new);
-                *child = new;
-                true
+    /// Right-clicking a divider offers [`DividerAction`]s and whatever
+    /// [`Behavior::divider_context_menu`] adds.
+    fn divider_context_menu<Pane>(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        response: &egui::Response,
+        linear_id: TileId,
+        pair: [TileId; 2],
+    ) {
+        let (collapse_before, collapse_after) = match self.dir {
+            LinearDir::Horizontal => ("Collapse left", "Collapse right"),
+            LinearDir::Vertical => ("Collapse top", "Collapse bottom"),
+        };
+        let lock = if self.is_divider_locked(pair) {
+            "Unlock ratio"
+        } else {
+            "Lock ratio"
+        };
+        let entries = [
+            ("Equalize", DividerAction::Equalize),
+            (collapse_before, DividerAction::CollapseBefore),
+            (collapse_after, DividerAction::CollapseAfter),
+            (lock, DividerAction::ToggleLock),
+        ];
+
+        let mut chosen = None;
+        response.context_menu(|ui| {
+            for (label, action) in entries {
+                if behavior.is_divider_action_allowed(tiles, linear_id, action)
+                    && ui.button(label).clicked()
+                {
+                    chosen = Some(action);
+                    ui.close_menu();
+                }
             }
+            behavior.divider_context_menu(tiles, ui, linear_id, pair);
         });
+
+        if let Some(action) = chosen {
+            if self.apply_divider_action(tiles, behavior, action, pair) {
+                tiles.on_edit(behavior, EditAction::TileResized);
+                tiles.on_resized(linear_id);
+            }
+        }
+    }
+
+    /// Show the section header above each child, see [`Self::collapsible`].
+    ///
+    /// Folded children have no rect, so we walk down from the top of the container,
+    /// just like [`Self::layout_vertical`] did.
+    fn section_headers_ui<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut egui::Ui,
+        parent_id: TileId,
+        visible_children: &[TileId],
+    ) {
+        let parent_rect = tree.tiles.rect_or_die(parent_id);
+        let depth = tree.tiles.ancestors(parent_id).count();
+        let gap_height = behavior.gap_width_at_depth(ui.style(), depth);
+        let header_height = behavior.section_header_height(ui.style());
+
+        let mut y = parent_rect.top() - self.scroll_offset;
+        for &child in visible_children {
+            let header_rect = Rect::from_min_size(
+                pos2(parent_rect.left(), y),
+                vec2(parent_rect.width(), header_height),
+            );
+            y = match tree.tiles.rect(child) {
+                Some(child_rect) if !self.is_collapsed(child) => child_rect.bottom(),
+                _ => header_rect.bottom(),
+            } + gap_height;
+
+            let collapsed = self.is_collapsed(child);
+            let id = ui.id().with((parent_id, "section", child));
+            let mut header_ui = ui.new_child(egui::UiBuilder::new().max_rect(header_rect));
+            let response =
+                behavior.section_header_ui(&mut tree.tiles, &mut header_ui, id, child, collapsed);
+            if response.clicked() {
+                self.set_collapsed(child, !collapsed);
+                tree.tiles.on_edit(behavior, EditAction::SectionToggled);
+            }
+        }
+    }
+
+    pub(super) fn simplify_children(&mut self, mut simplify: impl FnMut(TileId) -> SimplifyAction) {
+        let mut index = 0;
+        while let Some(&child) = self.children.get(index) {
+            match simplify(child) {
+                SimplifyAction::Remove => {
+                    self.remove_at(index);
+                }
+                SimplifyAction::Keep => {
+                    index += 1;
+                }
+                SimplifyAction::Replace(new) => {
+                    self.shares.replace_with(child, new);
+                    if self.collapsed.remove(&child) {
+                        self.collapsed.insert(new);
+                    }
+                    self.children[index] = new;
+                    index += 1;
+                }
+            }
+        }
     }
 
     /// Returns child index, if found.
     pub(crate) fn remove_child(&mut self, needle: TileId) -> Option<usize> {
         let index = self.children.iter().position(|&child| child == needle)?;
-        self.children.remove(index);
+        self.remove_at(index);
         Some(index)
     }
+
+    /// Remove the child at the given index, handing its share over
+    /// according to [`Self::share_redistribution`].
+    fn remove_at(&mut self, index: usize) {
+        let removed = self.children.remove(index);
+        let next = (index < self.children.len()).then_some(index);
+        let heir = match self.share_redistribution {
+            ShareRedistribution::Proportional => None,
+            ShareRedistribution::PreviousSibling => index.checked_sub(1).or(next),
+            ShareRedistribution::NextSibling => next.or(index.checked_sub(1)),
+        };
+        if let Some(heir) = heir {
+            let share = self.shares[removed];
+            self.shares[self.children[heir]] += share;
+            self.shares.retain(|child| child != removed);
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
 fn resize_interaction<Pane>(
     behavior: &mut dyn Behavior<Pane>,
+    tiles: &mut Tiles<Pane>,
     shares: &mut Shares,
     children: &[TileId],
     splitter_response: &egui::Response,
     [left, right]: [TileId; 2],
     dx: f32,
     i: usize,
-    tile_width: impl Fn(TileId) -> f32,
+    size_of_rect: fn(&Rect) -> f32,
 ) -> ResizeState {
     if splitter_response.double_clicked() {
-        behavior.on_edit(EditAction::TileResized);
+        tiles.on_edit(behavior, EditAction::TileResized);
 
         // double-click to center the split between left and right:
         let mean = 0.5 * (shares[left] + shares[right]);
@@ -401,7 +1166,8 @@ fn resize_interaction<Pane>(
         shares[right] = mean;
         ResizeState::Hovering
     } else if splitter_response.dragged() {
-        behavior.on_edit(EditAction::TileResized);
+        tiles.on_edit(behavior, EditAction::TileResized);
+        let tile_width = |tile_id: TileId| size_of_rect(&tiles.rect_or_die(tile_id));
 
         if dx < 0.0 {
             // Expand right, shrink stuff to the left:
@@ -476,8 +1242,13 @@ fn linear_drop_zones<Pane>(
     let dragged_index = children
         .iter()
         .position(|&child| is_being_dragged(egui_ctx, tree.id, child));
+    let mirrored = tree.right_to_left && dir == LinearDir::Horizontal;
 
     let after_rect = |rect: Rect| match dir {
+        LinearDir::Horizontal if mirrored => Rect::from_min_max(
+            rect.left_top(),
+            rect.left_bottom() + vec2(preview_thickness, 0.0),
+        ),
         LinearDir::Horizontal => Rect::from_min_max(
             rect.right_top() - vec2(preview_thickness, 0.0),
             rect.right_bottom(),
@@ -493,6 +1264,7 @@ fn linear_drop_zones<Pane>(
         children,
         dragged_index,
         dir,
+        mirrored,
         |tile_id| tree.tiles.rect(tile_id),
         add_drop_drect,
         after_rect,
@@ -502,16 +1274,23 @@ fn linear_drop_zones<Pane>(
 /// Register drop-zones for a linear container.
 ///
 /// `get_rect`: return `None` for invisible tiles.
+/// If `mirrored`, horizontal children go right-to-left.
+#[allow(clippy::too_many_arguments)]
 pub(super) fn drop_zones(
     preview_thickness: f32,
     children: &[TileId],
     dragged_index: Option<usize>,
     dir: LinearDir,
+    mirrored: bool,
     get_rect: impl Fn(TileId) -> Option<Rect>,
     mut add_drop_drect: impl FnMut(Rect, usize),
     after_rect: impl Fn(Rect) -> Rect,
 ) {
     let before_rect = |rect: Rect| match dir {
+        LinearDir::Horizontal if mirrored => Rect::from_min_max(
+            rect.right_top() - vec2(preview_thickness, 0.0),
+            rect.right_bottom(),
+        ),
         LinearDir::Horizontal => Rect::from_min_max(
             rect.left_top(),
             rect.left_bottom() + vec2(preview_thickness, 0.0),
@@ -522,6 +1301,10 @@ pub(super) fn drop_zones(
         ),
     };
     let between_rects = |a: Rect, b: Rect| match dir {
+        LinearDir::Horizontal if mirrored => Rect::from_center_size(
+            a.left_center().lerp(b.right_center(), 0.5),
+            vec2(preview_thickness, a.height()),
+        ),
         LinearDir::Horizontal => Rect::from_center_size(
             a.right_center().lerp(b.left_center(), 0.5),
             vec2(preview_thickness, a.height()),
@@ -1,15 +1,16 @@
-use egui::Rect;
+use egui::{vec2, Rect};
 
-use crate::Tree;
+use crate::{is_being_dragged, tiles::LayoutContext, EditAction, TabState, Tree};
 
-use super::{Behavior, DropContext, SimplifyAction, TileId, Tiles};
+use super::{Behavior, DropContext, ResizeState, SimplifyAction, TileId};
 
 mod grid;
+mod grid_areas;
 mod linear;
 mod tabs;
 
-pub use grid::{Grid, GridLayout};
-pub use linear::{Linear, LinearDir, Shares};
+pub use grid::{Grid, GridEdit, GridGutter, GridLayout};
+pub use linear::{DividerAction, Linear, LinearDir, LinearOverflow, ShareRedistribution, Shares};
 pub use tabs::Tabs;
 
 // ----------------------------------------------------------------------------
@@ -17,7 +18,7 @@ pub use tabs::Tabs;
 /// The layout type of a [`Container`].
 ///
 /// This is used to describe a [`Container`], and to change it to a different layout type.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum ContainerKind {
     /// Each child in an individual tab.
@@ -36,6 +37,38 @@ pub enum ContainerKind {
 
 impl ContainerKind {
     pub const ALL: [Self; 4] = [Self::Tabs, Self::Horizontal, Self::Vertical, Self::Grid];
+
+    /// A short name to show the user, e.g. "Horizontal".
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tabs => "Tabs",
+            Self::Horizontal => "Horizontal",
+            Self::Vertical => "Vertical",
+            Self::Grid => "Grid",
+        }
+    }
+
+    /// A small menu button showing this kind, that lets the user pick another one.
+    ///
+    /// Fits in [`crate::Behavior::top_bar_right_ui`] or a context menu.
+    /// Returns the kind the user switched to, if any.
+    /// Apply it with [`Container::set_kind`], which keeps the children.
+    /// Where you only have a `&Tiles` (like in the tab bar), remember the choice
+    /// and apply it after [`crate::Tree::ui`].
+    pub fn switcher_ui(self, ui: &mut egui::Ui) -> Option<Self> {
+        let mut picked = None;
+        ui.menu_button(self.name(), |ui| {
+            for kind in Self::ALL {
+                if ui.selectable_label(kind == self, kind.name()).clicked() {
+                    picked = Some(kind).filter(|&kind| kind != self);
+                    ui.close_menu();
+                }
+            }
+        })
+        .response
+        .on_hover_text("Change the layout");
+        picked
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -100,6 +133,23 @@ impl Container {
         Self::Grid(Grid::new(children))
     }
 
+    /// Like `==`, but ignoring view state, such as the scroll offset of a [`Linear`].
+    pub(crate) fn layout_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Linear(a), Self::Linear(b)) => a.layout_eq(b),
+            _ => self == other,
+        }
+    }
+
+    /// Replace every [`TileId`] this refers to, e.g. when copying a whole subtree.
+    pub(crate) fn map_tile_ids(&mut self, f: impl Fn(TileId) -> TileId) {
+        match self {
+            Self::Tabs(tabs) => tabs.map_tile_ids(f),
+            Self::Linear(linear) => linear.map_tile_ids(f),
+            Self::Grid(grid) => grid.map_tile_ids(f),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.num_children() == 0
     }
@@ -212,6 +262,22 @@ impl Container {
         };
     }
 
+    /// Replace any non-finite or non-positive shares with the default share.
+    pub(crate) fn reset_invalid_shares(&mut self) {
+        let is_valid = |share: f32| share.is_finite() && 0.0 < share;
+        match self {
+            Self::Tabs(_) => {}
+            Self::Linear(linear) => linear.shares.retain_valid(is_valid),
+            Self::Grid(grid) => {
+                for share in grid.col_shares.iter_mut().chain(&mut grid.row_shares) {
+                    if !is_valid(*share) {
+                        *share = 1.0;
+                    }
+                }
+            }
+        }
+    }
+
     pub(super) fn simplify_children(&mut self, simplify: impl FnMut(TileId) -> SimplifyAction) {
         match self {
             Self::Tabs(tabs) => tabs.simplify_children(simplify),
@@ -222,7 +288,7 @@ impl Container {
 
     pub(super) fn layout<Pane>(
         &mut self,
-        tiles: &mut Tiles<Pane>,
+        ctx: &mut LayoutContext<'_>,
         style: &egui::Style,
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
@@ -232,11 +298,23 @@ impl Container {
         }
 
         match self {
-            Self::Tabs(tabs) => tabs.layout(tiles, style, behavior, rect),
+            Self::Tabs(tabs) => tabs.layout(ctx, style, behavior, rect),
             Self::Linear(linear) => {
-                linear.layout(tiles, style, behavior, rect);
+                linear.layout(ctx, style, behavior, rect);
             }
-            Self::Grid(grid) => grid.layout(tiles, style, behavior, rect),
+            Self::Grid(grid) => grid.layout(ctx, style, behavior, rect),
+        }
+    }
+
+    /// Feed everything that affects how this container lays out its children to the hasher.
+    pub(super) fn hash_layout(&self, state: &mut impl std::hash::Hasher) {
+        use std::hash::Hash as _;
+
+        self.kind().hash(state);
+        match self {
+            Self::Tabs(tabs) => tabs.hash_layout(state),
+            Self::Linear(linear) => linear.hash_layout(state),
+            Self::Grid(grid) => grid.hash_layout(state),
         }
     }
 
@@ -260,5 +338,121 @@ impl Container {
                 grid.ui(tree, behavior, drop_context, ui, tile_id);
             }
         }
+
+        if self.is_empty() && tree.edit_permissions(behavior, tile_id).drop_into {
+            let placeholder_rect = match self {
+                Self::Tabs(_) => rect.with_min_y(rect.top() + behavior.tab_bar_height(ui.style())),
+                Self::Linear(_) | Self::Grid(_) => rect,
+            };
+            if let Some(pane) = crate::pane_factory::add_pane_placeholder(
+                &mut tree.tiles,
+                behavior,
+                ui,
+                placeholder_rect,
+            ) {
+                self.add_child(pane);
+                if let Self::Tabs(tabs) = self {
+                    tabs.set_active(pane);
+                }
+            }
+        }
+    }
+}
+
+/// Show the title bar above a child of a [`Linear`] or [`Grid`],
+/// in the space left for it during layout. See [`Behavior::title_bar_height`].
+fn title_bar_ui<Pane>(
+    tree: &mut Tree<Pane>,
+    behavior: &mut dyn Behavior<Pane>,
+    ui: &mut egui::Ui,
+    child: TileId,
+) {
+    let height = behavior.title_bar_height(ui.style());
+    if height <= 0.0 {
+        return;
+    }
+    let Some(child_rect) = tree.tiles.rect(child) else {
+        return;
+    };
+    let title_bar_rect = Rect::from_min_max(
+        child_rect.left_top() - vec2(0.0, height),
+        child_rect.right_top(),
+    );
+
+    let permissions = tree.edit_permissions(behavior, child);
+    let state = TabState {
+        active: true,
+        is_being_dragged: is_being_dragged(ui.ctx(), tree.id, child),
+        closable: permissions.close && behavior.is_tab_closable(&tree.tiles, child),
+        draggable: permissions.drag,
+        multi_selected: false,
+        matches_filter: tree.matches_filter(child),
+    };
+
+    // Same id as a tab button, so dragging the title bar drags the tile:
+    let id = child.egui_id(tree.id);
+    let mut title_bar_ui = ui.new_child(egui::UiBuilder::new().max_rect(title_bar_rect));
+    let response = behavior.title_bar_ui(&mut tree.tiles, &mut title_bar_ui, id, child, &state);
+    if response.drag_started() {
+        tree.tiles.on_edit(behavior, EditAction::TileDragged);
+    }
+}
+
+/// How many steps to move the line in `divider_rect` by, from scrolling over it
+/// with [`Behavior::scroll_resize_modifiers`] held. Positive means right or down.
+fn scroll_resize_steps<Pane>(
+    behavior: &dyn Behavior<Pane>,
+    ui: &egui::Ui,
+    divider_rect: Rect,
+) -> Option<f32> {
+    let modifiers = behavior.scroll_resize_modifiers()?;
+    if !ui.rect_contains_pointer(divider_rect) {
+        return None;
+    }
+    let steps: f32 = ui.input(|i| {
+        i.events
+            .iter()
+            .filter_map(|event| match event {
+                egui::Event::MouseWheel {
+                    delta,
+                    modifiers: held,
+                    ..
+                } if held.matches_logically(modifiers) => {
+                    // Scrolling down (or right, on a trackpad) moves the line forward:
+                    let delta = delta.x + delta.y;
+                    (delta != 0.0).then(|| -delta.signum())
+                }
+                _ => None,
+            })
+            .sum()
+    });
+    (steps != 0.0).then_some(steps)
+}
+
+/// Move some share from one side of a line to the other, by the given number of steps.
+///
+/// Each step is a twentieth of the pair, and neither side shrinks below one step.
+fn shift_shares([before, after]: [f32; 2], steps: f32) -> [f32; 2] {
+    const STEP: f32 = 0.05;
+    let total = before + after;
+    let before = (before + steps * STEP * total).clamp(STEP * total, (1.0 - STEP) * total);
+    [before, total - before]
+}
+
+/// The line between two children of a container at the given nesting depth.
+fn resize_stroke<Pane>(
+    behavior: &dyn Behavior<Pane>,
+    style: &egui::Style,
+    resize_state: ResizeState,
+    depth: usize,
+) -> egui::Stroke {
+    match resize_state {
+        ResizeState::Idle => egui::Stroke::new(
+            behavior.gap_width_at_depth(style, depth),
+            behavior.gap_color(style, depth),
+        ),
+        ResizeState::Hovering | ResizeState::Dragging => {
+            behavior.resize_stroke(style, resize_state)
+        }
     }
 }
@@ -1,9 +1,10 @@
-use egui::{scroll_area::ScrollBarVisibility, vec2, NumExt, Rect, Vec2};
+use egui::{scroll_area::ScrollBarVisibility, vec2, Color32, NumExt, Rect, Vec2};
 
-use crate::behavior::{EditAction, TabState};
+use crate::behavior::{EditAction, TabState, TabSwitchAnimation};
+use crate::tiles::LayoutContext;
 use crate::{
     is_being_dragged, Behavior, ContainerInsertion, DropContext, InsertionPoint, SimplifyAction,
-    TileId, Tiles, Tree,
+    TileId, Tiles, Tree, TreeEvent,
 };
 
 /// Fixed size icons for `⏴` and `⏵`
@@ -17,6 +18,7 @@ pub struct Tabs {
     pub children: Vec<TileId>,
 
     /// The currently open tab.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub active: Option<TileId>,
 }
 
@@ -39,6 +41,10 @@ struct ScrollState {
     /// The available size for the tabs.
     pub available: Vec2,
 
+    /// The width of just the tabs last frame,
+    /// without the space that pushes them to the right in a right-to-left tree.
+    pub tabs_width: f32,
+
     /// Show the left scroll-arrow this frame?
     pub show_left_arrow: bool,
 
@@ -130,6 +136,51 @@ impl ScrollState {
     }
 }
 
+/// The last time the active tab of a [`Tabs`] container changed, stored in egui memory.
+#[derive(Clone, Copy, Debug)]
+struct TabSwitch {
+    previous: TileId,
+    active: TileId,
+    start_time: f64,
+}
+
+impl TabSwitch {
+    /// If we are still switching to the `active` tab, returns the previous tab
+    /// and how far along (0-1) the switch is.
+    fn progress(
+        ui: &egui::Ui,
+        id: egui::Id,
+        active: TileId,
+        duration: f32,
+    ) -> Option<(TileId, f32)> {
+        let now = ui.input(|i| i.time);
+        let switch = ui.data_mut(|data| {
+            let switch = data.get_temp_mut_or_insert_with(id, || Self {
+                previous: active,
+                active,
+                start_time: f64::NEG_INFINITY,
+            });
+            if switch.active != active {
+                *switch = Self {
+                    previous: switch.active,
+                    active,
+                    start_time: now,
+                };
+            }
+            *switch
+        });
+
+        let t = if 0.0 < duration {
+            (now - switch.start_time) as f32 / duration
+        } else {
+            1.0
+        };
+        (switch.previous != active && t < 1.0).then_some((switch.previous, t.max(0.0)))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 impl Tabs {
     pub fn new(children: Vec<TileId>) -> Self {
         let active = children.first().copied();
@@ -148,17 +199,33 @@ impl Tabs {
         Some(child) == self.active
     }
 
+    pub(super) fn hash_layout(&self, state: &mut impl std::hash::Hasher) {
+        use std::hash::Hash as _;
+
+        self.children.hash(state);
+        self.active.hash(state);
+    }
+
+    /// Replace every [`TileId`] this refers to, e.g. when copying a whole subtree.
+    pub(crate) fn map_tile_ids(&mut self, f: impl Fn(TileId) -> TileId) {
+        let Self { children, active } = self;
+        for child in children {
+            *child = f(*child);
+        }
+        *active = active.map(f);
+    }
+
     pub(super) fn layout<Pane>(
         &mut self,
-        tiles: &mut Tiles<Pane>,
+        ctx: &mut LayoutContext<'_>,
         style: &egui::Style,
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
     ) {
         let prev_active = self.active;
-        self.ensure_active(tiles);
+        self.ensure_active_by(|tile_id| ctx.is_visible(tile_id));
         if prev_active != self.active {
-            behavior.on_edit(EditAction::TabSelected);
+            ctx.on_edit(behavior, EditAction::TabSelected);
         }
 
         let mut active_rect = rect;
@@ -166,14 +233,18 @@ impl Tabs {
 
         if let Some(active) = self.active {
             // Only lay out the active tab (saves CPU):
-            tiles.layout_tile(style, behavior, active_rect, active);
+            ctx.layout_child(active, active_rect);
         }
     }
 
     /// Make sure we have an active tab (or no visible tabs).
     pub fn ensure_active<Pane>(&mut self, tiles: &Tiles<Pane>) {
+        self.ensure_active_by(|tile_id| tiles.is_visible(tile_id));
+    }
+
+    fn ensure_active_by(&mut self, is_visible: impl Fn(TileId) -> bool) {
         if let Some(active) = self.active {
-            if !tiles.is_visible(active) {
+            if !is_visible(active) {
                 self.active = None;
             }
         }
@@ -184,7 +255,7 @@ impl Tabs {
                 .children
                 .iter()
                 .copied()
-                .find(|&child_id| tiles.is_visible(child_id));
+                .find(|&child_id| is_visible(child_id));
         }
     }
 
@@ -199,19 +270,146 @@ impl Tabs {
     ) {
         let next_active = self.tab_bar_ui(tree, behavior, ui, rect, drop_context, tile_id);
 
+        let mut content_rect = rect;
+        content_rect.min.y += behavior.tab_bar_height(ui.style());
+
+        let mut switching_from = None;
         if let Some(active) = self.active {
-            tree.tile_ui(behavior, drop_context, ui, active);
+            switching_from =
+                self.active_tab_ui(tree, behavior, drop_context, ui, content_rect, tile_id);
             crate::cover_tile_if_dragged(tree, behavior, ui, active);
         }
 
+        // Panes behind inactive tabs normally get no ui at all, unless they ask for it:
+        for &child in &self.children {
+            if Some(child) != self.active && Some(child) != switching_from && tree.is_visible(child)
+            {
+                tree.inactive_pane_ui(behavior, ui, content_rect, child);
+            }
+        }
+
+        if next_active != self.active {
+            if let Some(tab) = next_active {
+                tree.tiles
+                    .push_event(TreeEvent::TabActivated { tabs: tile_id, tab });
+                if tree.activate_linked_tabs(tab) {
+                    ui.ctx().request_repaint(); // Containers shown before this one changed
+                }
+            }
+        }
+
         // We have only laid out the active tab, so we need to switch active tab _after_ the ui pass above:
         self.active = next_active;
     }
 
+    /// Show the active tab, animating the switch from the previous one if needed.
+    ///
+    /// Returns the previous tab, if it was shown too.
+    fn active_tab_ui<Pane>(
+        &self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        drop_context: &mut DropContext,
+        ui: &mut egui::Ui,
+        content_rect: Rect,
+        tile_id: TileId,
+    ) -> Option<TileId> {
+        let active = self.active?;
+
+        let animation = behavior.tab_switch_animation();
+        let switch = if animation == TabSwitchAnimation::None {
+            None
+        } else {
+            let id = tile_id.egui_id(tree.id).with("tab_switch");
+            TabSwitch::progress(ui, id, active, behavior.tab_switch_duration(ui.style()))
+        };
+        let switch = switch.filter(|&(previous, _)| {
+            self.children.contains(&previous)
+                && tree.is_visible(previous)
+                && tree.tiles.get(previous).is_some()
+        });
+
+        let Some((previous, t)) = switch else {
+            tree.tile_ui(behavior, drop_context, ui, active);
+            return None;
+        };
+
+        ui.ctx().request_repaint();
+        let t = behavior.animation_easing(t);
+
+        let (previous_rect, active_rect) = if animation == TabSwitchAnimation::Slide {
+            // Move towards the side the new tab came from in the tab bar:
+            let index_of = |tile_id| self.children.iter().position(|&child| child == tile_id);
+            let direction = if index_of(active) > index_of(previous) {
+                -1.0
+            } else {
+                1.0
+            };
+            let dx = direction * content_rect.width() * t;
+            (
+                content_rect.translate(vec2(dx, 0.0)),
+                content_rect.translate(vec2(dx - direction * content_rect.width(), 0.0)),
+            )
+        } else {
+            (content_rect, content_rect)
+        };
+
+        // The previous tab was not laid out, since it is no longer active:
+        tree.tiles.layout_tile(
+            ui.style(),
+            tree.layout_params(ui.ctx().pixels_per_point()),
+            behavior,
+            previous_rect,
+            None,
+            previous,
+        );
+        if active_rect != content_rect {
+            tree.tiles.layout_tile(
+                ui.style(),
+                tree.layout_params(ui.ctx().pixels_per_point()),
+                behavior,
+                active_rect,
+                None,
+                active,
+            );
+        }
+
+        let mut clip_ui = ui.new_child(egui::UiBuilder::new().max_rect(content_rect));
+        clip_ui.set_clip_rect(content_rect.intersect(ui.clip_rect()));
+
+        {
+            let mut previous_ui = clip_ui.new_child(
+                egui::UiBuilder::new()
+                    .id_salt(("tab_switch_previous", previous))
+                    .max_rect(previous_rect),
+            );
+            // The previous tab is on its way out, so it shouldn't be interacted with:
+            previous_ui.disable();
+            if animation == TabSwitchAnimation::Crossfade {
+                previous_ui.set_opacity(1.0 - t);
+            }
+            tree.tile_ui(
+                behavior,
+                &mut DropContext::disabled(),
+                &previous_ui,
+                previous,
+            );
+        }
+        {
+            let mut active_ui = clip_ui.new_child(egui::UiBuilder::new().max_rect(active_rect));
+            if animation == TabSwitchAnimation::Crossfade {
+                active_ui.set_opacity(t);
+            }
+            tree.tile_ui(behavior, drop_context, &active_ui, active);
+        }
+
+        Some(previous)
+    }
+
     /// Returns the next active tab (e.g. the one clicked, or the current).
     #[allow(clippy::too_many_lines)]
     fn tab_bar_ui<Pane>(
-        &self,
+        &mut self,
         tree: &mut Tree<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         ui: &mut egui::Ui,
@@ -225,13 +423,25 @@ impl Tabs {
         let tab_bar_rect = rect.split_top_bottom_at_y(rect.top() + tab_bar_height).0;
         let mut ui = ui.new_child(egui::UiBuilder::new().max_rect(tab_bar_rect));
 
-        let mut button_rects = ahash::HashMap::default();
+        let mut button_rects = std::mem::take(&mut drop_context.tab_button_rects);
+        button_rects.clear();
         let mut dragged_index = None;
+        let mut close_selection = false;
+        let mut close_all = false;
+        let mut added_pane = None;
+
+        // Right-to-left mirrors the whole bar, with the buttons on the left:
+        let rtl = tree.right_to_left;
+        let buttons_layout = if rtl {
+            egui::Layout::left_to_right(egui::Align::Center)
+        } else {
+            egui::Layout::right_to_left(egui::Align::Center)
+        };
 
         ui.painter()
             .rect_filled(ui.max_rect(), 0.0, behavior.tab_bar_color(ui.visuals()));
 
-        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+        ui.with_layout(buttons_layout, |ui| {
             let scroll_state_id = ui.make_persistent_id(tile_id);
             let mut scroll_state = ui.ctx().memory_mut(|m| {
                 m.data
@@ -239,20 +449,43 @@ impl Tabs {
                     .unwrap_or_default()
             });
 
+            if tree.edit_permissions(behavior, tile_id).close
+                && self
+                    .children
+                    .iter()
+                    .any(|&child| tree.may_close_tab(behavior, child))
+                && behavior.close_all_tabs_button(&tree.tiles, tile_id)
+            {
+                close_all = ui
+                    .small_button("🗙")
+                    .on_hover_text("Close all tabs")
+                    .clicked();
+            }
+
+            if tree.edit_permissions(behavior, tile_id).drop_into {
+                added_pane = crate::pane_factory::add_pane_button(&mut tree.tiles, behavior, ui);
+            }
+
             // Allow user to add buttons such as "add new tab".
             // They can also read and modify the scroll state if they want.
             behavior.top_bar_right_ui(&tree.tiles, ui, tile_id, self, &mut scroll_state.offset);
 
             let scroll_area_width = scroll_state.update(ui);
 
-            // We're in a right-to-left layout, so start with the right scroll-arrow:
-            scroll_state.right_arrow(ui);
+            // Start with the scroll-arrow next to the buttons:
+            if rtl {
+                scroll_state.left_arrow(ui);
+            } else {
+                scroll_state.right_arrow(ui);
+            }
 
             ui.allocate_ui_with_layout(
                 ui.available_size(),
                 egui::Layout::left_to_right(egui::Align::Center),
                 |ui| {
-                    scroll_state.left_arrow(ui);
+                    if !rtl {
+                        scroll_state.left_arrow(ui);
+                    }
 
                     // Prepare to show the scroll area with the tabs:
 
@@ -267,8 +500,15 @@ impl Tabs {
                         .auto_shrink([false; 2])
                         .horizontal_scroll_offset(scroll_state.offset);
 
+                    // Push the tabs to the right, if they fit:
+                    let padding = if rtl {
+                        (scroll_area_width - scroll_state.tabs_width).at_least(0.0)
+                    } else {
+                        0.0
+                    };
+
                     let output = scroll_area.show(ui, |ui| {
-                        if !tree.is_root(tile_id) {
+                        if !tree.is_root(tile_id) && tree.edit_permissions(behavior, tile_id).drag {
                             // Make the background behind the buttons draggable (to drag the parent container tile).
                             // We also sense clicks to avoid eager-dragging on mouse-down.
                             let sense = egui::Sense::click_and_drag();
@@ -277,34 +517,66 @@ impl Tabs {
                                 .on_hover_cursor(egui::CursorIcon::Grab)
                                 .drag_started()
                             {
-                                behavior.on_edit(EditAction::TileDragged);
+                                tree.tiles.on_edit(behavior, EditAction::TileDragged);
                                 ui.ctx().set_dragged_id(tile_id.egui_id(tree.id));
                             }
                         }
 
                         ui.spacing_mut().item_spacing.x = 0.0; // Tabs have spacing built-in
+                        ui.add_space(padding);
+
+                        // In the order they are shown, left to right:
+                        let mut children: Vec<(usize, TileId)> =
+                            self.children.iter().copied().enumerate().collect();
+                        if rtl {
+                            children.reverse();
+                        }
 
-                        for (i, &child_id) in self.children.iter().enumerate() {
+                        for (i, child_id) in children {
                             if !tree.is_visible(child_id) {
                                 continue;
                             }
+                            let matches_filter = tree.matches_filter(child_id);
+                            if matches_filter == Some(false)
+                                && behavior.hide_filtered_tabs()
+                                && !self.is_active(child_id)
+                            {
+                                continue;
+                            }
 
                             let is_being_dragged = is_being_dragged(ui.ctx(), tree.id, child_id);
 
                             let selected = self.is_active(child_id);
                             let id = child_id.egui_id(tree.id);
+                            let permissions = tree.edit_permissions(behavior, child_id);
                             let tab_state = TabState {
                                 active: selected,
                                 is_being_dragged,
-                                closable: behavior.is_tab_closable(&tree.tiles, child_id),
+                                closable: permissions.close
+                                    && behavior.is_tab_closable(&tree.tiles, child_id),
+                                draggable: permissions.drag,
+                                multi_selected: tree.is_tab_selected(child_id),
+                                matches_filter,
                             };
 
                             let response =
                                 behavior.tab_ui(&mut tree.tiles, ui, id, child_id, &tab_state);
 
                             if response.clicked() {
-                                behavior.on_edit(EditAction::TabSelected);
+                                tree.tiles.on_edit(behavior, EditAction::TabSelected);
                                 next_active = Some(child_id);
+                                let modifiers = ui.input(|i| i.modifiers);
+                                tree.on_tab_clicked(
+                                    tile_id,
+                                    &self.children,
+                                    self.active,
+                                    child_id,
+                                    modifiers,
+                                );
+                            }
+                            if tab_state.multi_selected && tree.tiles.get(child_id).is_none() {
+                                // Closed with its close button, so close the rest too:
+                                close_selection = true;
                             }
 
                             if let Some(mouse_pos) = drop_context.mouse_pos {
@@ -312,12 +584,14 @@ impl Tabs {
                                     && response.rect.contains(mouse_pos)
                                 {
                                     // Expand this tab - maybe the user wants to drop something into it!
-                                    behavior.on_edit(EditAction::TabSelected);
+                                    tree.tiles.on_edit(behavior, EditAction::TabSelected);
                                     next_active = Some(child_id);
                                 }
                             }
 
-                            button_rects.insert(child_id, response.rect);
+                            if drop_context.enabled {
+                                button_rects.insert(child_id, response.rect);
+                            }
                             if is_being_dragged {
                                 dragged_index = Some(i);
                             }
@@ -327,6 +601,40 @@ impl Tabs {
                     scroll_state.offset = output.state.offset.x;
                     scroll_state.content_size = output.content_size;
                     scroll_state.available = output.inner_rect.size();
+                    scroll_state.tabs_width = output.content_size.x - padding;
+
+                    // Hint at the tabs that are scrolled out of view:
+                    let fade_width = behavior
+                        .tab_bar_fade_width(ui.visuals())
+                        .at_most(output.inner_rect.width() / 2.0);
+                    if 0.0 < fade_width {
+                        let color = behavior.tab_bar_color(ui.visuals());
+                        let inner_rect = output.inner_rect;
+                        if 0.0 < scroll_state.offset {
+                            let mut fade_rect = inner_rect;
+                            fade_rect.max.x = inner_rect.left() + fade_width;
+                            paint_horizontal_fade(
+                                ui.painter(),
+                                fade_rect,
+                                color,
+                                Color32::TRANSPARENT,
+                            );
+                        }
+                        if scroll_state.offset + inner_rect.width() < scroll_state.content_size.x {
+                            let mut fade_rect = inner_rect;
+                            fade_rect.min.x = inner_rect.right() - fade_width;
+                            paint_horizontal_fade(
+                                ui.painter(),
+                                fade_rect,
+                                Color32::TRANSPARENT,
+                                color,
+                            );
+                        }
+                    }
+
+                    if rtl {
+                        scroll_state.right_arrow(ui);
+                    }
                 },
             );
 
@@ -334,9 +642,25 @@ impl Tabs {
                 .data_mut(|data| data.insert_temp(scroll_state_id, scroll_state));
         });
 
+        if let Some(pane) = added_pane {
+            self.add_child(pane);
+            next_active = Some(pane);
+        }
+        if close_selection {
+            tree.close_selected_tabs(behavior);
+        }
+        if close_all {
+            tree.close_tabs(behavior, self.children.clone());
+        }
+
         // -----------
         // Drop zones:
 
+        if !drop_context.enabled {
+            drop_context.tab_button_rects = button_rects;
+            return next_active;
+        }
+
         let preview_thickness = 6.0;
         let after_rect = |rect: Rect| {
             let dragged_size = if let Some(dragged_index) = dragged_index {
@@ -345,16 +669,20 @@ impl Tabs {
             } else {
                 rect.size() // guess that the size is the same as the last button
             };
-            Rect::from_min_size(
-                rect.right_top() + vec2(ui.spacing().item_spacing.x, 0.0),
-                dragged_size,
-            )
+            let spacing = ui.spacing().item_spacing.x;
+            if rtl {
+                let right_top = rect.left_top() - vec2(spacing, 0.0);
+                Rect::from_min_size(right_top - vec2(dragged_size.x, 0.0), dragged_size)
+            } else {
+                Rect::from_min_size(rect.right_top() + vec2(spacing, 0.0), dragged_size)
+            }
         };
         super::linear::drop_zones(
             preview_thickness,
             &self.children,
             dragged_index,
             super::LinearDir::Horizontal,
+            rtl,
             |tile_id| button_rects.get(&tile_id).copied(),
             |rect, i| {
                 drop_context.suggest_rect(
@@ -365,6 +693,43 @@ impl Tabs {
             after_rect,
         );
 
+        // Dropping on the outer edges of the tab bar splits the whole container:
+        let margin = behavior
+            .tab_bar_split_margin(ui.style())
+            .at_most(tab_bar_rect.height() / 2.0);
+        if 0.0 < margin {
+            let bar = tab_bar_rect;
+            let (left, right) = rect.split_left_right_at_fraction(0.5);
+            let (top, bottom) = rect.split_top_bottom_at_fraction(0.5);
+            let mut first = (bar.with_max_x(bar.left() + margin), left);
+            let mut last = (bar.with_min_x(bar.right() - margin), right);
+            if rtl {
+                std::mem::swap(&mut first, &mut last);
+            }
+            let edges = [
+                (ContainerInsertion::Horizontal(0), first.0, first.1),
+                (ContainerInsertion::Horizontal(usize::MAX), last.0, last.1),
+                (
+                    ContainerInsertion::Vertical(0),
+                    bar.with_max_y(bar.top() + margin),
+                    top,
+                ),
+                (
+                    ContainerInsertion::Vertical(usize::MAX),
+                    bar.with_min_y(bar.bottom() - margin),
+                    bottom,
+                ),
+            ];
+            for (insertion, zone, preview_rect) in edges {
+                drop_context.suggest_zone(
+                    InsertionPoint::new(tile_id, insertion),
+                    zone,
+                    preview_rect,
+                );
+            }
+        }
+
+        drop_context.tab_button_rects = button_rects;
         next_active
     }
 
@@ -389,3 +754,15 @@ impl Tabs {
         Some(index)
     }
 }
+
+/// Paint `rect` with a gradient going from `left` to `right`.
+fn paint_horizontal_fade(painter: &egui::Painter, rect: Rect, left: Color32, right: Color32) {
+    let mut mesh = egui::Mesh::default();
+    mesh.colored_vertex(rect.left_top(), left);
+    mesh.colored_vertex(rect.right_top(), right);
+    mesh.colored_vertex(rect.left_bottom(), left);
+    mesh.colored_vertex(rect.right_bottom(), right);
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(1, 2, 3);
+    painter.add(mesh);
+}
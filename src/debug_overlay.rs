@@ -0,0 +1,88 @@
+use std::fmt::Write as _;
+
+use egui::{Color32, Rect, Stroke, Ui};
+
+use crate::{Behavior, Container, Tile, TileId, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// Is the debug overlay shown? See [`Self::set_debug_overlay`].
+    #[inline]
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay
+    }
+
+    /// Overlay each shown tile with its [`TileId`], kind, rect, share, and whether it is the active tab.
+    ///
+    /// Useful for diagnosing layout bugs, and for screenshots in bug reports.
+    /// The user can also toggle this with [`Behavior::debug_overlay_shortcut`].
+    pub fn set_debug_overlay(&mut self, debug_overlay: bool) {
+        self.debug_overlay = debug_overlay;
+    }
+
+    /// Toggle the overlay on the shortcut, and paint it if it is on.
+    pub(crate) fn debug_overlay_ui(&mut self, behavior: &dyn Behavior<Pane>, ui: &Ui) {
+        if let Some(shortcut) = behavior.debug_overlay_shortcut() {
+            if ui.input_mut(|i| i.consume_shortcut(&shortcut)) {
+                self.debug_overlay = !self.debug_overlay;
+            }
+        }
+        if !self.debug_overlay {
+            return;
+        }
+
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(
+            egui::Order::Debug,
+            self.id.with("debug_overlay"),
+        ));
+        for tile_id in self.active_tiles() {
+            let Some(rect) = self.tiles.rect(tile_id) else {
+                continue;
+            };
+            let color = if self.tiles.get_pane(&tile_id).is_some() {
+                Color32::LIGHT_GREEN
+            } else {
+                Color32::LIGHT_BLUE
+            };
+            painter.rect_stroke(rect.shrink(0.5), 0.0, Stroke::new(1.0, color));
+            painter.debug_text(
+                rect.left_top() + egui::vec2(2.0, 2.0),
+                egui::Align2::LEFT_TOP,
+                color,
+                self.debug_label(tile_id, rect),
+            );
+        }
+    }
+
+    fn debug_label(&self, tile_id: TileId, rect: Rect) -> String {
+        let kind = match self.tiles.get(tile_id) {
+            Some(Tile::Pane(_)) => "Pane".to_owned(),
+            Some(Tile::Container(container)) => format!("{:?}", container.kind()),
+            None => "DANGLING".to_owned(),
+        };
+        let mut label = format!(
+            "{tile_id:?} {kind}\n[{:.0}, {:.0}] {:.0}×{:.0}",
+            rect.min.x,
+            rect.min.y,
+            rect.width(),
+            rect.height()
+        );
+
+        let parent = self
+            .tiles
+            .parent_of(tile_id)
+            .and_then(|parent_id| self.tiles.get_container(parent_id));
+        match parent {
+            Some(Container::Linear(linear)) => {
+                write!(label, "\nshare {:.2}", linear.shares[tile_id]).ok();
+            }
+            Some(Container::Tabs(tabs)) if tabs.is_active(tile_id) => {
+                label += "\nactive";
+            }
+            _ => {}
+        }
+        if self.focused_pane() == Some(tile_id) {
+            label += "\nfocused";
+        }
+        label
+    }
+}
@@ -0,0 +1,81 @@
+//! Import layouts from [`egui_dock`].
+
+use egui_dock::{Node, NodeIndex};
+
+use crate::{Linear, LinearDir, Tabs, TileId, Tiles, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// Convert the layout of an [`egui_dock::DockState`] into a [`Tree`].
+    ///
+    /// Each leaf of tabs becomes a [`Tabs`] container (keeping its active tab),
+    /// and each split becomes a binary [`Linear`] container with the same split fraction.
+    /// `pane` is called to create the pane for each tab.
+    ///
+    /// Only the main surface is imported; tabs in floating windows are skipped.
+    ///
+    /// This is useful when migrating from `egui_dock` without losing the layouts your users have saved.
+    pub fn from_egui_dock<Tab>(
+        id: impl Into<egui::Id>,
+        dock_state: &egui_dock::DockState<Tab>,
+        mut pane: impl FnMut(&Tab) -> Pane,
+    ) -> Self {
+        let mut tiles = Tiles::default();
+        let root = import_node(
+            dock_state.main_surface(),
+            NodeIndex::root(),
+            &mut tiles,
+            &mut pane,
+        );
+        match root {
+            Some(root) => Self::new(id, root, tiles),
+            None => Self::empty(id),
+        }
+    }
+}
+
+/// Returns `None` if the node contains no tabs.
+fn import_node<Tab, Pane>(
+    dock_tree: &egui_dock::Tree<Tab>,
+    index: NodeIndex,
+    tiles: &mut Tiles<Pane>,
+    pane: &mut impl FnMut(&Tab) -> Pane,
+) -> Option<TileId> {
+    if dock_tree.len() <= index.0 {
+        return None;
+    }
+
+    let (dir, fraction) = match &dock_tree[index] {
+        Node::Empty => return None,
+        Node::Leaf { tabs, active, .. } => {
+            let children: Vec<TileId> = tabs
+                .iter()
+                .map(|tab| tiles.insert_pane(pane(tab)))
+                .collect();
+            let active = children.get(active.0).copied();
+            if children.is_empty() {
+                return None;
+            }
+            let mut container = Tabs::new(children);
+            if let Some(active) = active {
+                container.set_active(active);
+            }
+            return Some(tiles.insert_container(container));
+        }
+        // `egui_dock` names splits by the direction of the dividing line's neighbors,
+        // i.e. a horizontal split puts its children side by side:
+        Node::Horizontal { fraction, .. } => (LinearDir::Horizontal, *fraction),
+        Node::Vertical { fraction, .. } => (LinearDir::Vertical, *fraction),
+    };
+
+    let first = import_node(dock_tree, index.left(), tiles, pane);
+    let second = import_node(dock_tree, index.right(), tiles, pane);
+    match (first, second) {
+        (Some(first), Some(second)) => Some(tiles.insert_container(Linear::new_binary(
+            dir,
+            [first, second],
+            fraction.clamp(0.0, 1.0),
+        ))),
+        (Some(only), None) | (None, Some(only)) => Some(only),
+        (None, None) => None,
+    }
+}
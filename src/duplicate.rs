@@ -0,0 +1,114 @@
+use crate::tile_id_hash::TileIdMap;
+use crate::{
+    Behavior, Container, ContainerInsertion, InsertionPoint, LinearDir, Tile, TileId, Tree,
+    TreeEvent,
+};
+
+impl<Pane> Tree<Pane> {
+    /// Show a copy of the given tile right next to it, e.g. to compare a view with itself.
+    ///
+    /// Panes are copied with [`Behavior::clone_pane`]. A container is copied with everything
+    /// in it, so the copy shares no tiles with the original, and every tile in it has a new id.
+    /// The copy becomes the next tab if the tile is in a [`crate::Tabs`],
+    /// and the next sibling otherwise.
+    ///
+    /// Returns the id of the new tile, or `None` (and copies nothing)
+    /// if the behavior can't clone one of the panes.
+    pub fn duplicate(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+    ) -> Option<TileId> {
+        let insertion_point = if let Some(parent_id) = self.tiles.parent_of(tile_id) {
+            let Some(Tile::Container(parent)) = self.tiles.get(parent_id) else {
+                return None;
+            };
+            let index = match parent {
+                Container::Grid(grid) => grid.slot_of(tile_id),
+                _ => parent.children().position(|&child| child == tile_id),
+            }
+            .map_or(usize::MAX, |index| index + 1);
+            let insertion = match parent {
+                Container::Tabs(_) => ContainerInsertion::Tabs(index),
+                Container::Linear(linear) => match linear.dir {
+                    LinearDir::Horizontal => ContainerInsertion::Horizontal(index),
+                    LinearDir::Vertical => ContainerInsertion::Vertical(index),
+                },
+                Container::Grid(_) => ContainerInsertion::Grid(index),
+            };
+            Some(InsertionPoint::new(parent_id, insertion))
+        } else if self.is_root(tile_id) {
+            None
+        } else {
+            log::debug!("Can't duplicate {tile_id:?}, since it has no place in the tree");
+            return None;
+        };
+
+        let mut panes = TileIdMap::default();
+        self.clone_panes(behavior, tile_id, &mut panes)?;
+        let copy_id = self.insert_copy(tile_id, &mut panes)?;
+        if let Some(insertion_point) = insertion_point {
+            self.tiles.insert_at(insertion_point, copy_id);
+        } else {
+            // The root: put the two in tabs.
+            self.root = Some(self.tiles.insert_tab_tile(vec![tile_id, copy_id]));
+        }
+        self.make_active(|id, _| id == copy_id);
+        self.tiles.push_event(TreeEvent::TileDuplicated {
+            original: tile_id,
+            copy: copy_id,
+        });
+
+        Some(copy_id)
+    }
+
+    /// Clone every pane in the given tile, or return `None` if any of them can't be cloned.
+    fn clone_panes(
+        &self,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+        panes: &mut TileIdMap<Pane>,
+    ) -> Option<()> {
+        match self.tiles.get(tile_id)? {
+            Tile::Pane(pane) => {
+                panes.insert(tile_id, behavior.clone_pane(pane)?);
+            }
+            Tile::Container(container) => {
+                for &child in container.children() {
+                    self.clone_panes(behavior, child, panes)?;
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Insert a copy of the given tile, using the panes from [`Self::clone_panes`].
+    ///
+    /// The copy isn't placed anywhere yet.
+    fn insert_copy(&mut self, tile_id: TileId, panes: &mut TileIdMap<Pane>) -> Option<TileId> {
+        let copy_id = match self.tiles.get(tile_id)? {
+            Tile::Pane(_) => self.tiles.insert_pane(panes.remove(&tile_id)?),
+            Tile::Container(container) => {
+                let mut copy = container.clone();
+                let mut new_ids = TileIdMap::default();
+                for child in container.children_vec() {
+                    new_ids.insert(child, self.insert_copy(child, panes)?);
+                }
+                copy.map_tile_ids(|id| new_ids.get(&id).copied().unwrap_or(id));
+                self.tiles.insert_container(copy)
+            }
+        };
+        if !self.tiles.is_visible(tile_id) {
+            self.tiles.set_visible(copy_id, false);
+        }
+        Some(copy_id)
+    }
+
+    /// Duplicate the panes the user asked to duplicate from the tab context menu,
+    /// see [`Behavior::duplicate_in_tab_context_menu`].
+    pub(crate) fn duplicate_requested(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        for tile_id in std::mem::take(&mut self.tiles.duplicate_requests) {
+            self.duplicate(behavior, tile_id);
+        }
+    }
+}
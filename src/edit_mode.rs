@@ -0,0 +1,99 @@
+use crate::{Behavior, TileId, Tree};
+
+/// How much the user may change the layout of a [`Tree`], see [`Tree::set_edit_mode`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EditMode {
+    /// The user can drag, resize, and close tiles.
+    #[default]
+    Normal,
+
+    /// The layout is frozen: no dragging, resizing, closing, or reordering of tabs.
+    ///
+    /// The user can still switch tabs and interact with the panes.
+    Locked,
+}
+
+/// Which edits the user may make to a specific tile, see [`Behavior::edit_permissions`].
+///
+/// This lets you mix fixed parts of the layout with parts the user can arrange.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EditPermissions {
+    /// Can the tile be dragged somewhere else?
+    pub drag: bool,
+
+    /// Can the tile be resized?
+    ///
+    /// A line between two tiles can only be dragged if both tiles and their parent allow it.
+    /// In a grid, where the lines span several tiles, only the grid itself is asked.
+    pub resize: bool,
+
+    /// Can the tile be closed with the close button on its tab?
+    ///
+    /// [`Behavior::is_tab_closable`] must also agree.
+    pub close: bool,
+
+    /// Can other tiles be dropped into or next to this tile?
+    pub drop_into: bool,
+}
+
+impl EditPermissions {
+    /// Everything is allowed.
+    pub const ALL: Self = Self {
+        drag: true,
+        resize: true,
+        close: true,
+        drop_into: true,
+    };
+
+    /// Nothing is allowed.
+    pub const NONE: Self = Self {
+        drag: false,
+        resize: false,
+        close: false,
+        drop_into: false,
+    };
+}
+
+impl Default for EditPermissions {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// How much the user may change the layout.
+    #[inline]
+    pub fn edit_mode(&self) -> EditMode {
+        self.edit_mode
+    }
+
+    /// Lock or unlock the layout, e.g. for kiosk deployments
+    /// where a carefully designed layout should stay as it is.
+    ///
+    /// The edit mode is not persisted.
+    pub fn set_edit_mode(&mut self, edit_mode: EditMode) {
+        self.edit_mode = edit_mode;
+    }
+
+    /// Is the user not allowed to change the layout?
+    #[inline]
+    pub(crate) fn is_locked(&self) -> bool {
+        self.edit_mode == EditMode::Locked
+    }
+
+    /// Which edits the user may make to the given tile.
+    ///
+    /// This is [`Behavior::edit_permissions`], or [`EditPermissions::NONE`] if the tree is
+    /// [`EditMode::Locked`].
+    pub fn edit_permissions(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        tile_id: TileId,
+    ) -> EditPermissions {
+        if self.is_locked() {
+            EditPermissions::NONE
+        } else {
+            behavior.edit_permissions(&self.tiles, tile_id)
+        }
+    }
+}
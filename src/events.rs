@@ -0,0 +1,152 @@
+use crate::tile_id_hash::TileIdSet;
+use crate::{Behavior, Tile, TileId, Tiles, Tree, VisibilityReason};
+
+/// A change to the structure of a [`Tree`], see [`Tree::take_events`].
+///
+/// More kinds of events may be added in the future, so this is `#[non_exhaustive]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TreeEvent {
+    /// A tile was moved, e.g. by drag-and-drop.
+    TileMoved {
+        tile_id: TileId,
+
+        /// The container it was in before, if any.
+        from: Option<TileId>,
+
+        /// The container it is in now.
+        to: TileId,
+    },
+
+    /// A tile was closed with the close button on its tab.
+    TileClosed(TileId),
+
+    /// The user switched to another tab.
+    TabActivated {
+        /// The [`crate::Tabs`] container.
+        tabs: TileId,
+
+        /// The tab that is now active.
+        tab: TileId,
+    },
+
+    /// The user resized the children of the given container.
+    Resized(TileId),
+
+    /// A new container was created to hold a dropped tile next to an existing one.
+    ///
+    /// The container takes the place (and [`TileId`]) of the existing tile,
+    /// which is moved into it under a new [`TileId`].
+    ContainerCreated(TileId),
+
+    /// A tile was copied with [`Tree::duplicate`].
+    TileDuplicated { original: TileId, copy: TileId },
+
+    /// A tile showed up in the tree for the first time, see [`crate::Behavior::on_tile_inserted`].
+    TileInserted {
+        tile_id: TileId,
+
+        /// The container it is in, or `None` for the root and auto-hidden tiles.
+        parent: Option<TileId>,
+    },
+
+    /// A pane started being shown, see [`crate::Behavior::on_pane_shown`].
+    PaneShown {
+        tile_id: TileId,
+        reason: VisibilityReason,
+    },
+
+    /// A pane stopped being shown, see [`crate::Behavior::on_pane_hidden`].
+    PaneHidden {
+        tile_id: TileId,
+        reason: VisibilityReason,
+    },
+
+    /// The layout was reverted with [`Tree::undo`].
+    LayoutUndone,
+
+    /// The layout was re-applied with [`Tree::redo`].
+    LayoutRedone,
+}
+
+impl<Pane> Tree<Pane> {
+    /// Start recording [`TreeEvent`]s, to be drained with [`Self::take_events`].
+    ///
+    /// This is an alternative to implementing the many callbacks in [`crate::Behavior`].
+    /// Recording is not persisted, so call this again after deserializing a tree.
+    pub fn enable_events(&mut self) {
+        self.tiles.events.get_or_insert_with(Vec::new);
+    }
+
+    /// Take the events recorded since the last call, oldest first.
+    ///
+    /// Call this after [`Self::ui`]. Returns nothing unless [`Self::enable_events`] was called.
+    pub fn take_events(&mut self) -> Vec<TreeEvent> {
+        self.tiles
+            .events
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Tell the behavior about the tiles that weren't in the tree last frame, parents first.
+    pub(crate) fn announce_inserted_tiles(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        let roots = self
+            .root
+            .into_iter()
+            .chain(self.auto_hidden.iter().map(|&(tile_id, _)| tile_id));
+        let mut stack: Vec<(TileId, Option<TileId>)> =
+            roots.map(|tile_id| (tile_id, None)).collect();
+        stack.reverse();
+
+        let mut inserted = Vec::new();
+        let mut reachable = TileIdSet::default();
+        while let Some((tile_id, parent)) = stack.pop() {
+            if !reachable.insert(tile_id) {
+                continue; // guard against cycles
+            }
+            if !self.tiles.known_tiles.contains(&tile_id) {
+                inserted.push((tile_id, parent));
+            }
+            if let Some(Tile::Container(container)) = self.tiles.get(tile_id) {
+                let children = container.children_vec();
+                stack.extend(
+                    children
+                        .into_iter()
+                        .rev()
+                        .map(|child| (child, Some(tile_id))),
+                );
+            }
+        }
+        self.tiles
+            .hidden_reasons
+            .retain(|tile_id, _| reachable.contains(tile_id));
+        self.tiles.known_tiles = reachable;
+
+        for (tile_id, parent) in inserted {
+            if matches!(self.tiles.get(tile_id), Some(Tile::Pane(_))) && !self.is_shown(tile_id) {
+                // Remember why, so that showing it later gets the right reason:
+                let reason = self.hidden_reason(tile_id);
+                self.tiles.hidden_reasons.insert(tile_id, reason);
+            }
+            behavior.on_tile_inserted(&mut self.tiles, tile_id, parent);
+            self.tiles
+                .push_event(TreeEvent::TileInserted { tile_id, parent });
+        }
+    }
+}
+
+impl<Pane> Tiles<Pane> {
+    /// Record an event, if events are enabled.
+    pub(crate) fn push_event(&mut self, event: TreeEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    /// The user resized the children of the given container.
+    pub(crate) fn on_resized(&mut self, container_id: TileId) {
+        self.resized = Some(container_id);
+        self.push_event(TreeEvent::Resized(container_id));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::tile_id_hash::TileIdSet;
+use crate::{Behavior, Tile, TileId, Tree};
+
+/// See [`Tree::set_filter`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TreeFilter {
+    text: String,
+
+    /// The matching panes and their ancestors, recomputed each frame.
+    matches: TileIdSet,
+}
+
+impl<Pane> Tree<Pane> {
+    /// Help the user find panes in a large workspace by dimming the panes
+    /// that don't match the given text, and highlighting the tabs of those that do.
+    ///
+    /// Whether a pane matches is up to [`Behavior::matches_filter`],
+    /// which by default looks for the text in its tab title.
+    /// With [`Behavior::hide_filtered_tabs`] the tabs of panes that don't match are hidden too.
+    ///
+    /// `None` or an empty string turns filtering off. The filter is not persisted.
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        self.filter = filter
+            .filter(|text| !text.is_empty())
+            .map(|text| TreeFilter {
+                text,
+                matches: Default::default(),
+            });
+    }
+
+    /// See [`Self::set_filter`].
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_ref().map(|filter| filter.text.as_str())
+    }
+
+    /// Does this tile match [`Self::filter`], as of the last frame?
+    ///
+    /// A container matches if any pane in it does.
+    /// Returns `None` when there is no filter.
+    pub fn matches_filter(&self, tile_id: TileId) -> Option<bool> {
+        self.filter
+            .as_ref()
+            .map(|filter| filter.matches.contains(&tile_id))
+    }
+
+    /// Ask [`Behavior::matches_filter`] about each pane.
+    pub(crate) fn update_filter_matches(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        let Some(filter) = &mut self.filter else {
+            return;
+        };
+        filter.matches.clear();
+        for tile_id in self.tiles.tile_ids() {
+            if matches!(self.tiles.get(tile_id), Some(Tile::Pane(_)))
+                && behavior.matches_filter(&self.tiles, tile_id, &filter.text)
+            {
+                filter.matches.insert(tile_id);
+                filter.matches.extend(self.tiles.ancestors(tile_id));
+            }
+        }
+    }
+}
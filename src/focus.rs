@@ -0,0 +1,46 @@
+use crate::{TileId, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// The pane the user is working in, if any.
+    ///
+    /// A pane gets focus when the user clicks inside it, or when you call [`Self::focus_pane`].
+    /// The focus is not persisted.
+    #[inline]
+    pub fn focused_pane(&self) -> Option<TileId> {
+        self.focused
+    }
+
+    /// The container holding the [`Self::focused_pane`], if any.
+    ///
+    /// This is the "active pane group", which gets an accent,
+    /// see [`crate::Behavior::paint_focused_group_accent`].
+    pub fn focused_group(&self) -> Option<TileId> {
+        self.tiles.parent_of(self.focused?)
+    }
+
+    /// Focus the given pane, and make sure it is shown by activating any tabs it is in.
+    ///
+    /// Returns `false` if there is no such pane.
+    pub fn focus_pane(&mut self, tile_id: TileId) -> bool {
+        if !self.tiles.get(tile_id).is_some_and(|tile| tile.is_pane()) {
+            return false;
+        }
+        self.focused = Some(tile_id);
+        self.make_active(|id, _| id == tile_id);
+        true
+    }
+
+    /// No pane has focus after this.
+    pub fn clear_focus(&mut self) {
+        self.focused = None;
+    }
+
+    /// Forget the focus if the focused pane is gone.
+    pub(crate) fn validate_focus(&mut self) {
+        if let Some(focused) = self.focused {
+            if !self.tiles.get(focused).is_some_and(|tile| tile.is_pane()) {
+                self.focused = None;
+            }
+        }
+    }
+}
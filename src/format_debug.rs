@@ -0,0 +1,136 @@
+use std::fmt::Write as _;
+
+use crate::{Behavior, Container, GridLayout, LinearDir, Tile, TileId, Tiles, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// An indented, human-readable rendering of the tree, for logs and bug reports.
+    ///
+    /// Shows the kind of each container, the shares of linear children,
+    /// which tab is active, and the title of each pane (from [`Behavior::tab_title_for_pane`]).
+    ///
+    /// ```text
+    /// Tree 1A2B
+    ///   #1 Tabs, active: #2
+    ///     #2 Horizontal
+    ///       #4 [share 1.50] Pane "Files"
+    ///       #5 [share 0.50, invisible] Pane "Console"
+    ///     #3 Grid (Auto), columns: [1.00, 1.00], rows: [1.00]
+    ///       #6 Pane "Viewer"
+    ///       #7 Pane "Plot"
+    /// ```
+    pub fn format_debug(&self, behavior: &mut dyn Behavior<Pane>) -> String {
+        let mut out = String::new();
+        writeln!(out, "Tree {:?}", self.id).ok();
+
+        let mut write_line =
+            |out: &mut String, tile_id, parent: Option<&Container>, tile: Option<&Tile<Pane>>| {
+                format_tile(out, behavior, &self.tiles, tile_id, parent, tile)
+            };
+        if let Some(root) = self.root {
+            write_tile_tree(&mut out, &self.tiles, 1, root, None, &mut write_line).ok();
+        } else {
+            writeln!(out, "  (empty)").ok();
+        }
+
+        for &(tile_id, edge) in &self.auto_hidden {
+            writeln!(out, "  auto-hidden at {edge:?}:").ok();
+            write_tile_tree(&mut out, &self.tiles, 2, tile_id, None, &mut write_line).ok();
+        }
+
+        if let Some(modal) = self.modal() {
+            writeln!(out, "  modal: {modal:?}").ok();
+        }
+
+        out
+    }
+}
+
+/// Write one line for each tile, starting with `tile_id` and going depth-first into its children,
+/// indented by two spaces per level.
+///
+/// `write_line` writes a line (without the indentation and the newline) for a tile,
+/// given its parent container, if any. The tile is `None` if it is missing from `tiles`.
+pub(crate) fn write_tile_tree<Pane, W, F>(
+    out: &mut W,
+    tiles: &Tiles<Pane>,
+    indent: usize,
+    tile_id: TileId,
+    parent: Option<&Container>,
+    write_line: &mut F,
+) -> std::fmt::Result
+where
+    W: std::fmt::Write,
+    F: FnMut(&mut W, TileId, Option<&Container>, Option<&Tile<Pane>>) -> std::fmt::Result,
+{
+    write!(out, "{}", "  ".repeat(indent))?;
+    let tile = tiles.get(tile_id);
+    write_line(out, tile_id, parent, tile)?;
+    writeln!(out)?;
+
+    if let Some(Tile::Container(container)) = tile {
+        for &child in container.children() {
+            write_tile_tree(out, tiles, indent + 1, child, Some(container), write_line)?;
+        }
+    }
+    Ok(())
+}
+
+fn format_tile<Pane>(
+    out: &mut String,
+    behavior: &mut dyn Behavior<Pane>,
+    tiles: &Tiles<Pane>,
+    tile_id: TileId,
+    parent: Option<&Container>,
+    tile: Option<&Tile<Pane>>,
+) -> std::fmt::Result {
+    let mut annotations: Vec<String> = vec![];
+    if let Some(Container::Linear(linear)) = parent {
+        annotations.push(format!("share {:.2}", linear.shares[tile_id]));
+    }
+    if !tiles.is_visible(tile_id) {
+        annotations.push("invisible".to_owned());
+    }
+    let annotations = if annotations.is_empty() {
+        String::new()
+    } else {
+        format!("[{}] ", annotations.join(", "))
+    };
+
+    write!(out, "{tile_id:?} {annotations}")?;
+
+    match tile {
+        None => write!(out, "DANGLING"),
+        Some(Tile::Pane(pane)) => {
+            let title = behavior.tab_title_for_pane(pane);
+            write!(out, "Pane {:?}", title.text())
+        }
+        Some(Tile::Container(Container::Tabs(tabs))) => {
+            write!(out, "Tabs")?;
+            if let Some(active) = tabs.active {
+                write!(out, ", active: {active:?}")?;
+            }
+            Ok(())
+        }
+        Some(Tile::Container(Container::Linear(linear))) => match linear.dir {
+            LinearDir::Horizontal => write!(out, "Horizontal"),
+            LinearDir::Vertical => write!(out, "Vertical"),
+        },
+        Some(Tile::Container(Container::Grid(grid))) => {
+            match grid.layout {
+                GridLayout::Auto => write!(out, "Grid (Auto)")?,
+                GridLayout::Columns(n) => write!(out, "Grid ({n} columns)")?,
+            }
+            write!(
+                out,
+                ", columns: {}, rows: {}",
+                format_shares(&grid.col_shares),
+                format_shares(&grid.row_shares)
+            )
+        }
+    }
+}
+
+fn format_shares(shares: &[f32]) -> String {
+    let shares: Vec<String> = shares.iter().map(|share| format!("{share:.2}")).collect();
+    format!("[{}]", shares.join(", "))
+}
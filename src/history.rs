@@ -0,0 +1,179 @@
+use crate::behavior::EditAction;
+use crate::tile_id_hash::TileIdSet;
+use crate::tiles::TileStructure;
+use crate::{Behavior, Edge, TileId, Tree, TreeEvent};
+
+/// Opt-in undo/redo history of the layout of a [`Tree`].
+///
+/// Only the tile structure is recorded. Panes closed since are kept in
+/// [`crate::Tiles`] for as long as a step refers to them.
+///
+/// See [`Tree::enable_history`].
+#[derive(Clone)]
+pub(crate) struct History {
+    /// Oldest first.
+    undo: Vec<Snapshot>,
+
+    /// Most recently undone last.
+    redo: Vec<Snapshot>,
+
+    /// The layout as of the last recorded step.
+    current: Snapshot,
+
+    /// Maximum length of [`Self::undo`].
+    max_steps: usize,
+}
+
+/// The layout of a [`Tree`], without the panes or any transient state.
+#[derive(Clone)]
+struct Snapshot {
+    root: Option<TileId>,
+    structure: TileStructure,
+    auto_hidden: Vec<(TileId, Edge)>,
+}
+
+impl Snapshot {
+    fn new<Pane>(tree: &Tree<Pane>) -> Self {
+        Self {
+            root: tree.root,
+            structure: tree.tiles.structure(),
+            auto_hidden: tree.auto_hidden.clone(),
+        }
+    }
+
+    fn layout_eq<Pane>(&self, tree: &Tree<Pane>) -> bool {
+        self.root == tree.root
+            && self.auto_hidden == tree.auto_hidden
+            && tree.tiles.structure_eq(&self.structure)
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Start recording changes to the layout, so they can be undone with [`Self::undo`].
+    ///
+    /// Changes are recorded at the end of each [`Self::ui`] call, but only while no mouse button is held,
+    /// so a whole resize or drag-and-drop is recorded as a single step.
+    ///
+    /// Only the layout is recorded, not the panes, so the history stays small no matter
+    /// how heavy the panes are. A closed pane is kept (outside the tree) for as long as
+    /// a step refers to it, so undoing the close brings it back.
+    ///
+    /// At most `max_steps` steps are kept.
+    pub fn enable_history(&mut self, max_steps: usize) {
+        self.tiles.graveyard = Some(Default::default());
+        self.history = Some(Box::new(History {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            current: Snapshot::new(self),
+            max_steps,
+        }));
+    }
+
+    /// Stop recording changes, and forget the history.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+        self.tiles.graveyard = None;
+    }
+
+    /// Forget the history, but keep recording, starting from the current layout.
+    pub(crate) fn clear_history(&mut self) {
+        if let Some(history) = &self.history {
+            self.enable_history(history.max_steps);
+        }
+    }
+
+    /// Is there anything to [`Self::undo`]?
+    pub fn can_undo(&self) -> bool {
+        self.history.as_ref().is_some_and(|h| !h.undo.is_empty())
+    }
+
+    /// Is there anything to [`Self::redo`]?
+    pub fn can_redo(&self) -> bool {
+        self.history.as_ref().is_some_and(|h| !h.redo.is_empty())
+    }
+
+    /// Go back to the layout before the last recorded change.
+    ///
+    /// This is recorded as an [`EditAction::LayoutUndone`] and a [`TreeEvent::LayoutUndone`].
+    ///
+    /// Returns `false` if there was nothing to undo, or history is not enabled.
+    pub fn undo(&mut self, behavior: &mut dyn Behavior<Pane>) -> bool {
+        let Some(mut history) = self.history.take() else {
+            return false;
+        };
+        let undone = if let Some(snapshot) = history.undo.pop() {
+            let previous = std::mem::replace(&mut history.current, snapshot);
+            history.redo.push(previous);
+            self.restore(&history);
+            self.tiles.on_edit(behavior, EditAction::LayoutUndone);
+            self.tiles.push_event(TreeEvent::LayoutUndone);
+            true
+        } else {
+            false
+        };
+        self.history = Some(history);
+        undone
+    }
+
+    /// Re-apply the last change that was undone with [`Self::undo`].
+    ///
+    /// This is recorded as an [`EditAction::LayoutRedone`] and a [`TreeEvent::LayoutRedone`].
+    ///
+    /// Returns `false` if there was nothing to redo, or history is not enabled.
+    pub fn redo(&mut self, behavior: &mut dyn Behavior<Pane>) -> bool {
+        let Some(mut history) = self.history.take() else {
+            return false;
+        };
+        let redone = if let Some(snapshot) = history.redo.pop() {
+            let previous = std::mem::replace(&mut history.current, snapshot);
+            history.undo.push(previous);
+            self.restore(&history);
+            self.tiles.on_edit(behavior, EditAction::LayoutRedone);
+            self.tiles.push_event(TreeEvent::LayoutRedone);
+            true
+        } else {
+            false
+        };
+        self.history = Some(history);
+        redone
+    }
+
+    /// Record the current layout as a new step, if it changed.
+    pub(crate) fn record_history(&mut self, ui: &egui::Ui) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        if ui.input(|i| i.pointer.any_down()) || history.current.layout_eq(self) {
+            return;
+        }
+
+        let snapshot = Snapshot::new(self);
+        let Some(history) = &mut self.history else {
+            return;
+        };
+        let previous = std::mem::replace(&mut history.current, snapshot);
+        history.undo.push(previous);
+        if history.max_steps < history.undo.len() {
+            history.undo.remove(0);
+        }
+        history.redo.clear();
+
+        let referenced: TileIdSet = history
+            .undo
+            .iter()
+            .flat_map(|snapshot| snapshot.structure.pane_ids())
+            .collect();
+        if let Some(graveyard) = &mut self.tiles.graveyard {
+            graveyard.retain(|tile_id, _| referenced.contains(tile_id));
+        }
+    }
+
+    /// Switch to the layout of [`History::current`], filling it with the panes that still exist
+    /// or were kept since they were closed.
+    fn restore(&mut self, history: &History) {
+        let snapshot = &history.current;
+        self.root = snapshot.root;
+        self.auto_hidden = snapshot.auto_hidden.clone();
+        self.tiles.restore_structure(&snapshot.structure);
+    }
+}
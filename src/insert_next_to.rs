@@ -0,0 +1,116 @@
+use crate::{
+    Container, ContainerInsertion, ContainerKind, InsertionPoint, SimplifyAction, Tile, TileId,
+    Tree, TreeEvent,
+};
+
+/// Where [`Tree::insert_pane_after`] puts a new pane, relative to an existing tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NextTo {
+    /// The tab after it.
+    NextTab,
+
+    /// To the left of it.
+    Left,
+
+    /// To the right of it.
+    Right,
+
+    /// Above it.
+    Above,
+
+    /// Below it.
+    Below,
+}
+
+impl NextTo {
+    /// The kind of container holding both tiles.
+    fn container_kind(self) -> ContainerKind {
+        match self {
+            Self::NextTab => ContainerKind::Tabs,
+            Self::Left | Self::Right => ContainerKind::Horizontal,
+            Self::Above | Self::Below => ContainerKind::Vertical,
+        }
+    }
+
+    /// Does the new pane go after the existing tile?
+    fn is_after(self) -> bool {
+        !matches!(self, Self::Left | Self::Above)
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Add a pane next to an existing tile, and make it active.
+    ///
+    /// If the parent of `existing` is already the right kind of container
+    /// (e.g. [`crate::Tabs`] for [`NextTo::NextTab`]), the pane goes right next to it there.
+    /// Otherwise `existing` is put in a new container of that kind, together with the pane.
+    /// Either way `existing` keeps its id.
+    ///
+    /// Returns the id of the new pane, or `None` (and adds nothing)
+    /// if `existing` is not part of the tree.
+    pub fn insert_pane_after(
+        &mut self,
+        existing: TileId,
+        pane: Pane,
+        placement: NextTo,
+    ) -> Option<TileId> {
+        let parent_id = self.tiles.parent_of(existing);
+        if parent_id.is_none() && !self.is_root(existing) {
+            log::debug!("Can't insert next to {existing:?}, since it has no place in the tree");
+            return None;
+        }
+
+        let kind = placement.container_kind();
+        let place_in_parent = parent_id.and_then(|parent_id| match self.tiles.get(parent_id) {
+            Some(Tile::Container(parent)) if parent.kind() == kind => parent
+                .children()
+                .position(|&child| child == existing)
+                .map(|index| (parent_id, index)),
+            _ => None,
+        });
+
+        let new_id = self.tiles.insert_pane(pane);
+        if let Some((parent_id, index)) = place_in_parent {
+            let index = if placement.is_after() {
+                index + 1
+            } else {
+                index
+            };
+            let insertion = match kind {
+                ContainerKind::Tabs => ContainerInsertion::Tabs(index),
+                ContainerKind::Horizontal => ContainerInsertion::Horizontal(index),
+                ContainerKind::Vertical => ContainerInsertion::Vertical(index),
+                ContainerKind::Grid => ContainerInsertion::Grid(index),
+            };
+            self.tiles
+                .insert_at(InsertionPoint::new(parent_id, insertion), new_id);
+        } else {
+            let children = if placement.is_after() {
+                vec![existing, new_id]
+            } else {
+                vec![new_id, existing]
+            };
+            let container_id = self.tiles.insert_container(Container::new(kind, children));
+            self.tiles
+                .push_event(TreeEvent::ContainerCreated(container_id));
+
+            if let Some(parent_id) = parent_id {
+                if let Some(Tile::Container(parent)) = self.tiles.get_mut(parent_id) {
+                    // The new container takes the place (and share) of `existing`:
+                    parent.simplify_children(|child| {
+                        if child == existing {
+                            SimplifyAction::Replace(container_id)
+                        } else {
+                            SimplifyAction::Keep
+                        }
+                    });
+                }
+            } else {
+                self.root = Some(container_id);
+            }
+        }
+
+        self.make_active(|tile_id, _| tile_id == new_id);
+        Some(new_id)
+    }
+}
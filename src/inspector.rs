@@ -0,0 +1,129 @@
+use egui::{Stroke, Ui};
+
+use crate::{Behavior, ContainerKind, Tile, TileId, Tree};
+
+/// Something the user did in [`Tree::inspector_ui`].
+///
+/// These are applied after the whole outline has been shown.
+enum InspectorAction {
+    Select(TileId),
+    SetVisible(TileId, bool),
+    SetKind(TileId, ContainerKind),
+}
+
+impl<Pane> Tree<Pane> {
+    /// An interactive outline of the tree, e.g. for a "layout editor" side panel or for debugging.
+    ///
+    /// Select a tile to highlight where it is shown. Each tile can be hidden,
+    /// and the kind of each container changed.
+    /// There are also buttons to remove the selected tile, and to [`Self::simplify`] the tree.
+    pub fn inspector_ui(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &mut Ui) {
+        let selected_id = self.id.with("inspector_selected");
+        let mut selected: Option<TileId> = ui
+            .data(|data| data.get_temp::<Option<TileId>>(selected_id))
+            .flatten()
+            .filter(|&tile_id| self.tiles.get(tile_id).is_some());
+
+        ui.horizontal(|ui| {
+            if ui.button("Simplify").clicked() {
+                self.simplify(&behavior.simplification_options());
+            }
+            if ui
+                .add_enabled(selected.is_some(), egui::Button::new("Remove selected"))
+                .clicked()
+            {
+                if let Some(tile_id) = selected.take() {
+                    self.close_recursively(tile_id);
+                }
+            }
+        });
+
+        let mut actions = vec![];
+        if let Some(root) = self.root {
+            self.inspector_tile_ui(behavior, ui, root, selected, &mut actions);
+        }
+        for &(tile_id, edge) in &self.auto_hidden {
+            ui.label(format!("Auto-hidden at {edge:?}:"));
+            self.inspector_tile_ui(behavior, ui, tile_id, selected, &mut actions);
+        }
+
+        for action in actions {
+            match action {
+                InspectorAction::Select(tile_id) => selected = Some(tile_id),
+                InspectorAction::SetVisible(tile_id, visible) => {
+                    self.set_visible(tile_id, visible);
+                }
+                InspectorAction::SetKind(tile_id, kind) => {
+                    if let Some(Tile::Container(container)) = self.tiles.get_mut(tile_id) {
+                        container.set_kind(kind);
+                    }
+                }
+            }
+        }
+
+        if let Some(rect) = selected.and_then(|tile_id| self.tiles.rect(tile_id)) {
+            ui.ctx()
+                .layer_painter(egui::LayerId::new(
+                    egui::Order::Debug,
+                    self.id.with("inspector_highlight"),
+                ))
+                .rect_stroke(rect, 0.0, Stroke::new(2.0, ui.visuals().selection.bg_fill));
+        }
+        ui.data_mut(|data| data.insert_temp(selected_id, selected));
+    }
+
+    fn inspector_tile_ui(
+        &self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+        tile_id: TileId,
+        selected: Option<TileId>,
+        actions: &mut Vec<InspectorAction>,
+    ) {
+        let text = format!(
+            "{} - {tile_id:?}",
+            behavior.tab_title_for_tile(&self.tiles, tile_id).text()
+        );
+        let Some(tile) = self.tiles.get(tile_id) else {
+            ui.label(format!("{tile_id:?} (dangling)"));
+            return;
+        };
+
+        egui::collapsing_header::CollapsingState::load_with_default_open(
+            ui.ctx(),
+            ui.id().with((tile_id, "inspector")),
+            true,
+        )
+        .show_header(ui, |ui| {
+            if ui
+                .selectable_label(selected == Some(tile_id), text)
+                .clicked()
+            {
+                actions.push(InspectorAction::Select(tile_id));
+            }
+            let mut visible = self.is_visible(tile_id);
+            if ui.checkbox(&mut visible, "Visible").changed() {
+                actions.push(InspectorAction::SetVisible(tile_id, visible));
+            }
+        })
+        .body(|ui| {
+            if let Tile::Container(container) = tile {
+                let mut kind = container.kind();
+                egui::ComboBox::from_id_salt((tile_id, "kind"))
+                    .selected_text(kind.name())
+                    .show_ui(ui, |ui| {
+                        for typ in ContainerKind::ALL {
+                            ui.selectable_value(&mut kind, typ, typ.name());
+                        }
+                    });
+                if kind != container.kind() {
+                    actions.push(InspectorAction::SetKind(tile_id, kind));
+                }
+
+                for &child in container.children() {
+                    self.inspector_tile_ui(behavior, ui, child, selected, actions);
+                }
+            }
+        });
+    }
+}
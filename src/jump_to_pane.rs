@@ -0,0 +1,234 @@
+use egui::{Key, Modifiers, Rect, Ui};
+
+use crate::{Behavior, Tile, TileId, Tree};
+
+/// At most this many matches are listed.
+const MAX_MATCHES: usize = 12;
+
+/// How long a pane flashes after being jumped to, in seconds.
+const FLASH_DURATION: f64 = 0.6;
+
+/// The state of the jump-to-pane popup.
+///
+/// Stored in egui temp storage, just like the auto-hide flyout state.
+#[derive(Clone, Debug, Default)]
+struct JumpToPaneState {
+    open: bool,
+    query: String,
+
+    /// Index into the matches.
+    selected: usize,
+
+    /// The pane that was jumped to, and when.
+    flash: Option<(TileId, f64)>,
+}
+
+impl<Pane> Tree<Pane> {
+    /// Open a popup for finding a pane by its title, and jumping to it.
+    ///
+    /// The user can also open it with [`Behavior::jump_to_pane_shortcut`].
+    /// Picking a pane activates the tabs it is in, focuses it,
+    /// and flashes it if [`Behavior::flash_jumped_pane`] says so.
+    pub fn open_jump_to_pane(&self, ctx: &egui::Context) {
+        ctx.data_mut(|data| {
+            *data.get_temp_mut_or_default::<JumpToPaneState>(self.jump_to_pane_id()) =
+                JumpToPaneState {
+                    open: true,
+                    ..Default::default()
+                };
+        });
+    }
+
+    fn jump_to_pane_id(&self) -> egui::Id {
+        self.id.with("jump_to_pane")
+    }
+
+    /// Show the jump-to-pane popup, if open, and the flash of the last jump.
+    pub(crate) fn jump_to_pane_ui(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &Ui,
+        tree_rect: Rect,
+    ) {
+        if let Some(shortcut) = behavior.jump_to_pane_shortcut() {
+            if ui.input_mut(|i| i.consume_shortcut(&shortcut)) {
+                self.open_jump_to_pane(ui.ctx());
+            }
+        }
+
+        let state_id = self.jump_to_pane_id();
+        let mut state: JumpToPaneState =
+            ui.data(|data| data.get_temp(state_id)).unwrap_or_default();
+
+        if state.open {
+            if let Some(tile_id) = self.jump_to_pane_popup(behavior, ui, tree_rect, &mut state) {
+                state.open = false;
+                self.focus_pane(tile_id);
+                if behavior.flash_jumped_pane() {
+                    state.flash = Some((tile_id, ui.input(|i| i.time)));
+                }
+            }
+        }
+
+        if let Some((tile_id, start_time)) = state.flash {
+            let t = (ui.input(|i| i.time) - start_time) / FLASH_DURATION;
+            if let (Some(rect), true) = (self.tiles.rect(tile_id), t < 1.0) {
+                let painter = ui.ctx().layer_painter(egui::LayerId::new(
+                    egui::Order::Foreground,
+                    state_id.with("flash"),
+                ));
+                let color = ui
+                    .visuals()
+                    .selection
+                    .bg_fill
+                    .gamma_multiply(0.5 * (1.0 - t) as f32);
+                painter.rect_filled(rect, 0.0, color);
+                ui.ctx().request_repaint();
+            } else {
+                state.flash = None;
+            }
+        }
+
+        ui.data_mut(|data| data.insert_temp(state_id, state));
+    }
+
+    /// Returns the pane the user picked, if any.
+    fn jump_to_pane_popup(
+        &self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &Ui,
+        tree_rect: Rect,
+        state: &mut JumpToPaneState,
+    ) -> Option<TileId> {
+        let mut matches: Vec<(i32, String, TileId)> = self
+            .tiles
+            .iter()
+            .filter_map(|(&tile_id, tile)| {
+                let Tile::Pane(pane) = tile else {
+                    return None;
+                };
+                let title = behavior.tab_title_for_pane(pane).text().to_owned();
+                let score = fuzzy_score(&state.query, &title)?;
+                Some((score, title, tile_id))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| (&a.1, a.2).cmp(&(&b.1, b.2))));
+        matches.truncate(MAX_MATCHES);
+        state.selected = state.selected.min(matches.len().saturating_sub(1));
+
+        let (escape, enter) = ui.input_mut(|i| {
+            if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                state.selected = (state.selected + 1).min(matches.len().saturating_sub(1));
+            }
+            if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                state.selected = state.selected.saturating_sub(1);
+            }
+            (
+                i.consume_key(Modifiers::NONE, Key::Escape),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+            )
+        });
+        if escape {
+            state.open = false;
+            return None;
+        }
+
+        let mut picked = enter
+            .then(|| matches.get(state.selected).map(|(_, _, tile_id)| *tile_id))
+            .flatten();
+
+        let area_response = egui::Area::new(self.jump_to_pane_id().with("popup"))
+            .order(egui::Order::Foreground)
+            .pivot(egui::Align2::CENTER_TOP)
+            .fixed_pos(tree_rect.center_top() + egui::vec2(0.0, 32.0))
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(300.0);
+                    let query_response = ui.add(
+                        egui::TextEdit::singleline(&mut state.query)
+                            .hint_text("Go to pane…")
+                            .desired_width(f32::INFINITY),
+                    );
+                    query_response.request_focus();
+                    if query_response.changed() {
+                        state.selected = 0;
+                    }
+
+                    for (i, (_, title, tile_id)) in matches.iter().enumerate() {
+                        let label = ui.selectable_label(i == state.selected, title);
+                        if label.clicked() {
+                            picked = Some(*tile_id);
+                        }
+                    }
+                    if matches.is_empty() {
+                        ui.weak("No matching pane");
+                    }
+                });
+            })
+            .response;
+
+        if picked.is_none() && area_response.clicked_elsewhere() {
+            state.open = false;
+        }
+        picked
+    }
+}
+
+/// How well `text` matches `query`, or `None` if it doesn't.
+///
+/// The characters of the query must appear in order in the text, ignoring case.
+/// Consecutive characters and characters at the start of words score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut query = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+
+    let mut score = 0;
+    let mut previous_matched = false;
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        let is_word_start = previous.map_or(true, |p| !p.is_alphanumeric());
+        if c.to_lowercase().eq(std::iter::once(wanted)) {
+            query.next();
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if is_word_start {
+                score += 8;
+            }
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = Some(c);
+    }
+
+    query.peek().is_none().then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+        assert!(fuzzy_score("lgs", "Logs").is_some());
+        assert!(fuzzy_score("LOGS", "logs").is_some());
+        assert!(fuzzy_score("slg", "Logs").is_none());
+
+        // Word starts and consecutive characters win:
+        let word_start = fuzzy_score("sv", "Scene View").unwrap();
+        let scattered = fuzzy_score("sv", "Observer").unwrap();
+        assert!(scattered < word_start);
+        let consecutive = fuzzy_score("plot", "Plot").unwrap();
+        let spread = fuzzy_score("plot", "Pilot").unwrap();
+        assert!(spread < consecutive);
+    }
+}
@@ -0,0 +1,229 @@
+use egui::Rect;
+
+use crate::tile_id_hash::TileIdMap;
+use crate::tiles::LayoutParams;
+use crate::{Behavior, Container, TileId};
+
+/// Remembers where each container put its children last time it was laid out.
+///
+/// A container that has the same rect and the same contents as last time
+/// will put its children in the same places, so we can skip laying it out again.
+///
+/// On top of that we remember the result of laying out each whole tree,
+/// keyed on a hash of its structure, so that a static layout costs
+/// a single walk over the tree instead of a lookup per container.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LayoutCache {
+    /// The style the cached layouts were computed with.
+    style: Option<egui::Style>,
+
+    /// The params the cached layouts were computed with.
+    params: Option<LayoutParams>,
+
+    /// The values from the [`Behavior`] the cached layouts were computed with.
+    values: Option<LayoutValues>,
+
+    containers: TileIdMap<CachedLayout>,
+
+    /// Keyed by the root of each tree we laid out.
+    trees: TileIdMap<CachedTree>,
+}
+
+/// Where a container is laid out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LayoutArea {
+    pub rect: Rect,
+
+    /// Only matters for grids, since those are the only containers that look at it.
+    pub viewport: Option<Rect>,
+
+    /// How deep in the tree the container is, which decides the gaps between its children.
+    pub depth: usize,
+
+    /// The gap between the children, from [`Behavior::gap_width_at_depth`].
+    pub gap: f32,
+}
+
+/// The values from the [`Behavior`] that the layout of every container depends on.
+///
+/// These are asked for each frame, so a [`Behavior`] (or its [`crate::TileTreeStyle`])
+/// can change them without calling [`crate::Tiles::invalidate_layout`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LayoutValues {
+    min_size: f32,
+    tab_bar_height: f32,
+    title_bar_height: f32,
+    section_header_height: f32,
+}
+
+impl LayoutValues {
+    pub fn new<Pane>(behavior: &dyn Behavior<Pane>, style: &egui::Style) -> Self {
+        Self {
+            min_size: behavior.min_size(),
+            tab_bar_height: behavior.tab_bar_height(style),
+            title_bar_height: behavior.title_bar_height(style),
+            section_header_height: behavior.section_header_height(style),
+        }
+    }
+}
+
+/// A call to [`Behavior::grid_auto_column_count`] made while laying out a grid, and its answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct AutoColumnCount {
+    pub num_visible_children: usize,
+    pub rect: Rect,
+    pub gap: f32,
+    pub num_cols: usize,
+}
+
+impl AutoColumnCount {
+    /// Would the [`Behavior`] still give the same answer?
+    pub fn is_unchanged<Pane>(&self, behavior: &dyn Behavior<Pane>) -> bool {
+        behavior.grid_auto_column_count(self.num_visible_children, self.rect, self.gap)
+            == self.num_cols
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CachedLayout {
+    area: LayoutArea,
+
+    /// The container as it was right after it was laid out.
+    container: Container,
+
+    /// If this is a grid with [`crate::GridLayout::Auto`], how many columns it got.
+    pub auto_column_count: Option<AutoColumnCount>,
+
+    pub children: Vec<(TileId, Rect)>,
+
+    /// Children outside the viewport, that got a rect but no layout.
+    pub placeholders: Vec<(TileId, Rect)>,
+}
+
+#[derive(Clone, Debug)]
+struct CachedTree {
+    rect: Rect,
+    viewport: Option<Rect>,
+
+    /// Hash of everything in the tree that affects the layout.
+    structure: u64,
+
+    /// Every tile in the tree, and where it went.
+    rects: Vec<(TileId, Rect)>,
+
+    /// How many columns each grid with [`crate::GridLayout::Auto`] in the tree got.
+    auto_column_counts: Vec<AutoColumnCount>,
+}
+
+impl LayoutCache {
+    /// Forget everything if the style, params, or layout values changed since last time.
+    pub fn check_style(&mut self, style: &egui::Style, params: LayoutParams, values: LayoutValues) {
+        if self.style.as_ref() != Some(style)
+            || self.params != Some(params)
+            || self.values != Some(values)
+        {
+            self.clear();
+            self.style = Some(style.clone());
+            self.params = Some(params);
+            self.values = Some(values);
+        }
+    }
+
+    /// Where the children (and placeholders) of this container went last time,
+    /// if nothing has changed since then.
+    pub fn get<Pane>(
+        &self,
+        tile_id: TileId,
+        area: LayoutArea,
+        container: &Container,
+        behavior: &dyn Behavior<Pane>,
+    ) -> Option<&CachedLayout> {
+        let cached = self.containers.get(&tile_id)?;
+        let same_viewport =
+            cached.area.viewport == area.viewport || !matches!(container, Container::Grid(_));
+        let same_area = cached.area.rect == area.rect
+            && cached.area.depth == area.depth
+            && cached.area.gap == area.gap
+            && same_viewport;
+        let same_columns = cached
+            .auto_column_count
+            .map_or(true, |count| count.is_unchanged(behavior));
+        (same_area && cached.container == *container && same_columns).then_some(cached)
+    }
+
+    pub fn insert(
+        &mut self,
+        tile_id: TileId,
+        area: LayoutArea,
+        container: &Container,
+        auto_column_count: Option<AutoColumnCount>,
+        children: &[(TileId, Rect)],
+        placeholders: &[(TileId, Rect)],
+    ) {
+        self.containers.insert(
+            tile_id,
+            CachedLayout {
+                area,
+                container: container.clone(),
+                auto_column_count,
+                children: children.to_vec(),
+                placeholders: placeholders.to_vec(),
+            },
+        );
+    }
+
+    /// Where every tile in the tree went last time,
+    /// if the tree had the same structure, rect and viewport then,
+    /// and its grids would get the same number of columns.
+    pub fn get_tree<Pane>(
+        &self,
+        root: TileId,
+        rect: Rect,
+        viewport: Option<Rect>,
+        structure: u64,
+        behavior: &dyn Behavior<Pane>,
+    ) -> Option<&[(TileId, Rect)]> {
+        let cached = self.trees.get(&root)?;
+        let same_columns = cached
+            .auto_column_counts
+            .iter()
+            .all(|count| count.is_unchanged(behavior));
+        (cached.rect == rect
+            && cached.viewport == viewport
+            && cached.structure == structure
+            && same_columns)
+            .then_some(&cached.rects[..])
+    }
+
+    pub fn insert_tree(
+        &mut self,
+        root: TileId,
+        rect: Rect,
+        viewport: Option<Rect>,
+        structure: u64,
+        rects: Vec<(TileId, Rect)>,
+        auto_column_counts: Vec<AutoColumnCount>,
+    ) {
+        self.trees.insert(
+            root,
+            CachedTree {
+                rect,
+                viewport,
+                structure,
+                rects,
+                auto_column_counts,
+            },
+        );
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(TileId) -> bool) {
+        self.containers.retain(|&tile_id, _| keep(tile_id));
+        self.trees.retain(|&tile_id, _| keep(tile_id));
+    }
+
+    pub fn clear(&mut self) {
+        self.style = None;
+        self.containers.clear();
+        self.trees.clear();
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{Container, ContainerKind, Tile, TileId, Tiles, Tree};
+
+/// A simplified, declarative description of a layout,
+/// similar to the layout descriptions used by Visual Studio Code.
+///
+/// This is easy to author by hand or to generate with other tools,
+/// and can be turned into a [`Tree`] with [`Tree::from_layout_description`].
+/// Panes are referred to by a `Key`, e.g. a string identifier.
+///
+/// In JSON it looks like this:
+///
+/// ```json
+/// {
+///   "kind": "Horizontal",
+///   "groups": [
+///     { "size": 1.0, "pane": "files" },
+///     { "size": 3.0, "kind": "Tabs", "groups": [{ "pane": "editor" }, { "pane": "preview" }] }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct LayoutDescription<Key> {
+    /// The size relative to the other groups in a horizontal or vertical parent.
+    #[cfg_attr(feature = "serde", serde(default = "default_size"))]
+    pub size: f32,
+
+    /// If set, this is a leaf showing the pane with this key, and [`Self::groups`] is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub pane: Option<Key>,
+
+    /// How to lay out [`Self::groups`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: ContainerKind,
+
+    /// The children of this group.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub groups: Vec<Self>,
+}
+
+#[cfg(feature = "serde")]
+fn default_size() -> f32 {
+    1.0
+}
+
+impl<Key> LayoutDescription<Key> {
+    /// A leaf showing the pane with the given key.
+    pub fn pane(key: Key) -> Self {
+        Self {
+            size: 1.0,
+            pane: Some(key),
+            kind: ContainerKind::default(),
+            groups: Vec::new(),
+        }
+    }
+
+    /// A container of the given groups.
+    pub fn group(kind: ContainerKind, groups: Vec<Self>) -> Self {
+        Self {
+            size: 1.0,
+            pane: None,
+            kind,
+            groups,
+        }
+    }
+
+    /// Set the size relative to the other groups in a horizontal or vertical parent.
+    #[inline]
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Instantiate a tree from a [`LayoutDescription`], creating each pane from its key.
+    ///
+    /// Empty groups are skipped.
+    pub fn from_layout_description<Key>(
+        id: impl Into<egui::Id>,
+        description: &LayoutDescription<Key>,
+        mut create: impl FnMut(&Key) -> Pane,
+    ) -> Self {
+        let mut tiles = Tiles::default();
+        match insert_description(&mut tiles, description, &mut create) {
+            Some(root) => Self::new(id, root, tiles),
+            None => Self::empty(id),
+        }
+    }
+
+    /// Describe the layout of this tree, identifying each pane by a key.
+    ///
+    /// Returns `None` for an empty tree.
+    ///
+    /// Only the structure and the sizes of horizontal and vertical children are kept;
+    /// invisible and auto-hidden tiles are left out.
+    pub fn to_layout_description<Key>(
+        &self,
+        mut key_of: impl FnMut(&Pane) -> Key,
+    ) -> Option<LayoutDescription<Key>> {
+        describe_tile(&self.tiles, self.root?, &mut key_of)
+    }
+}
+
+fn insert_description<Key, Pane>(
+    tiles: &mut Tiles<Pane>,
+    description: &LayoutDescription<Key>,
+    create: &mut impl FnMut(&Key) -> Pane,
+) -> Option<TileId> {
+    if let Some(key) = &description.pane {
+        return Some(tiles.insert_pane(create(key)));
+    }
+
+    let mut children = vec![];
+    let mut sizes = vec![];
+    for group in &description.groups {
+        if let Some(child) = insert_description(tiles, group, create) {
+            children.push(child);
+            sizes.push(group.size);
+        }
+    }
+    if children.is_empty() {
+        return None;
+    }
+
+    let mut container = Container::new(description.kind, children.clone());
+    if let Container::Linear(linear) = &mut container {
+        for (child, size) in children.into_iter().zip(sizes) {
+            if size.is_finite() && 0.0 < size {
+                linear.shares[child] = size;
+            }
+        }
+    }
+    Some(tiles.insert_container(container))
+}
+
+fn describe_tile<Key, Pane>(
+    tiles: &Tiles<Pane>,
+    tile_id: TileId,
+    key_of: &mut impl FnMut(&Pane) -> Key,
+) -> Option<LayoutDescription<Key>> {
+    if !tiles.is_visible(tile_id) {
+        return None;
+    }
+
+    match tiles.get(tile_id)? {
+        Tile::Pane(pane) => Some(LayoutDescription::pane(key_of(pane))),
+        Tile::Container(container) => {
+            let groups = container
+                .children()
+                .filter_map(|&child| {
+                    let group = describe_tile(tiles, child, key_of)?;
+                    Some(match container {
+                        Container::Linear(linear) => group.with_size(linear.shares[child]),
+                        Container::Tabs(_) | Container::Grid(_) => group,
+                    })
+                })
+                .collect();
+            Some(LayoutDescription::group(container.kind(), groups))
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use egui::Rect;
+
+use crate::format_debug::write_tile_tree;
+use crate::{Behavior, Container, Tile, TileId, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// A compact text rendering of where each tile goes when the tree is laid out in `rect`,
+    /// for golden tests of layouts.
+    ///
+    /// Uses [`Self::compute_layout`], so no [`egui::Ui`] is needed.
+    /// Tiles are listed depth-first in the order they are shown,
+    /// with the position and size of each, rounded to a tenth of a point.
+    /// Tiles that are not shown (e.g. inactive tabs) are marked as `hidden`.
+    ///
+    /// The output only depends on the tree and the given arguments,
+    /// so you can check it into your repository and compare against it in a test:
+    ///
+    /// ```text
+    /// #3 Horizontal 0,0 201x100
+    ///   #1 Pane "Files" 0,0 100x100
+    ///   #2 Pane "Console" 101,0 100x100
+    /// ```
+    pub fn layout_snapshot(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        style: &egui::Style,
+        rect: Rect,
+    ) -> String {
+        let rects = self.compute_layout(behavior, style, rect);
+
+        let mut out = String::new();
+        if let Some(root) = self.root {
+            let mut write_line = |out: &mut String,
+                                  tile_id,
+                                  _parent: Option<&Container>,
+                                  tile: Option<&Tile<Pane>>| {
+                snapshot_tile(out, behavior, &rects, tile_id, tile)
+            };
+            write_tile_tree(&mut out, &self.tiles, 0, root, None, &mut write_line).ok();
+        } else {
+            writeln!(out, "(empty)").ok();
+        }
+        out
+    }
+}
+
+fn snapshot_tile<Pane>(
+    out: &mut String,
+    behavior: &mut dyn Behavior<Pane>,
+    rects: &BTreeMap<TileId, Rect>,
+    tile_id: TileId,
+    tile: Option<&Tile<Pane>>,
+) -> std::fmt::Result {
+    write!(out, "{tile_id:?} ")?;
+
+    match tile {
+        None => return write!(out, "DANGLING"),
+        Some(Tile::Pane(pane)) => {
+            write!(out, "Pane {:?}", behavior.tab_title_for_pane(pane).text())?;
+        }
+        Some(Tile::Container(container)) => {
+            write!(out, "{:?}", container.kind())?;
+        }
+    }
+
+    if let Some(rect) = rects.get(&tile_id) {
+        write!(
+            out,
+            " {},{} {}x{}",
+            format_coordinate(rect.min.x),
+            format_coordinate(rect.min.y),
+            format_coordinate(rect.width()),
+            format_coordinate(rect.height()),
+        )
+    } else {
+        write!(out, " hidden")
+    }
+}
+
+/// Rounded to one decimal, without a trailing `.0`, so that snapshots stay short
+/// and don't change because of floating point noise.
+fn format_coordinate(value: f32) -> String {
+    let rounded = (value * 10.0).round() / 10.0 + 0.0; // + 0.0 turns -0.0 into 0.0
+    if rounded == rounded.trunc() {
+        format!("{rounded:.0}")
+    } else {
+        format!("{rounded:.1}")
+    }
+}
@@ -0,0 +1,51 @@
+use egui::Rect;
+
+use crate::tile_id_hash::TileIdMap;
+use crate::TileId;
+
+/// Animates tiles from where they were right before the layout changed (e.g. on a drop)
+/// to where they are laid out now.
+#[derive(Clone, Debug)]
+pub(crate) struct LayoutTransition {
+    /// Where each tile was shown right before the change.
+    from: TileIdMap<Rect>,
+
+    start_time: f64,
+
+    /// In seconds.
+    duration: f32,
+
+    /// How far along we are this frame (0-1), after easing.
+    t: f32,
+}
+
+impl LayoutTransition {
+    pub fn new(from: TileIdMap<Rect>, start_time: f64, duration: f32) -> Self {
+        Self {
+            from,
+            start_time,
+            duration,
+            t: 0.0,
+        }
+    }
+
+    /// Move the transition along to the given time.
+    ///
+    /// Returns `false` once it is done.
+    pub fn update(&mut self, now: f64, easing: impl Fn(f32) -> f32) -> bool {
+        let t = if 0.0 < self.duration {
+            (now - self.start_time) as f32 / self.duration
+        } else {
+            1.0
+        };
+        self.t = easing(t.clamp(0.0, 1.0));
+        t < 1.0
+    }
+
+    /// Where to show a tile that was laid out at `rect`.
+    pub fn rect(&self, tile_id: TileId, rect: Rect) -> Rect {
+        self.from
+            .get(&tile_id)
+            .map_or(rect, |from| from.lerp_towards(&rect, self.t))
+    }
+}
@@ -19,7 +19,8 @@
 //! ## Example
 //! See [`Tree`] for how to construct a tree.
 //!
-//! ```
+#![cfg_attr(feature = "serde", doc = "```")]
+#![cfg_attr(not(feature = "serde"), doc = "```ignore")]
 //! // This specifies how you want to represent your panes in memory.
 //! // Implementing serde is optional, but will make the entire tree serializable.
 //! #[derive(serde::Serialize, serde::Deserialize)]
@@ -75,11 +76,112 @@
 //! }
 //! ```
 //!
+//! ## Feature flags
+//! * `serde` (enabled by default): implements `serde::Serialize` and `serde::Deserialize`
+//!   for [`Tree`], [`Tiles`], [`TileId`], and all the containers.
+//!   Turn off the default features if you don't need to persist your layouts.
+//! * `egui_dock`: adds `Tree::from_egui_dock` for importing layouts from
+//!   [`egui_dock`](https://github.com/Adanos020/egui_dock).
+//! * `profiling`: adds [`profiling`](https://docs.rs/profiling) scopes to layout, tile ui,
+//!   drag-and-drop, simplification, and garbage collection.
+//!   Pick a backend in your application, e.g. with the `profiling/profile-with-puffin` feature.
+//! * `fast_tile_id_hash` (enabled by default): use a cheap hash function for the internal maps
+//!   keyed by [`TileId`], instead of `ahash`.
+//! * `test_support`: adds the `test_support` module, with helpers for simulating drags,
+//!   tab clicks, and resizes against a tree in a headless egui context.
+//!
+//! ## Serialization format
+//! With the `serde` feature, a [`Tree`] is serialized as a plain structure that is stable
+//! within a [`FORMAT_VERSION`], so layouts written by one release load in the next,
+//! and other tools can generate them. In JSON it looks like this:
+//!
+//! ```json
+//! {
+//!   "format_version": 1,
+//!   "id": 42,
+//!   "root": 1,
+//!   "tiles": {
+//!     "next_tile_id": 6,
+//!     "tiles": {
+//!       "1": { "Container": { "Tabs": { "children": [2, 3], "active": 2 } } },
+//!       "2": { "Pane": "…your pane…" },
+//!       "3": { "Container": { "Linear": {
+//!         "children": [4], "dir": "Vertical", "shares": { "shares": { "4": 1.0 } }
+//!       } } },
+//!       "4": { "Pane": "…your pane…" },
+//!       "5": { "Pane": "…your pane…" }
+//!     },
+//!     "invisible": [],
+//!     "metadata": { "2": { "user-created": true } }
+//!   },
+//!   "height": null,
+//!   "width": null,
+//!   "auto_hidden": [[5, "Left"]]
+//! }
+//! ```
+//!
+//! * `format_version`: see [`FORMAT_VERSION`]. Defaults to `0` (before versioning); call [`Tree::migrate`] to upgrade.
+//! * `id`: the [`egui::Id`] of the tree, as a non-zero integer.
+//! * `root`: the [`TileId`] of the root tile, or `null` for an empty tree.
+//! * `tiles.tiles`: a map from [`TileId`] to [`Tile`], which is tagged as either `Pane` or `Container`.
+//!   A container is tagged as `Tabs`, `Linear`, or `Grid`:
+//!   * `Tabs`: `children`, and the `active` child (default `null`).
+//!   * `Linear`: `children`, `dir` (`"Horizontal"` or `"Vertical"`), and `shares` per child (default `1.0` for missing ones).
+//!     Optionally `collapsible` (default `false`) and the `collapsed` children, see [`Linear::collapsible`],
+//!     and `share_redistribution` (default `"Proportional"`).
+//!     Also `overflow` (`"Shrink"` or `"Scroll"`, default `"Shrink"`) and per-child `min_sizes` (default empty).
+//!   * `Grid`: `children` in row-major order with `null` for holes,
+//!     `layout` (`"Auto"` or `{ "Columns": n }`, default `"Auto"`), and `col_shares` and `row_shares` (default empty).
+//!     Optionally `template_areas` (one string of area names per row) and `area_children`
+//!     (a map from area name to child), see [`Grid::set_template_areas`].
+//! * `tiles.next_tile_id` (default `1`): a hint for allocating new ids.
+//! * `tiles.invisible` (default empty): the ids of invisible tiles.
+//! * `tiles.metadata` (default empty): per tile, a map of app-defined values,
+//!   see [`Tiles::set_metadata`].
+//! * `height`, `width` (default `null`): a fixed size for the tree, or `null` for the available space.
+//! * `auto_hidden` (default empty): auto-hidden tiles and the [`Edge`] they are collapsed into.
+//! * `modal` (optional): the tile shown as a modal, see [`Tree::enter_modal`].
+//! * `tab_links` (default empty): groups of tabs that are activated together, see [`Tree::link_tabs`].
+//!
+//! All UI state that is meaningful to restore is part of this: the active tab of each [`Tabs`],
+//! the shares, which tiles are invisible or auto-hidden, and which tile is shown as a modal.
+//! Purely transient state, like tab bar scrolling or an auto-hide flyout being open, is not.
+//!
+//! Tiles that are not reachable from the root (or an auto-hidden tile) are dropped,
+//! and dangling references are removed, the first time the tree is shown.
+//! Use [`Tree::split_panes`] if you want to store the panes separately,
+//! or [`Tree::save_panes`] if your panes don't implement `serde`.
+//!
 //! ## Invisible tiles
 //! Tiles can be made invisible with [`Tree::set_visible`] and [`Tiles::set_visible`].
 //! Invisible tiles still retain their ordering in the container their in until
 //! they are made visible again.
 //!
+//! ## Shared panes
+//! Each pane tile owns its `Pane`, but nothing stops that `Pane` from being a handle,
+//! e.g. an `Rc<RefCell<View>>` or a key into your own storage.
+//! This way the same underlying view can be shown in several tiles at once:
+//!
+//! ```
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! #[derive(Clone)]
+//! struct SharedView(Rc<RefCell<String>>);
+//!
+//! let mut tiles = egui_tiles::Tiles::default();
+//! let first = tiles.insert_pane(SharedView(Rc::new(RefCell::new("Hello".to_owned()))));
+//! let second = tiles.insert_pane_copy(first).unwrap();
+//! let root = tiles.insert_horizontal_tile(vec![first, second]);
+//! let tree = egui_tiles::Tree::new("my_tree", root, tiles);
+//! ```
+//!
+//! The library never needs `Pane: Clone`, except for the few methods that copy panes,
+//! like [`Tiles::insert_pane_copy`].
+//! Each tile is still its own tile: closing it, or dropping it with [`Behavior::retain_pane`],
+//! only drops that tile's handle. The shared view lives on for as long as any handle does.
+//! [`Behavior::pane_ui`] is called once per tile, with that tile's [`TileId`],
+//! so use that id for any per-tile UI state.
+//!
 //! ## Shares
 //! The relative sizes of linear layout (horizontal or vertical) and grid columns and rows are specified by _shares_.
 //! If the shares are `1,2,3` it means the first element gets `1/6` of the space, the second `2/6`, and the third `3/6`.
@@ -97,9 +199,10 @@
 
 // ## Implementation notes
 // In many places we want to recursively visit all tiles, while also mutating them.
-// In order to not get into trouble with the borrow checker a trick is used:
-// each [`Tile`] is removed, mutated, recursed, and then re-added.
-// You'll see this pattern many times reading the following code.
+// In order to not get into trouble with the borrow checker, we visit the tiles in place
+// and copy out the child ids before recursing, or (during layout) borrow the fields of
+// [`Tiles`] separately and let each container push its children onto a stack.
+// A tile is never removed from the tree just to be visited, so a panicking callback can't lose it.
 //
 // Each frame consists of two passes: layout, and ui.
 // The layout pass figures out where each tile should be placed.
@@ -116,17 +219,82 @@
 
 use egui::{Pos2, Rect};
 
+/// Start a profiling scope that lasts until the end of the current block,
+/// if the `profiling` feature is enabled.
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "profiling")]
+        profiling::scope!($name);
+    };
+}
+
+mod auto_hide;
 mod behavior;
+mod breadcrumbs;
+mod command;
+mod compact_layout;
 mod container;
+mod debug_overlay;
+#[cfg(feature = "egui_dock")]
+mod dock_import;
+mod duplicate;
+mod edit_mode;
+mod events;
+mod filter;
+mod focus;
+mod format_debug;
+mod history;
+mod insert_next_to;
+mod inspector;
+mod jump_to_pane;
+mod layout_cache;
+mod layout_description;
+mod layout_snapshot;
+mod layout_transition;
+mod metadata;
+mod min_size;
+mod minimap;
+mod pane_factory;
+#[cfg(feature = "serde")]
+mod pane_serde;
+mod parent_index;
+mod style;
+mod tab_links;
+mod tab_selection;
 mod tile;
+mod tile_id_hash;
+mod tile_store;
 mod tiles;
+mod transpose;
 mod tree;
-
-pub use behavior::{Behavior, EditAction, TabState};
-pub use container::{Container, ContainerKind, Grid, GridLayout, Linear, LinearDir, Shares, Tabs};
+mod tree_response;
+
+#[cfg(feature = "test_support")]
+pub mod test_support;
+
+pub use auto_hide::Edge;
+pub use behavior::{
+    Behavior, ContainerOverflow, EditAction, EditSummary, TabState, TabSwitchAnimation,
+    VisibilityReason,
+};
+pub use command::Command;
+pub use container::{
+    Container, ContainerKind, DividerAction, Grid, GridEdit, GridGutter, GridLayout, Linear,
+    LinearDir, LinearOverflow, ShareRedistribution, Shares, Tabs,
+};
+pub use edit_mode::{EditMode, EditPermissions};
+pub use events::TreeEvent;
+pub use insert_next_to::NextTo;
+pub use layout_description::LayoutDescription;
+pub use metadata::MetadataValue;
+pub use pane_factory::PaneFactory;
+#[cfg(feature = "serde")]
+pub use pane_serde::PaneSerde;
+pub use style::TileTreeStyle;
 pub use tile::{Tile, TileId};
 pub use tiles::Tiles;
-pub use tree::Tree;
+pub use tree::{Tree, FORMAT_VERSION};
+pub use tree_response::{TileDrop, TreeResponse};
 
 // ----------------------------------------------------------------------------
 
@@ -171,6 +339,19 @@ pub struct SimplificationOptions {
     /// If a horizontal container contain another horizontal container, join them?
     /// Same for vertical containers. Does NOT apply to grid container or tab containers.
     pub join_nested_linear_containers: bool,
+
+    /// If a [`Grid`] contains nothing but another grid, replace the outer grid with the inner one?
+    ///
+    /// The outer grid keeps its [`TileId`].
+    /// This applies even if [`Self::prune_single_child_containers`] is off.
+    pub join_nested_grids: bool,
+
+    /// Turn a [`Grid`] whose children fill exactly one row or one column
+    /// into a horizontal or vertical [`Linear`] container, keeping the sizes of the children?
+    ///
+    /// Only applies to grids with a fixed [`GridLayout::Columns`] and no holes,
+    /// since an auto grid may pick a different number of columns when resized.
+    pub convert_single_line_grids: bool,
 }
 
 impl SimplificationOptions {
@@ -192,6 +373,8 @@ impl SimplificationOptions {
         prune_single_child_containers: false,
         all_panes_must_have_tabs: false,
         join_nested_linear_containers: false,
+        join_nested_grids: false,
+        convert_single_line_grids: false,
     };
 }
 
@@ -204,10 +387,60 @@ impl Default for SimplificationOptions {
             prune_single_child_containers: true,
             all_panes_must_have_tabs: false,
             join_nested_linear_containers: true,
+            join_nested_grids: true,
+            convert_single_line_grids: false,
         }
     }
 }
 
+/// What [`Tree::simplify`] changed, listing the [`TileId`]s of the affected containers.
+///
+/// All but the [`Self::converted`] containers are gone from the tree afterwards.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimplifyReport {
+    /// Empty containers that were removed.
+    pub pruned: Vec<TileId>,
+
+    /// Single-child containers that were replaced by their only child.
+    pub collapsed: Vec<TileId>,
+
+    /// Containers whose children were moved into their parent,
+    /// see [`SimplificationOptions::join_nested_linear_containers`]
+    /// and [`SimplificationOptions::join_nested_grids`].
+    pub joined: Vec<TileId>,
+
+    /// Grids that were turned into [`Linear`] containers (keeping their [`TileId`]),
+    /// see [`SimplificationOptions::convert_single_line_grids`].
+    pub converted: Vec<TileId>,
+}
+
+impl SimplifyReport {
+    /// Was nothing changed?
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            pruned,
+            collapsed,
+            joined,
+            converted,
+        } = self;
+        pruned.is_empty() && collapsed.is_empty() && joined.is_empty() && converted.is_empty()
+    }
+}
+
+/// Which panes [`Tree::normalize`] wraps in a [`Tabs`] container of their own.
+///
+/// Panes that [`Behavior::wrap_pane_in_tabs`] says no to are never wrapped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NormalizePolicy {
+    /// Every pane that isn't already in a [`Tabs`] container,
+    /// just like [`SimplificationOptions::all_panes_must_have_tabs`].
+    #[default]
+    AllPanes,
+
+    /// Only a pane at the root, and the panes directly in the root container.
+    TopLevelPanes,
+}
+
 /// The current state of a resize handle.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ResizeState {
@@ -220,6 +453,96 @@ pub enum ResizeState {
     Dragging,
 }
 
+/// What will happen if the dragged tile is dropped where it is now.
+///
+/// Used to pick an icon for the drop preview, see [`Behavior::drop_operation_icon`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DropOperation {
+    /// Put it to the left of the target.
+    SplitLeft,
+
+    /// Put it to the right of the target.
+    SplitRight,
+
+    /// Put it above the target.
+    SplitUp,
+
+    /// Put it below the target.
+    SplitDown,
+
+    /// Add it as a new tab.
+    Tab,
+
+    /// Put it into a grid cell.
+    Grid,
+}
+
+/// How to show where a dragged tile will land.
+///
+/// Picked per [`DropOperation`] by [`Behavior::drop_indicator`],
+/// which defaults to [`TileTreeStyle::drop_indicator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum DropIndicator {
+    /// A thin line where the tile will be inserted.
+    Line,
+
+    /// The half of the drop target on the side where the tile will go.
+    HalfRect,
+
+    /// The whole drop target.
+    #[default]
+    FilledRect,
+}
+
+impl DropIndicator {
+    /// The part of the drop target `rect` to paint for the given operation.
+    ///
+    /// Tabs and grid cells have no side, so [`Self::HalfRect`] shows all of them,
+    /// and [`Self::Line`] shows their top and left edge respectively.
+    pub fn rect(self, rect: Rect, operation: DropOperation, line_width: f32) -> Rect {
+        let center = rect.center();
+        match self {
+            Self::FilledRect => rect,
+            Self::HalfRect => match operation {
+                DropOperation::SplitLeft => rect.with_max_x(center.x),
+                DropOperation::SplitRight => rect.with_min_x(center.x),
+                DropOperation::SplitUp => rect.with_max_y(center.y),
+                DropOperation::SplitDown => rect.with_min_y(center.y),
+                DropOperation::Tab | DropOperation::Grid => rect,
+            },
+            Self::Line => match operation {
+                DropOperation::SplitLeft | DropOperation::Grid => {
+                    rect.with_max_x(rect.left() + line_width)
+                }
+                DropOperation::SplitRight => rect.with_min_x(rect.right() - line_width),
+                DropOperation::SplitUp | DropOperation::Tab => {
+                    rect.with_max_y(rect.top() + line_width)
+                }
+                DropOperation::SplitDown => rect.with_min_y(rect.bottom() - line_width),
+            },
+        }
+    }
+}
+
+/// The [`egui::DragAndDrop`] payload while a tile is being dragged.
+///
+/// This lets other widgets in your app react to tiles being dragged over them,
+/// with [`egui::Response::dnd_hover_payload`] and [`egui::Response::dnd_release_payload`],
+/// e.g. for a "trash" drop zone that closes the pane.
+///
+/// It also works the other way: setting this payload
+/// (e.g. with [`egui::Response::dnd_set_drag_payload`])
+/// drags that tile around the tree, just like dragging its tab.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DraggedTile {
+    /// The [`Tree::id`] of the tree the tile is in.
+    pub tree_id: egui::Id,
+
+    /// The tile being dragged.
+    pub tile_id: TileId,
+}
+
 // ----------------------------------------------------------------------------
 
 /// An insertion point in a specific container.
@@ -270,6 +593,19 @@ impl InsertionPoint {
             insertion,
         }
     }
+
+    /// Which way the preview rect sits in the parent decides e.g. left vs right.
+    fn operation(self, preview_rect: Rect, parent_rect: Rect) -> DropOperation {
+        let offset = preview_rect.center() - parent_rect.center();
+        match self.insertion {
+            ContainerInsertion::Tabs(_) => DropOperation::Tab,
+            ContainerInsertion::Horizontal(_) if offset.x < 0.0 => DropOperation::SplitLeft,
+            ContainerInsertion::Horizontal(_) => DropOperation::SplitRight,
+            ContainerInsertion::Vertical(_) if offset.y < 0.0 => DropOperation::SplitUp,
+            ContainerInsertion::Vertical(_) => DropOperation::SplitDown,
+            ContainerInsertion::Grid(_) => DropOperation::Grid,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -288,6 +624,8 @@ enum SimplifyAction {
 pub(crate) fn is_being_dragged(ctx: &egui::Context, tree_id: egui::Id, tile_id: TileId) -> bool {
     let dragged_id = ctx.dragged_id().or(ctx.drag_stopped_id());
     dragged_id == Some(tile_id.egui_id(tree_id))
+        || egui::DragAndDrop::payload::<DraggedTile>(ctx)
+            .is_some_and(|payload| *payload == DraggedTile { tree_id, tile_id })
 }
 
 /// If this tile is currently being dragged, cover it with a semi-transparent overlay ([`Behavior::dragged_overlay_color`]).
@@ -319,9 +657,37 @@ struct DropContext {
     best_insertion: Option<InsertionPoint>,
     best_dist_sq: f32,
     preview_rect: Option<Rect>,
+
+    /// Scratch space for the tab bars, reused across frames.
+    tab_button_rects: tile_id_hash::TileIdMap<Rect>,
+
+    /// Tiles that don't allow drops, see [`EditPermissions::drop_into`].
+    no_drop_into: tile_id_hash::TileIdSet,
+
+    /// Horizontal containers start at the right, see [`Tree::set_right_to_left`].
+    right_to_left: bool,
+
+    /// If set, docked panes are collected here instead of being shown, see [`Tree::ui_deferred`].
+    deferred_panes: Option<Vec<(TileId, Rect)>>,
 }
 
 impl DropContext {
+    /// For showing tiles that nothing can be dropped onto.
+    fn disabled() -> Self {
+        Self {
+            enabled: false,
+            dragged_tile_id: None,
+            mouse_pos: None,
+            best_insertion: None,
+            best_dist_sq: f32::INFINITY,
+            preview_rect: None,
+            tab_button_rects: Default::default(),
+            no_drop_into: Default::default(),
+            right_to_left: false,
+            deferred_panes: None,
+        }
+    }
+
     fn on_tile<Pane>(
         &mut self,
         behavior: &dyn Behavior<Pane>,
@@ -333,15 +699,20 @@ impl DropContext {
         if !self.enabled {
             return;
         }
+        profile_scope!("DropContext::on_tile");
 
         if tile.kind() != Some(ContainerKind::Horizontal) {
+            let (mut first, mut last) = rect.split_left_right_at_fraction(0.5);
+            if self.right_to_left {
+                std::mem::swap(&mut first, &mut last);
+            }
             self.suggest_rect(
                 InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(0)),
-                rect.split_left_right_at_fraction(0.5).0,
+                first,
             );
             self.suggest_rect(
                 InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(usize::MAX)),
-                rect.split_left_right_at_fraction(0.5).1,
+                last,
             );
         }
 
@@ -363,8 +734,21 @@ impl DropContext {
         );
     }
 
+    /// Like [`Self::suggest_rect`], but this one wins while the mouse is inside `zone`,
+    /// unless an earlier zone already did.
+    fn suggest_zone(&mut self, insertion: InsertionPoint, zone: Rect, preview_rect: Rect) {
+        if !self.enabled || self.no_drop_into.contains(&insertion.parent_id) {
+            return;
+        }
+        if 0.0 < self.best_dist_sq && self.mouse_pos.is_some_and(|pos| zone.contains(pos)) {
+            self.best_dist_sq = 0.0;
+            self.best_insertion = Some(insertion);
+            self.preview_rect = Some(preview_rect);
+        }
+    }
+
     fn suggest_rect(&mut self, insertion: InsertionPoint, preview_rect: Rect) {
-        if !self.enabled {
+        if !self.enabled || self.no_drop_into.contains(&insertion.parent_id) {
             return;
         }
         let target_point = preview_rect.center();
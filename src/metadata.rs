@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use crate::{TileId, Tiles};
+
+/// A value attached to a tile with [`Tiles::set_metadata`].
+///
+/// Serialized as a plain JSON-like value, e.g. `true`, `42`, `0.5`, or `"text"`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum MetadataValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl MetadataValue {
+    /// The text, if this is a [`Self::String`].
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for MetadataValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i32> for MetadataValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<i64> for MetadataValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f32> for MetadataValue {
+    fn from(value: f32) -> Self {
+        Self::Float(value.into())
+    }
+}
+
+impl From<f64> for MetadataValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for MetadataValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for MetadataValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl TryFrom<&MetadataValue> for bool {
+    type Error = ();
+
+    fn try_from(value: &MetadataValue) -> Result<Self, ()> {
+        match value {
+            MetadataValue::Bool(value) => Ok(*value),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<&MetadataValue> for i64 {
+    type Error = ();
+
+    fn try_from(value: &MetadataValue) -> Result<Self, ()> {
+        match value {
+            MetadataValue::Int(value) => Ok(*value),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<&MetadataValue> for f64 {
+    type Error = ();
+
+    /// Integers are converted too, since e.g. JSON doesn't tell `1.0` from `1`.
+    fn try_from(value: &MetadataValue) -> Result<Self, ()> {
+        match value {
+            MetadataValue::Int(value) => Ok(*value as Self),
+            MetadataValue::Float(value) => Ok(*value),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<&MetadataValue> for String {
+    type Error = ();
+
+    fn try_from(value: &MetadataValue) -> Result<Self, ()> {
+        value.as_str().map(ToOwned::to_owned).ok_or(())
+    }
+}
+
+/// Metadata of one tile, keyed by name.
+pub(crate) type TileMetadata = BTreeMap<String, MetadataValue>;
+
+impl<Pane> Tiles<Pane> {
+    /// Attach a value to a tile, e.g. a `"user-created"` flag or tool-specific data.
+    ///
+    /// This is stored and serialized together with the tiles,
+    /// and dropped when the tile is removed from the tree,
+    /// so you don't need to keep a separate map keyed by [`TileId`].
+    pub fn set_metadata(
+        &mut self,
+        tile_id: TileId,
+        key: impl Into<String>,
+        value: impl Into<MetadataValue>,
+    ) {
+        self.metadata
+            .entry(tile_id)
+            .or_default()
+            .insert(key.into(), value.into());
+    }
+
+    /// The value set with [`Self::set_metadata`], if any.
+    pub fn metadata(&self, tile_id: TileId, key: &str) -> Option<&MetadataValue> {
+        self.metadata.get(&tile_id)?.get(key)
+    }
+
+    /// The value set with [`Self::set_metadata`], if it is of the given type.
+    ///
+    /// ```
+    /// # let mut tiles = egui_tiles::Tiles::<()>::default();
+    /// # let tile_id = tiles.insert_pane(());
+    /// tiles.set_metadata(tile_id, "user-created", true);
+    /// assert_eq!(tiles.metadata_as::<bool>(tile_id, "user-created"), Some(true));
+    /// assert_eq!(tiles.metadata_as::<String>(tile_id, "user-created"), None);
+    /// ```
+    pub fn metadata_as<T>(&self, tile_id: TileId, key: &str) -> Option<T>
+    where
+        T: for<'a> TryFrom<&'a MetadataValue>,
+    {
+        T::try_from(self.metadata(tile_id, key)?).ok()
+    }
+
+    /// Remove a value set with [`Self::set_metadata`], returning it.
+    pub fn remove_metadata(&mut self, tile_id: TileId, key: &str) -> Option<MetadataValue> {
+        let tile_metadata = self.metadata.get_mut(&tile_id)?;
+        let value = tile_metadata.remove(key);
+        if tile_metadata.is_empty() {
+            self.metadata.remove(&tile_id);
+        }
+        value
+    }
+
+    /// All metadata of a tile, ordered by key.
+    pub fn all_metadata(&self, tile_id: TileId) -> impl Iterator<Item = (&str, &MetadataValue)> {
+        self.metadata
+            .get(&tile_id)
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+}
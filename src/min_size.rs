@@ -0,0 +1,99 @@
+use egui::{vec2, Vec2};
+
+use crate::{Behavior, Container, LinearDir, LinearOverflow, Tile, TileId, Tiles, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// The smallest size the tree can be shown at without squishing any tile
+    /// below its minimum size.
+    ///
+    /// Each pane needs [`Behavior::min_size`] in both directions,
+    /// or more along a [`crate::Linear`] that sets [`crate::Linear::set_min_size`] for it.
+    /// Containers add their tab bars, title bars, and gaps,
+    /// and a [`LinearOverflow::Scroll`] container needs no length at all, since it can scroll.
+    /// A [`crate::GridLayout::Auto`] grid is assumed to keep the columns of its last layout.
+    ///
+    /// Use this e.g. to set the minimum size of the native window.
+    /// See also [`crate::TreeResponse::over_constrained`].
+    pub fn min_size(&self, behavior: &dyn Behavior<Pane>, style: &egui::Style) -> Vec2 {
+        match self.root {
+            Some(root) if self.is_visible(root) => {
+                let depth = 0;
+                self.tiles.min_size(behavior, style, root, depth)
+            }
+            _ => Vec2::ZERO,
+        }
+    }
+}
+
+impl<Pane> Tiles<Pane> {
+    fn min_size(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        style: &egui::Style,
+        tile_id: TileId,
+        depth: usize,
+    ) -> Vec2 {
+        let container = match self.get(tile_id) {
+            Some(Tile::Container(container)) => container,
+            Some(Tile::Pane(_)) => return Vec2::splat(behavior.min_size()),
+            None => return Vec2::ZERO,
+        };
+        let children: Vec<TileId> = container
+            .children()
+            .copied()
+            .filter(|&child| self.is_visible(child))
+            .collect();
+        let child_min_size = |child: TileId| self.min_size(behavior, style, child, depth + 1);
+        let gap = behavior.gap_width_at_depth(style, depth);
+
+        match container {
+            Container::Tabs(_) => {
+                let content = children
+                    .iter()
+                    .map(|&child| child_min_size(child))
+                    .fold(Vec2::ZERO, Vec2::max);
+                content + vec2(0.0, behavior.tab_bar_height(style))
+            }
+
+            Container::Linear(linear) => {
+                let header_height = linear.header_height(behavior, style);
+                let num_gaps = children.len().saturating_sub(1);
+                let mut length = gap * num_gaps as f32;
+                let mut thickness: f32 = 0.0;
+                for &child in &children {
+                    if linear.is_collapsed(child) {
+                        continue; // just its header
+                    }
+                    let min_size = child_min_size(child);
+                    let (child_length, child_thickness) = match linear.dir {
+                        LinearDir::Horizontal => (min_size.x, min_size.y),
+                        LinearDir::Vertical => (min_size.y, min_size.x),
+                    };
+                    length += child_length.max(linear.min_size(child).unwrap_or_default());
+                    thickness = thickness.max(child_thickness);
+                }
+                if linear.overflow == LinearOverflow::Scroll {
+                    length = 0.0;
+                }
+                match linear.dir {
+                    LinearDir::Horizontal => vec2(length, thickness + header_height),
+                    LinearDir::Vertical => {
+                        vec2(thickness, length + header_height * children.len() as f32)
+                    }
+                }
+            }
+
+            Container::Grid(grid) => {
+                let (num_cols, num_rows) = grid.dimensions(|child| self.is_visible(child));
+                let cell = children
+                    .iter()
+                    .map(|&child| child_min_size(child))
+                    .fold(Vec2::ZERO, Vec2::max)
+                    + vec2(0.0, behavior.title_bar_height(style));
+                let width = num_cols as f32 * cell.x + num_cols.saturating_sub(1) as f32 * gap;
+                let height = num_rows as f32 * cell.y + num_rows.saturating_sub(1) as f32 * gap;
+                vec2(width, height)
+            }
+        }
+    }
+}
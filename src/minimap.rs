@@ -0,0 +1,89 @@
+use egui::{emath::RectTransform, Rect, Ui, Vec2};
+
+use crate::{Behavior, TileId, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// A scaled-down picture of where each pane was shown last frame,
+    /// e.g. for an [`egui::Area`] in a corner of a large layout.
+    ///
+    /// Each pane is filled with [`Behavior::minimap_color`] and labeled
+    /// with [`Behavior::tab_title_for_tile`]; the [`Self::focused_pane`] is outlined.
+    /// Clicking a pane focuses it.
+    ///
+    /// Shows an empty frame of the given size until the tree has been shown once.
+    /// Returns the pane that was clicked, if any.
+    pub fn minimap_ui(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+        size: Vec2,
+    ) -> Option<TileId> {
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+        let visuals = ui.visuals();
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(
+            rect,
+            visuals.widgets.noninteractive.rounding,
+            visuals.extreme_bg_color,
+        );
+
+        let mut panes: Vec<(TileId, Rect)> = self
+            .tiles
+            .shown_panes
+            .iter()
+            .filter_map(|&tile_id| Some((tile_id, self.tiles.rect(tile_id)?)))
+            .collect();
+        panes.sort_by_key(|&(tile_id, _)| tile_id);
+        let bounds = panes.iter().fold(Rect::NOTHING, |bounds, &(_, pane_rect)| {
+            bounds.union(pane_rect)
+        });
+        if !bounds.is_positive() {
+            return None;
+        }
+
+        let inner = rect.shrink(2.0);
+        let scale = (inner.width() / bounds.width()).min(inner.height() / bounds.height());
+        let to_screen = RectTransform::from_to(
+            bounds,
+            Rect::from_center_size(inner.center(), scale * bounds.size()),
+        );
+
+        let font_id = egui::FontId::proportional(9.0);
+        let mut clicked = None;
+        for (tile_id, pane_rect) in panes {
+            let pane_rect = to_screen.transform_rect(pane_rect).shrink(0.5);
+            let hovered = response
+                .hover_pos()
+                .is_some_and(|pos| pane_rect.contains(pos));
+
+            let color = behavior.minimap_color(visuals, &self.tiles, tile_id);
+            painter.rect_filled(pane_rect, 1.0, color);
+            let stroke = if self.focused == Some(tile_id) {
+                visuals.selection.stroke
+            } else if hovered {
+                visuals.widgets.hovered.fg_stroke
+            } else {
+                visuals.widgets.noninteractive.bg_stroke
+            };
+            painter.rect_stroke(pane_rect, 1.0, stroke);
+
+            let title = behavior.tab_title_for_tile(&self.tiles, tile_id);
+            painter.with_clip_rect(pane_rect.intersect(rect)).text(
+                pane_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                title.text(),
+                font_id.clone(),
+                visuals.text_color(),
+            );
+
+            if hovered && response.clicked() {
+                clicked = Some(tile_id);
+            }
+        }
+
+        if let Some(tile_id) = clicked {
+            self.focus_pane(tile_id);
+        }
+        clicked
+    }
+}
@@ -0,0 +1,68 @@
+use egui::{vec2, Rect, Ui};
+
+use crate::{Behavior, EditAction, TileId, Tiles};
+
+/// A kind of pane the user can create from the built-in "add pane" menus.
+///
+/// Return these from [`Behavior::pane_factories`],
+/// and create the pane in [`Behavior::create_pane`].
+/// The menus show up on the tab bars, in empty containers and in empty grid cells.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PaneFactory {
+    /// Shown in the menus, and passed back to [`Behavior::create_pane`].
+    pub name: String,
+}
+
+impl PaneFactory {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+/// List the [`Behavior::pane_factories`], and create the pane the user picks.
+///
+/// Returns the new pane, which isn't in any container yet.
+pub(crate) fn add_pane_menu<Pane>(
+    tiles: &mut Tiles<Pane>,
+    behavior: &mut dyn Behavior<Pane>,
+    ui: &mut Ui,
+) -> Option<TileId> {
+    let mut created = None;
+    for factory in behavior.pane_factories() {
+        if ui.button(&factory.name).clicked() {
+            ui.close_menu();
+            if let Some(pane) = behavior.create_pane(&factory) {
+                created = Some(tiles.insert_pane(pane));
+                tiles.on_edit(behavior, EditAction::PaneAdded);
+            }
+        }
+    }
+    created
+}
+
+/// A "➕" button opening [`add_pane_menu`], unless there are no [`Behavior::pane_factories`].
+pub(crate) fn add_pane_button<Pane>(
+    tiles: &mut Tiles<Pane>,
+    behavior: &mut dyn Behavior<Pane>,
+    ui: &mut Ui,
+) -> Option<TileId> {
+    if behavior.pane_factories().is_empty() {
+        return None;
+    }
+    let menu = ui.menu_button("➕", |ui| add_pane_menu(tiles, behavior, ui));
+    menu.response.on_hover_text("Add pane");
+    menu.inner.flatten()
+}
+
+/// An [`add_pane_button`] in the middle of `rect`, e.g. an empty container or grid cell.
+pub(crate) fn add_pane_placeholder<Pane>(
+    tiles: &mut Tiles<Pane>,
+    behavior: &mut dyn Behavior<Pane>,
+    ui: &mut Ui,
+    rect: Rect,
+) -> Option<TileId> {
+    let size = vec2(1.0, 1.0) * ui.spacing().interact_size.y;
+    let button_rect = Rect::from_center_size(rect.center(), size);
+    let mut ui = ui.new_child(egui::UiBuilder::new().max_rect(button_rect));
+    add_pane_button(tiles, behavior, &mut ui)
+}
@@ -0,0 +1,66 @@
+use crate::Tree;
+
+/// Callbacks for persisting panes that don't implement `serde` themselves.
+///
+/// Useful when your panes hold runtime-only state, like textures or channels,
+/// but you still want to persist the layout.
+/// Each pane is converted to and from a serializable [`Self::Saved`] value,
+/// e.g. a key or a `serde_json::Value`.
+///
+/// See [`Tree::save_panes`] and [`Tree::load_panes`].
+///
+/// ```
+/// use egui_tiles::{PaneSerde, Tree};
+///
+/// struct Pane {
+///     path: String,
+///     receiver: std::sync::mpsc::Receiver<String>, // not serializable
+/// }
+///
+/// struct Hooks;
+///
+/// impl PaneSerde<Pane> for Hooks {
+///     type Saved = String;
+///
+///     fn save_pane(&mut self, pane: &Pane) -> String {
+///         pane.path.clone()
+///     }
+///
+///     fn load_pane(&mut self, path: String) -> Option<Pane> {
+///         let (_sender, receiver) = std::sync::mpsc::channel();
+///         Some(Pane { path, receiver })
+///     }
+/// }
+///
+/// # let (_sender, receiver) = std::sync::mpsc::channel();
+/// let tree = Tree::new_tabs("my_tree", vec![Pane { path: "a.txt".to_owned(), receiver }]);
+/// let saved: Tree<String> = tree.save_panes(&mut Hooks);
+/// // … serialize `saved` with any serde format …
+/// let restored: Tree<Pane> = Tree::load_panes(saved, &mut Hooks);
+/// ```
+pub trait PaneSerde<Pane> {
+    /// What each pane is saved as.
+    type Saved: serde::Serialize + serde::de::DeserializeOwned;
+
+    /// Convert a pane into something serializable.
+    fn save_pane(&mut self, pane: &Pane) -> Self::Saved;
+
+    /// Recreate a pane from what [`Self::save_pane`] returned.
+    ///
+    /// Return `None` if the pane can no longer be created,
+    /// and it will be removed from the layout.
+    fn load_pane(&mut self, saved: Self::Saved) -> Option<Pane>;
+}
+
+impl<Pane> Tree<Pane> {
+    /// Convert all panes with [`PaneSerde::save_pane`], giving a tree that can be serialized.
+    pub fn save_panes<H: PaneSerde<Pane> + ?Sized>(&self, hooks: &mut H) -> Tree<H::Saved> {
+        let (layout, mut panes) = self.split_panes(|_, pane| hooks.save_pane(pane));
+        layout.join_panes(|tile_id| panes.remove(&tile_id))
+    }
+
+    /// Recreate a tree saved with [`Self::save_panes`], using [`PaneSerde::load_pane`].
+    pub fn load_panes<H: PaneSerde<Pane> + ?Sized>(saved: Tree<H::Saved>, hooks: &mut H) -> Self {
+        saved.filter_map_panes(|_, saved| hooks.load_pane(saved))
+    }
+}
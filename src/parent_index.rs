@@ -0,0 +1,89 @@
+use crate::tile_id_hash::TileIdMap;
+use crate::{Tile, TileId};
+
+/// A `child -> parent` index over all the containers in [`crate::Tiles`].
+///
+/// It is kept up to date by [`crate::Tiles::insert`] and [`crate::Tiles::remove`].
+/// Anything that hands out mutable access to a container (e.g. [`crate::Tiles::get_mut`])
+/// can change its children behind our back, so that invalidates the index instead.
+/// It is rebuilt at the start of each frame by [`crate::Tree::ui`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ParentIndex {
+    parents: TileIdMap<TileId>,
+
+    /// If `false`, [`Self::parents`] may be out of date and must not be used.
+    ///
+    /// This is the default, so that a deserialized [`crate::Tiles`] starts out with no index.
+    valid: bool,
+}
+
+/// The result of looking up a parent in a [`ParentIndex`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Lookup {
+    /// The index is out of date, so we don't know.
+    Stale,
+
+    /// The tile has no parent.
+    Root,
+
+    /// The tile is a child of this container.
+    Parent(TileId),
+}
+
+impl ParentIndex {
+    pub fn get(&self, child_id: TileId) -> Lookup {
+        if !self.valid {
+            Lookup::Stale
+        } else if let Some(&parent_id) = self.parents.get(&child_id) {
+            Lookup::Parent(parent_id)
+        } else {
+            Lookup::Root
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    pub fn invalidate(&mut self) {
+        self.valid = false;
+        self.parents.clear();
+    }
+
+    pub fn rebuild<'a, Pane: 'a>(
+        &mut self,
+        tiles: impl Iterator<Item = (&'a TileId, &'a Tile<Pane>)>,
+    ) {
+        self.parents.clear();
+        self.valid = true; // `add` ignores an invalid index
+        for (&tile_id, tile) in tiles {
+            self.add(tile_id, tile);
+        }
+    }
+
+    /// The given tile was added.
+    pub fn add<Pane>(&mut self, tile_id: TileId, tile: &Tile<Pane>) {
+        if !self.valid {
+            return;
+        }
+        if let Tile::Container(container) = tile {
+            for &child_id in container.children() {
+                self.parents.insert(child_id, tile_id);
+            }
+        }
+    }
+
+    /// The given tile was removed (or is about to be replaced).
+    pub fn remove<Pane>(&mut self, tile_id: TileId, tile: &Tile<Pane>) {
+        if !self.valid {
+            return;
+        }
+        if let Tile::Container(container) = tile {
+            for child_id in container.children() {
+                if self.parents.get(child_id) == Some(&tile_id) {
+                    self.parents.remove(child_id);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use egui::{Color32, Rgba, Stroke, Visuals};
+
+use crate::DropIndicator;
+
+/// All the colors and sizes used to paint a [`crate::Tree`], in one place.
+///
+/// Return your own from [`crate::Behavior::tile_tree_style`] to theme the whole tree at once.
+/// The individual [`crate::Behavior`] hooks (e.g. [`crate::Behavior::tab_bar_color`])
+/// read from this by default, so you can still override just one of them.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TileTreeStyle {
+    /// The background color of the tab bar, and of the lines between tiles.
+    pub tab_bar_color: Color32,
+
+    /// The height of the bar holding tab titles.
+    pub tab_bar_height: f32,
+
+    /// Extra spacing to left and right of tab titles.
+    pub tab_title_spacing: f32,
+
+    /// How wide the fade is at an edge of the tab bar where tabs are scrolled out of view.
+    ///
+    /// Zero means no fade.
+    pub tab_bar_fade_width: f32,
+
+    /// The background color of the active tab.
+    pub active_tab_bg_color: Color32,
+
+    /// The background color of the other tabs.
+    pub inactive_tab_bg_color: Color32,
+
+    /// The background color of inactive tabs in a multi-selection, see [`crate::Tree::selected_tabs`].
+    pub selected_tab_bg_color: Color32,
+
+    /// The outline around the active tab title.
+    pub active_tab_outline: Stroke,
+
+    /// The outline around the other tab titles.
+    pub inactive_tab_outline: Stroke,
+
+    /// The title text color of the active tab.
+    pub active_tab_text_color: Color32,
+
+    /// The title text color of the other tabs.
+    pub inactive_tab_text_color: Color32,
+
+    /// The line separating the tab bar and the content of the active tab.
+    pub tab_bar_hline: Stroke,
+
+    /// Width of the gap between tiles in a horizontal or vertical layout,
+    /// and between rows/columns in a grid layout.
+    pub gap_width: f32,
+
+    /// The line between tiles when hovering it, showing that it can be dragged to resize.
+    pub resize_hovered_stroke: Stroke,
+
+    /// The line between tiles while dragging it to resize.
+    pub resize_dragging_stroke: Stroke,
+
+    /// The outline of the candidate area when drag-and-dropping a tile.
+    pub drag_preview_stroke: Stroke,
+
+    /// The fill of the candidate area when drag-and-dropping a tile.
+    pub drag_preview_fill: Color32,
+
+    /// The rounding of the candidate area when drag-and-dropping a tile.
+    pub drag_preview_rounding: f32,
+
+    /// How much of the candidate area to show when drag-and-dropping a tile.
+    pub drop_indicator: DropIndicator,
+
+    /// Covers the tile that is being dragged.
+    pub dragged_overlay_color: Color32,
+
+    /// Dims the rest of the tree behind a modal tile.
+    pub modal_backdrop_color: Color32,
+
+    /// Underlines the tab bar of the container holding the focused pane.
+    pub focused_group_underline: Stroke,
+
+    /// The border around the container holding the focused pane.
+    pub focused_group_border: Stroke,
+
+    /// Covers the panes that don't have focus, when [`crate::Behavior::dim_unfocused_panes`] is on.
+    pub unfocused_pane_overlay_color: Color32,
+}
+
+impl TileTreeStyle {
+    /// The default style for dark mode.
+    pub fn dark() -> Self {
+        Self::from_visuals(&Visuals::dark())
+    }
+
+    /// The default style for light mode.
+    pub fn light() -> Self {
+        Self::from_visuals(&Visuals::light())
+    }
+
+    /// Pick colors that go with the given egui visuals.
+    pub fn from_visuals(visuals: &Visuals) -> Self {
+        let tab_bar_color = if visuals.dark_mode {
+            visuals.extreme_bg_color
+        } else {
+            (Rgba::from(visuals.panel_fill) * Rgba::from_gray(0.8)).into()
+        };
+
+        Self {
+            tab_bar_color,
+            tab_bar_height: 24.0,
+            tab_title_spacing: 8.0,
+            tab_bar_fade_width: 16.0,
+            active_tab_bg_color: visuals.panel_fill, // same as the tab contents
+            inactive_tab_bg_color: Color32::TRANSPARENT, // fade into background
+            selected_tab_bg_color: visuals.selection.bg_fill.gamma_multiply(0.4),
+            active_tab_outline: Stroke::new(1.0, visuals.widgets.active.bg_fill),
+            inactive_tab_outline: Stroke::NONE,
+            active_tab_text_color: visuals.widgets.active.text_color(),
+            inactive_tab_text_color: visuals.widgets.noninteractive.text_color(),
+            tab_bar_hline: Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color),
+            gap_width: 1.0,
+            resize_hovered_stroke: visuals.widgets.hovered.fg_stroke,
+            resize_dragging_stroke: visuals.widgets.active.fg_stroke,
+            drag_preview_stroke: visuals.selection.stroke,
+            drag_preview_fill: visuals.selection.stroke.color.gamma_multiply(0.5),
+            drag_preview_rounding: 1.0,
+            drop_indicator: DropIndicator::FilledRect,
+            dragged_overlay_color: visuals.panel_fill.gamma_multiply(0.5),
+            modal_backdrop_color: Color32::from_black_alpha(128),
+            focused_group_underline: Stroke::new(2.0, visuals.selection.bg_fill),
+            focused_group_border: Stroke::new(1.0, visuals.selection.bg_fill.gamma_multiply(0.5)),
+            unfocused_pane_overlay_color: visuals.panel_fill.gamma_multiply(0.6),
+        }
+    }
+}
+
+impl Default for TileTreeStyle {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{Container, Tile, TileId, Tree};
+
+impl<Pane> Tree<Pane> {
+    /// Link tabs in different containers, so that activating one of them activates the others,
+    /// e.g. a "source" and a "preview" pane that should always be shown together.
+    ///
+    /// If any of the tiles is already linked, the groups are merged.
+    /// The links are persisted with the tree.
+    pub fn link_tabs(&mut self, tile_ids: impl IntoIterator<Item = TileId>) {
+        let mut group: Vec<TileId> = tile_ids.into_iter().collect();
+
+        // Merge with the groups they are already in:
+        self.tab_links.retain(|other| {
+            if other.iter().any(|tile_id| group.contains(tile_id)) {
+                group.extend(other);
+                false
+            } else {
+                true
+            }
+        });
+
+        group.sort();
+        group.dedup();
+        if 2 <= group.len() {
+            self.tab_links.push(group);
+        }
+    }
+
+    /// Remove the tile from its group of linked tabs, if any.
+    pub fn unlink_tab(&mut self, tile_id: TileId) {
+        for group in &mut self.tab_links {
+            group.retain(|&id| id != tile_id);
+        }
+        self.tab_links.retain(|group| 2 <= group.len());
+    }
+
+    /// The tabs linked to this one with [`Self::link_tabs`], not including itself.
+    pub fn linked_tabs(&self, tile_id: TileId) -> impl Iterator<Item = TileId> + '_ {
+        self.tab_links
+            .iter()
+            .filter(move |group| group.contains(&tile_id))
+            .flatten()
+            .copied()
+            .filter(move |&id| id != tile_id)
+    }
+
+    /// After the user activated a tab, activate its linked partners too.
+    ///
+    /// Partners in the same container are skipped, since only one of them can be active.
+    /// Returns `true` if anything changed.
+    pub(crate) fn activate_linked_tabs(&mut self, tab: TileId) -> bool {
+        let parent = self.tiles.parent_of(tab);
+        let partners: Vec<TileId> = self
+            .linked_tabs(tab)
+            .filter(|&partner| self.tiles.parent_of(partner) != parent)
+            .collect();
+
+        let mut changed = false;
+        for partner in partners {
+            let Some(partner_parent) = self.tiles.parent_of(partner) else {
+                continue;
+            };
+            if let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get_mut(partner_parent)
+            {
+                if tabs.active != Some(partner) {
+                    tabs.set_active(partner);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Forget links to tiles that are gone.
+    pub(crate) fn gc_tab_links(&mut self) {
+        if self.tab_links.is_empty() {
+            return;
+        }
+        for group in &mut self.tab_links {
+            group.retain(|&tile_id| self.tiles.get(tile_id).is_some());
+        }
+        self.tab_links.retain(|group| 2 <= group.len());
+    }
+}
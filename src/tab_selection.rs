@@ -0,0 +1,267 @@
+use egui::Modifiers;
+
+use crate::{Behavior, Container, EditAction, Tile, TileId, Tree, TreeEvent};
+
+/// Tabs the user picked with Ctrl/Shift-click, see [`Tree::selected_tabs`].
+#[derive(Clone, Debug)]
+pub(crate) struct TabSelection {
+    /// The [`crate::Tabs`] container the tabs are in.
+    tabs_id: TileId,
+
+    /// In the order of the tabs.
+    selected: Vec<TileId>,
+
+    /// Where a Shift-click selects from.
+    anchor: TileId,
+}
+
+impl<Pane> Tree<Pane> {
+    /// The tabs the user selected for a bulk operation, and the [`crate::Tabs`] they are in.
+    ///
+    /// The user builds the selection by Ctrl-clicking (Cmd-clicking on Mac) tabs to toggle them,
+    /// or Shift-clicking to select a range. A plain click clears it.
+    /// Only tabs in one container can be selected at a time.
+    ///
+    /// Dragging one of the selected tabs moves all of them,
+    /// and closing one of them closes all of them, see [`Self::close_selected_tabs`].
+    /// The selection is not persisted.
+    pub fn selected_tabs(&self) -> Option<(TileId, &[TileId])> {
+        let selection = self.tab_selection.as_ref()?;
+        Some((selection.tabs_id, &selection.selected))
+    }
+
+    /// Select some of the children of the given [`crate::Tabs`].
+    ///
+    /// Anything that isn't a child of it is ignored.
+    pub fn set_selected_tabs(&mut self, tabs_id: TileId, selected: &[TileId]) {
+        let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get(tabs_id) else {
+            self.tab_selection = None;
+            return;
+        };
+        let selected: Vec<TileId> = tabs
+            .children
+            .iter()
+            .copied()
+            .filter(|child| selected.contains(child))
+            .collect();
+        let anchor = selected.first().copied();
+        self.tab_selection = anchor.map(|anchor| TabSelection {
+            tabs_id,
+            selected,
+            anchor,
+        });
+    }
+
+    /// Deselect all tabs.
+    pub fn clear_tab_selection(&mut self) {
+        self.tab_selection = None;
+    }
+
+    /// Is this tab part of [`Self::selected_tabs`]?
+    pub fn is_tab_selected(&self, tile_id: TileId) -> bool {
+        self.tab_selection
+            .as_ref()
+            .is_some_and(|selection| selection.selected.contains(&tile_id))
+    }
+
+    /// Close all [`Self::selected_tabs`] that may be closed,
+    /// asking [`Behavior::on_tab_close`] for each.
+    ///
+    /// Tabs are skipped like in [`Self::close_all_tabs`].
+    /// Returns how many were closed.
+    pub fn close_selected_tabs(&mut self, behavior: &mut dyn Behavior<Pane>) -> usize {
+        let Some(selection) = self.tab_selection.take() else {
+            return 0;
+        };
+        let mut num_closed = 0;
+        for tile_id in selection.selected {
+            if self.tiles.get(tile_id).is_none() {
+                continue; // Already closed, e.g. with its own close button
+            }
+            if self.may_close_tab(behavior, tile_id) && self.close_tab(behavior, tile_id) {
+                num_closed += 1;
+            }
+        }
+        num_closed
+    }
+
+    /// Close every tab in the given [`crate::Tabs`] that may be closed,
+    /// asking [`Behavior::on_tab_close`] for each.
+    ///
+    /// A tab is skipped if [`Behavior::is_tab_closable`] or [`Behavior::edit_permissions`]
+    /// says it can't be closed, or if [`Behavior::on_tab_close`] returns `false`.
+    /// Returns the skipped tabs, in order, which are still open.
+    ///
+    /// The [`crate::Tabs`] itself is left in place, even if it ends up empty.
+    /// See also [`Behavior::close_all_tabs_button`].
+    pub fn close_all_tabs(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tabs_id: TileId,
+    ) -> Vec<TileId> {
+        let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get(tabs_id) else {
+            log::warn!("close_all_tabs: {tabs_id:?} is not a Tabs container");
+            return vec![];
+        };
+        self.close_tabs(behavior, tabs.children.clone())
+    }
+
+    /// Close the given tabs like [`Self::close_all_tabs`], returning the ones that were skipped.
+    ///
+    /// The [`crate::Tabs`] shows its own close-all button with this,
+    /// since it isn't in [`Self::tiles`] while it is being shown.
+    pub(crate) fn close_tabs(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tabs: Vec<TileId>,
+    ) -> Vec<TileId> {
+        tabs.into_iter()
+            .filter(|&tile_id| {
+                !(self.may_close_tab(behavior, tile_id) && self.close_tab(behavior, tile_id))
+            })
+            .collect()
+    }
+
+    /// Do [`Behavior::is_tab_closable`] and [`Behavior::edit_permissions`] allow closing this tab?
+    pub(crate) fn may_close_tab(&self, behavior: &dyn Behavior<Pane>, tile_id: TileId) -> bool {
+        self.edit_permissions(behavior, tile_id).close
+            && behavior.is_tab_closable(&self.tiles, tile_id)
+    }
+
+    /// Close the given tab, unless [`Behavior::on_tab_close`] says no.
+    fn close_tab(&mut self, behavior: &mut dyn Behavior<Pane>, tile_id: TileId) -> bool {
+        if !behavior.on_tab_close(&mut self.tiles, tile_id) {
+            return false;
+        }
+        self.tiles.on_edit(behavior, EditAction::TabClosed);
+        self.tiles.push_event(TreeEvent::TileClosed(tile_id));
+        self.close_recursively(tile_id);
+        true
+    }
+
+    /// Move all [`Self::selected_tabs`] into the given container, keeping their order.
+    ///
+    /// See [`Self::move_tile_to_container`].
+    pub fn move_selected_tabs(&mut self, destination_container: TileId, insertion_index: usize) {
+        let Some(selection) = &self.tab_selection else {
+            return;
+        };
+        let selected = selection.selected.clone();
+        for (i, tile_id) in selected.into_iter().enumerate() {
+            self.move_tile_to_container(tile_id, destination_container, insertion_index + i, false);
+        }
+        self.selection_moved_to(destination_container);
+    }
+
+    /// After the user dropped a selected tab, move the rest of the selection right after it.
+    pub(crate) fn move_selected_tabs_after(&mut self, dropped: TileId) {
+        if !self.is_tab_selected(dropped) {
+            return;
+        }
+        let Some(selection) = &self.tab_selection else {
+            return;
+        };
+        let others: Vec<TileId> = selection
+            .selected
+            .iter()
+            .copied()
+            .filter(|&tile_id| tile_id != dropped)
+            .collect();
+        let Some(parent_id) = self.tiles.parent_of(dropped) else {
+            return;
+        };
+
+        let mut previous = dropped;
+        for tile_id in others {
+            let Some(Tile::Container(parent)) = self.tiles.get(parent_id) else {
+                break;
+            };
+            let index = parent
+                .children()
+                .position(|&child| child == previous)
+                .map_or(usize::MAX, |index| index + 1);
+            self.move_tile_to_container(tile_id, parent_id, index, false);
+            previous = tile_id;
+        }
+
+        if let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get_mut(parent_id) {
+            tabs.set_active(dropped);
+        }
+        self.selection_moved_to(parent_id);
+    }
+
+    /// Keep the selection if the tabs ended up in another [`crate::Tabs`].
+    fn selection_moved_to(&mut self, container_id: TileId) {
+        if let Some(selection) = &mut self.tab_selection {
+            if matches!(
+                self.tiles.get(container_id),
+                Some(Tile::Container(Container::Tabs(_)))
+            ) {
+                selection.tabs_id = container_id;
+            } else {
+                self.tab_selection = None;
+            }
+        }
+    }
+
+    /// Update the selection after a click on a tab in the given [`crate::Tabs`].
+    pub(crate) fn on_tab_clicked(
+        &mut self,
+        tabs_id: TileId,
+        children: &[TileId],
+        active: Option<TileId>,
+        clicked: TileId,
+        modifiers: Modifiers,
+    ) {
+        let previous = self
+            .tab_selection
+            .take()
+            .filter(|selection| selection.tabs_id == tabs_id);
+
+        if modifiers.command {
+            // Toggle, starting from the active tab:
+            let (mut selected, anchor) = previous.map_or_else(
+                || (active.into_iter().collect(), active.unwrap_or(clicked)),
+                |selection| (selection.selected, selection.anchor),
+            );
+            if let Some(index) = selected.iter().position(|&id| id == clicked) {
+                selected.remove(index);
+            } else {
+                selected.push(clicked);
+            }
+            selected.sort_by_key(|id| children.iter().position(|child| child == id));
+            self.tab_selection = (!selected.is_empty()).then_some(TabSelection {
+                tabs_id,
+                selected,
+                anchor,
+            });
+        } else if modifiers.shift {
+            let anchor = previous.map_or(active.unwrap_or(clicked), |selection| selection.anchor);
+            let position = |needle: TileId| children.iter().position(|&child| child == needle);
+            if let (Some(from), Some(to)) = (position(anchor), position(clicked)) {
+                self.tab_selection = Some(TabSelection {
+                    tabs_id,
+                    selected: children[from.min(to)..=from.max(to)].to_vec(),
+                    anchor,
+                });
+            }
+        }
+    }
+
+    /// Forget selected tabs that were closed or moved elsewhere.
+    pub(crate) fn validate_tab_selection(&mut self) {
+        let Some(selection) = &mut self.tab_selection else {
+            return;
+        };
+        let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get(selection.tabs_id) else {
+            self.tab_selection = None;
+            return;
+        };
+        selection
+            .selected
+            .retain(|tile_id| tabs.children.contains(tile_id));
+        if selection.selected.is_empty() {
+            self.tab_selection = None;
+        }
+    }
+}
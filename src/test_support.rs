@@ -0,0 +1,235 @@
+//! Helpers for testing how a [`Tree`] reacts to the user, without a window.
+//!
+//! ```
+//! use egui_tiles::test_support::Harness;
+//! # use egui_tiles::{Behavior, TileId, Tiles, Tree, UiResponse};
+//! # struct Pane;
+//! # struct MyBehavior;
+//! # impl Behavior<Pane> for MyBehavior {
+//! #     fn pane_ui(&mut self, _: &mut egui::Ui, _: TileId, _: &mut Pane) -> UiResponse {
+//! #         UiResponse::None
+//! #     }
+//! #     fn tab_title_for_pane(&mut self, _: &Pane) -> egui::WidgetText {
+//! #         "Pane".into()
+//! #     }
+//! # }
+//!
+//! let mut tiles = Tiles::default();
+//! let first = tiles.insert_pane(Pane);
+//! let second = tiles.insert_pane(Pane);
+//! let root = tiles.insert_tab_tile(vec![first, second]);
+//! let mut tree = Tree::new("my_tree", root, tiles);
+//!
+//! let mut harness = Harness::new(egui::vec2(800.0, 600.0));
+//! harness.run(&mut tree, &mut MyBehavior);
+//! assert!(harness.click_tab(&mut tree, &mut MyBehavior, second));
+//! assert!(tree.active_tiles().contains(&second));
+//! ```
+
+use egui::{Pos2, Rect, Vec2};
+
+use crate::{Behavior, TileId, Tree, TreeResponse};
+
+/// How many frames a simulated drag moves the pointer over.
+const DRAG_STEPS: usize = 8;
+
+/// Shows a [`Tree`] in a headless [`egui::Context`], and feeds it simulated pointer input.
+///
+/// Each call runs one or more whole frames, so you can assert on the tree afterwards.
+pub struct Harness {
+    ctx: egui::Context,
+    screen_rect: Rect,
+    time: f64,
+    pointer_pos: Pos2,
+    events: Vec<egui::Event>,
+    shapes: Vec<egui::epaint::ClippedShape>,
+}
+
+impl Harness {
+    /// The tree fills a screen of the given size.
+    pub fn new(screen_size: Vec2) -> Self {
+        Self {
+            ctx: egui::Context::default(),
+            screen_rect: Rect::from_min_size(Pos2::ZERO, screen_size),
+            time: 0.0,
+            pointer_pos: Pos2::ZERO,
+            events: Vec::new(),
+            shapes: Vec::new(),
+        }
+    }
+
+    /// The headless context, e.g. for checking what is being dragged.
+    pub fn ctx(&self) -> &egui::Context {
+        &self.ctx
+    }
+
+    /// Everything painted in the last frame, e.g. for checking what is highlighted or dimmed.
+    pub fn shapes(&self) -> &[egui::epaint::ClippedShape] {
+        &self.shapes
+    }
+
+    /// Show the tree for one frame, with any input queued since the last frame.
+    pub fn run<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+    ) -> TreeResponse {
+        let raw_input = egui::RawInput {
+            screen_rect: Some(self.screen_rect),
+            time: Some(self.time),
+            events: std::mem::take(&mut self.events),
+            ..Default::default()
+        };
+        let mut response = TreeResponse::default();
+        let output = self.ctx.run(raw_input, |ctx| {
+            egui::CentralPanel::default()
+                .frame(egui::Frame::none())
+                .show(ctx, |ui| response = tree.ui(behavior, ui));
+        });
+        self.shapes = output.shapes;
+        self.time += 1.0 / 60.0;
+        response
+    }
+
+    /// Move the pointer to the given position, taking one frame.
+    pub fn hover<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        pos: Pos2,
+    ) {
+        self.pointer_pos = pos;
+        self.events.push(egui::Event::PointerMoved(pos));
+        self.run(tree, behavior);
+    }
+
+    /// Click the primary mouse button at the given position.
+    pub fn click<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        pos: Pos2,
+    ) {
+        self.hover(tree, behavior, pos);
+        self.press(true);
+        self.run(tree, behavior);
+        self.press(false);
+        self.run(tree, behavior);
+    }
+
+    /// Turn the mouse wheel by `delta` points at the given position, with `modifiers` held.
+    pub fn scroll<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        pos: Pos2,
+        delta: Vec2,
+        modifiers: egui::Modifiers,
+    ) {
+        self.hover(tree, behavior, pos);
+        self.events.push(egui::Event::MouseWheel {
+            unit: egui::MouseWheelUnit::Point,
+            delta,
+            modifiers,
+        });
+        self.run(tree, behavior);
+    }
+
+    /// Press the primary mouse button at `from`, move the pointer to `to` over a few frames,
+    /// and release it there.
+    pub fn drag<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        from: Pos2,
+        to: Pos2,
+    ) {
+        self.hover(tree, behavior, from);
+        self.press(true);
+        self.run(tree, behavior);
+        for step in 1..=DRAG_STEPS {
+            let t = step as f32 / DRAG_STEPS as f32;
+            self.hover(tree, behavior, from.lerp(to, t));
+        }
+        self.press(false);
+        self.run(tree, behavior);
+        // Let the tree react to the drop:
+        self.run(tree, behavior);
+    }
+
+    /// Click the tab button of the given tile.
+    ///
+    /// Returns `false` if the tab button wasn't shown last frame.
+    pub fn click_tab<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+    ) -> bool {
+        let Some(tab_rect) = self.tab_rect(tree, tile_id) else {
+            return false;
+        };
+        self.click(tree, behavior, tab_rect.center());
+        true
+    }
+
+    /// Drag the tab button of the given tile to the given position, e.g. into another tile.
+    ///
+    /// Returns `false` if the tab button wasn't shown last frame.
+    pub fn drag_tab<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+        to: Pos2,
+    ) -> bool {
+        let Some(tab_rect) = self.tab_rect(tree, tile_id) else {
+            return false;
+        };
+        self.drag(tree, behavior, tab_rect.center(), to);
+        true
+    }
+
+    /// Drag the line between two neighboring tiles by `delta`, to resize them.
+    ///
+    /// Returns `false` if the two tiles weren't both shown last frame.
+    pub fn drag_divider<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        [first, second]: [TileId; 2],
+        delta: Vec2,
+    ) -> bool {
+        let (Some(first), Some(second)) = (tree.tiles.rect(first), tree.tiles.rect(second)) else {
+            return false;
+        };
+        let divider = if first.right() <= second.left() {
+            egui::pos2(
+                egui::lerp(first.right()..=second.left(), 0.5),
+                first.center().y,
+            )
+        } else {
+            egui::pos2(
+                first.center().x,
+                egui::lerp(first.bottom()..=second.top(), 0.5),
+            )
+        };
+        self.drag(tree, behavior, divider, divider + delta);
+        true
+    }
+
+    fn tab_rect<Pane>(&self, tree: &Tree<Pane>, tile_id: TileId) -> Option<Rect> {
+        self.ctx
+            .read_response(tile_id.egui_id(tree.id()))
+            .map(|response| response.rect)
+    }
+
+    fn press(&mut self, pressed: bool) {
+        self.events.push(egui::Event::PointerButton {
+            pos: self.pointer_pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        });
+    }
+}
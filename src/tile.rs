@@ -3,7 +3,7 @@ use crate::{Container, ContainerKind};
 /// An identifier for a [`Tile`] in the tree, be it a [`Container`] or a pane.
 ///
 /// This id is unique within the tree, but not across trees.
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TileId(pub u64);
 
@@ -0,0 +1,88 @@
+//! Hash maps and sets keyed by [`TileId`].
+//!
+//! These are hit a lot during layout and ui, so with the `fast_tile_id_hash` feature
+//! we use a single multiplication as the hash function instead of [`ahash`].
+//! That is fine, since a [`TileId`] is just a number we handed out ourselves,
+//! so there is nothing for an attacker to exploit.
+//!
+//! Iteration order depends on the hasher, so serialized maps and sets
+//! go through [`serialize_sorted_map`] and [`serialize_sorted_set`],
+//! which write them in order of [`TileId`]. That way saving an unchanged layout
+//! produces the same file every time.
+
+use crate::TileId;
+
+#[cfg(feature = "fast_tile_id_hash")]
+pub(crate) type TileIdBuildHasher = std::hash::BuildHasherDefault<TileIdHasher>;
+
+#[cfg(not(feature = "fast_tile_id_hash"))]
+pub(crate) type TileIdBuildHasher = ahash::RandomState;
+
+pub(crate) type TileIdMap<V> = std::collections::HashMap<TileId, V, TileIdBuildHasher>;
+
+pub(crate) type TileIdSet = std::collections::HashSet<TileId, TileIdBuildHasher>;
+
+/// Serialize a [`TileIdMap`] in order of [`TileId`].
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_sorted_map<V: serde::Serialize, S: serde::Serializer>(
+    map: &TileIdMap<V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut entries: Vec<(&TileId, &V)> = map.iter().collect();
+    entries.sort_by_key(|&(&tile_id, _)| tile_id);
+    serializer.collect_map(entries)
+}
+
+/// Serialize a [`TileIdSet`] in order of [`TileId`].
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_sorted_set<S: serde::Serializer>(
+    set: &TileIdSet,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tile_ids: Vec<TileId> = set.iter().copied().collect();
+    tile_ids.sort();
+    serializer.collect_seq(tile_ids)
+}
+
+/// An FxHash-style hasher.
+///
+/// [`TileId`]s are mostly small and sequential, so we can't use them as the hash directly:
+/// the hash map looks at the top bits, which would then all be zero.
+/// Multiplying with a large odd constant spreads the bits out.
+#[cfg(feature = "fast_tile_id_hash")]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct TileIdHasher {
+    hash: u64,
+}
+
+#[cfg(feature = "fast_tile_id_hash")]
+impl TileIdHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+#[cfg(feature = "fast_tile_id_hash")]
+impl std::hash::Hasher for TileIdHasher {
+    #[inline]
+    fn write_u64(&mut self, n: u64) {
+        self.add(n);
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        // Only used if someone hashes something other than a `TileId` with this.
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
@@ -0,0 +1,424 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::tile_id_hash::TileIdMap;
+use crate::{Tile, TileId};
+
+/// Slot indices below this are stored in the dense arena, the rest in a side map.
+///
+/// This bounds the memory used by the arena if a user picks a huge id.
+const MAX_DENSE_ID: u64 = 1 << 14;
+
+/// The storage of all tiles in [`crate::Tiles`], keyed by [`TileId`].
+///
+/// This is a generational arena: the lower 32 bits of a [`TileId`] are the index of its slot,
+/// and the upper 32 bits are the generation of that slot.
+/// [`crate::Tiles::next_free_id`] hands out ids sequentially (generation zero),
+/// so almost all slot indices are small, which makes lookups and iteration cheap.
+/// When a tile is removed its slot is handed out again by [`Self::recycle_id`],
+/// with a newer generation, so the arena doesn't keep growing in a long-running app,
+/// and the old id won't find the new tile.
+///
+/// Ids that don't fit (e.g. picked by the user) are stored in a side map.
+///
+/// Iteration is in order of slot, followed by the side map in order of id,
+/// so anything derived from it (layout, debug output) is deterministic.
+#[derive(Clone)]
+pub(crate) struct TileStore<Pane> {
+    /// Indexed by the slot of the [`TileId`].
+    dense: Vec<Option<(TileId, Tile<Pane>)>>,
+
+    /// The generation of the next id handed out for each slot in [`Self::dense`].
+    generations: Vec<u32>,
+
+    /// Empty slots in [`Self::dense`], for [`Self::recycle_id`].
+    free: BTreeSet<usize>,
+
+    /// Ids with a slot of [`MAX_DENSE_ID`] and above,
+    /// or whose slot is taken by a tile of another generation.
+    ///
+    /// Ordered, so that iteration doesn't depend on the hasher.
+    sparse: BTreeMap<TileId, Tile<Pane>>,
+
+    /// Number of tiles in [`Self::dense`].
+    dense_len: usize,
+
+    /// How many ids [`Self::recycle_id`] has handed out.
+    num_recycled: u64,
+}
+
+impl<Pane> Default for TileStore<Pane> {
+    fn default() -> Self {
+        Self {
+            dense: Vec::new(),
+            generations: Vec::new(),
+            free: Default::default(),
+            sparse: Default::default(),
+            dense_len: 0,
+            num_recycled: 0,
+        }
+    }
+}
+
+fn dense_index(tile_id: TileId) -> Option<usize> {
+    let index = tile_id.0 & u64::from(u32::MAX);
+    (index < MAX_DENSE_ID).then_some(index as usize)
+}
+
+fn generation_of(tile_id: TileId) -> u32 {
+    (tile_id.0 >> 32) as u32
+}
+
+impl<Pane> TileStore<Pane> {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.dense_len + self.sparse.len()
+    }
+
+    /// How many ids [`Self::recycle_id`] has handed out.
+    #[inline]
+    pub fn num_recycled(&self) -> u64 {
+        self.num_recycled
+    }
+
+    pub fn contains_key(&self, tile_id: &TileId) -> bool {
+        self.get(tile_id).is_some()
+    }
+
+    /// The slot of this id in [`Self::dense`], if that is where it is stored.
+    fn dense_slot(&self, tile_id: TileId) -> Option<usize> {
+        let index = dense_index(tile_id)?;
+        match self.dense.get(index) {
+            Some(Some((id, _))) if *id == tile_id => Some(index),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, tile_id: &TileId) -> Option<&Tile<Pane>> {
+        if let Some(index) = self.dense_slot(*tile_id) {
+            self.dense[index].as_ref().map(|(_, tile)| tile)
+        } else {
+            self.sparse.get(tile_id)
+        }
+    }
+
+    pub fn get_mut(&mut self, tile_id: &TileId) -> Option<&mut Tile<Pane>> {
+        if let Some(index) = self.dense_slot(*tile_id) {
+            self.dense[index].as_mut().map(|(_, tile)| tile)
+        } else {
+            self.sparse.get_mut(tile_id)
+        }
+    }
+
+    /// Returns the previous tile with the same id, if any.
+    pub fn insert(&mut self, tile_id: TileId, tile: Tile<Pane>) -> Option<Tile<Pane>> {
+        if let Some(index) = self.dense_slot(tile_id) {
+            return self.dense[index]
+                .replace((tile_id, tile))
+                .map(|(_, tile)| tile);
+        }
+        if !self.sparse.contains_key(&tile_id) {
+            if let Some(index) = dense_index(tile_id) {
+                if self.dense.len() <= index {
+                    self.dense.resize_with(index + 1, || None);
+                    self.generations.resize(index + 1, 0);
+                }
+                if self.dense[index].is_none() {
+                    self.dense[index] = Some((tile_id, tile));
+                    self.dense_len += 1;
+                    self.free.remove(&index);
+                    let generation = &mut self.generations[index];
+                    *generation = (*generation).max(generation_of(tile_id));
+                    return None;
+                }
+                // The slot is taken by another generation.
+            }
+        }
+        self.sparse.insert(tile_id, tile)
+    }
+
+    pub fn remove(&mut self, tile_id: &TileId) -> Option<Tile<Pane>> {
+        if let Some(index) = self.dense_slot(*tile_id) {
+            let (_, tile) = self.dense[index].take()?;
+            self.dense_len -= 1;
+            self.free_slot(index, *tile_id);
+            Some(tile)
+        } else {
+            self.sparse.remove(tile_id)
+        }
+    }
+
+    /// Remove the tiles matching the predicate, and return them.
+    pub fn remove_if(
+        &mut self,
+        mut remove: impl FnMut(&TileId, &Tile<Pane>) -> bool,
+    ) -> Vec<(TileId, Tile<Pane>)> {
+        let mut removed = Vec::new();
+        for index in 0..self.dense.len() {
+            if let Some((tile_id, tile)) = &self.dense[index] {
+                if remove(tile_id, tile) {
+                    let tile_id = *tile_id;
+                    if let Some(entry) = self.dense[index].take() {
+                        removed.push(entry);
+                    }
+                    self.dense_len -= 1;
+                    self.free_slot(index, tile_id);
+                }
+            }
+        }
+        let sparse_ids: Vec<TileId> = self
+            .sparse
+            .iter()
+            .filter(|(tile_id, tile)| remove(tile_id, tile))
+            .map(|(tile_id, _)| *tile_id)
+            .collect();
+        for tile_id in sparse_ids {
+            if let Some(tile) = self.sparse.remove(&tile_id) {
+                removed.push((tile_id, tile));
+            }
+        }
+        removed
+    }
+
+    /// The tile with this id was removed from this slot, so the slot can be reused.
+    fn free_slot(&mut self, index: usize, tile_id: TileId) {
+        // A slot that has run out of generations is retired:
+        if let Some(next) = generation_of(tile_id).checked_add(1) {
+            let generation = &mut self.generations[index];
+            *generation = (*generation).max(next);
+            self.free.insert(index);
+        }
+    }
+
+    /// An unused id in the slot of a removed tile, if any.
+    ///
+    /// Its generation is newer than that of any tile that was in the slot before,
+    /// so looking up one of their ids won't find whatever ends up in there.
+    ///
+    /// The slot stays free until a tile is inserted into it, so an id that is never used
+    /// doesn't leak the slot. The next call gets a newer generation of the same slot.
+    pub fn recycle_id(&mut self) -> Option<TileId> {
+        while let Some(&index) = self.free.last() {
+            let generation = self.generations[index];
+            if let Some(next) = generation.checked_add(1) {
+                self.generations[index] = next;
+            } else {
+                // Out of generations, so retire the slot:
+                self.free.remove(&index);
+            }
+            let tile_id = TileId::from_u64((u64::from(generation) << 32) | index as u64);
+            if !self.sparse.contains_key(&tile_id) {
+                self.num_recycled += 1;
+                return Some(tile_id);
+            }
+        }
+        None
+    }
+
+    /// All tiles, in order of slot.
+    pub fn iter(&self) -> impl Iterator<Item = (&TileId, &Tile<Pane>)> + '_ {
+        self.dense
+            .iter()
+            .filter_map(|slot| slot.as_ref().map(|(tile_id, tile)| (tile_id, tile)))
+            .chain(self.sparse.iter())
+    }
+
+    /// All tiles, in order of slot.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&TileId, &mut Tile<Pane>)> + '_ {
+        self.dense
+            .iter_mut()
+            .filter_map(|slot| slot.as_mut().map(|(tile_id, tile)| (&*tile_id, tile)))
+            .chain(self.sparse.iter_mut())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &TileId> + '_ {
+        self.iter().map(|(tile_id, _)| tile_id)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Tile<Pane>> + '_ {
+        self.iter().map(|(_, tile)| tile)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Tile<Pane>> + '_ {
+        self.iter_mut().map(|(_, tile)| tile)
+    }
+}
+
+impl<Pane> IntoIterator for TileStore<Pane> {
+    type Item = (TileId, Tile<Pane>);
+    type IntoIter = std::iter::Chain<
+        std::iter::Flatten<std::vec::IntoIter<Option<(TileId, Tile<Pane>)>>>,
+        std::collections::btree_map::IntoIter<TileId, Tile<Pane>>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.dense.into_iter().flatten().chain(self.sparse)
+    }
+}
+
+impl<Pane> FromIterator<(TileId, Tile<Pane>)> for TileStore<Pane> {
+    fn from_iter<I: IntoIterator<Item = (TileId, Tile<Pane>)>>(iter: I) -> Self {
+        let mut store = Self::default();
+        for (tile_id, tile) in iter {
+            store.insert(tile_id, tile);
+        }
+        store
+    }
+}
+
+impl<Pane: PartialEq> PartialEq for TileStore<Pane> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(tile_id, tile)| other.get(tile_id) == Some(tile))
+    }
+}
+
+impl<Pane: std::fmt::Debug> std::fmt::Debug for TileStore<Pane> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// Serialized as a map in order of id, just like a sorted `HashMap`:
+
+#[cfg(feature = "serde")]
+impl<Pane: serde::Serialize> serde::Serialize for TileStore<Pane> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<(&TileId, &Tile<Pane>)> = self.iter().collect();
+        entries.sort_by_key(|&(&tile_id, _)| tile_id);
+        serializer.collect_map(entries)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Pane: serde::Deserialize<'de>> serde::Deserialize<'de> for TileStore<Pane> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // In order of id, so ids that share a slot always end up in the same place:
+        let map = BTreeMap::<TileId, Tile<Pane>>::deserialize(deserializer)?;
+        Ok(map.into_iter().collect())
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Where each tile was laid out this frame, keyed by [`TileId`].
+///
+/// Uses the same slots as [`TileStore`].
+/// Instead of clearing the dense part each frame we bump a generation counter,
+/// so the allocation is kept and nothing is rehashed.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RectStore {
+    /// Indexed by the slot of the [`TileId`].
+    /// Only entries stamped with the current generation are valid.
+    dense: Vec<(u32, TileId, egui::Rect)>,
+
+    /// Ids with a slot of [`MAX_DENSE_ID`] and above,
+    /// or whose slot was taken by another id this frame.
+    sparse: TileIdMap<egui::Rect>,
+
+    generation: u32,
+}
+
+impl RectStore {
+    /// Forget all rects.
+    pub fn clear(&mut self) {
+        self.sparse.clear();
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            // Wrapped around, so old stamps could become valid again:
+            self.dense.clear();
+        }
+    }
+
+    pub fn get(&self, tile_id: TileId) -> Option<egui::Rect> {
+        if let Some(&(generation, id, rect)) = dense_index(tile_id).and_then(|i| self.dense.get(i))
+        {
+            if generation == self.generation && id == tile_id {
+                return Some(rect);
+            }
+        }
+        self.sparse.get(&tile_id).copied()
+    }
+
+    pub fn insert(&mut self, tile_id: TileId, rect: egui::Rect) {
+        if let Some(index) = dense_index(tile_id) {
+            if self.dense.len() <= index {
+                let stale = self.generation.wrapping_sub(1);
+                self.dense
+                    .resize(index + 1, (stale, TileId(0), egui::Rect::NOTHING));
+            }
+            let (generation, id, _) = self.dense[index];
+            if generation != self.generation || id == tile_id {
+                self.dense[index] = (self.generation, tile_id, rect);
+                return;
+            }
+        }
+        self.sparse.insert(tile_id, rect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuse_slots() {
+        let mut store = TileStore::<()>::default();
+        let old = TileId::from_u64(1);
+        store.insert(old, Tile::Pane(()));
+        store.remove(&old);
+
+        let new = store.recycle_id().expect("slot was freed");
+        assert_ne!(old, new);
+        assert_eq!(dense_index(old), dense_index(new));
+        store.insert(new, Tile::Pane(()));
+        assert!(store.get(&old).is_none(), "Stale id found the new tile");
+        assert!(store.get(&new).is_some());
+        assert_eq!(store.recycle_id(), None);
+
+        // The old id gets the slot if it is put back before it is reused:
+        store.remove(&new);
+        store.insert(old, Tile::Pane(()));
+        assert_eq!(store.recycle_id(), None);
+
+        // A tile with the id of another generation goes in the side map:
+        store.insert(new, Tile::Pane(()));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.dense.len(), 2);
+        assert!(store.remove(&new).is_some());
+        assert!(store.get(&old).is_some());
+    }
+
+    #[test]
+    fn test_unused_recycled_id() {
+        let mut store = TileStore::<()>::default();
+        let old = TileId::from_u64(1);
+        store.insert(old, Tile::Pane(()));
+        store.remove(&old);
+
+        // An id that is never inserted doesn't use up the slot:
+        let dropped = store.recycle_id().expect("slot was freed");
+        let first = store.recycle_id().expect("slot is still free");
+        let second = store.recycle_id().expect("slot is still free");
+        assert_ne!(dropped, first);
+        assert_ne!(first, second);
+        assert_eq!(dense_index(first), dense_index(old));
+        assert_eq!(dense_index(second), dense_index(old));
+
+        // Using both ids puts one in the slot and the other in the side map:
+        store.insert(second, Tile::Pane(()));
+        store.insert(first, Tile::Pane(()));
+        assert_eq!(store.len(), 2);
+        assert!(store.get(&first).is_some());
+        assert!(store.get(&second).is_some());
+        assert!(store.get(&old).is_none());
+        assert!(store.get(&dropped).is_none());
+        assert_eq!(store.recycle_id(), None);
+    }
+}
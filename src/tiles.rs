@@ -1,8 +1,17 @@
 use egui::{Pos2, Rect};
 
+use crate::behavior::{EditAction, EditSummary, VisibilityReason};
+use crate::layout_cache::{AutoColumnCount, LayoutArea, LayoutCache, LayoutValues};
+use crate::layout_transition::LayoutTransition;
+use crate::metadata::TileMetadata;
+use crate::parent_index::{Lookup, ParentIndex};
+use crate::tile_id_hash::{TileIdMap, TileIdSet};
+use crate::tile_store::{RectStore, TileStore};
+use crate::TreeEvent;
+
 use super::{
     Behavior, Container, ContainerInsertion, ContainerKind, GcAction, Grid, InsertionPoint, Linear,
-    LinearDir, SimplificationOptions, SimplifyAction, Tabs, Tile, TileId,
+    LinearDir, SimplificationOptions, SimplifyAction, SimplifyReport, Tabs, Tile, TileId,
 };
 
 /// Contains all tile state, but no root.
@@ -20,17 +29,84 @@ use super::{
 /// ```
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Tiles<Pane> {
+    /// See [`Self::next_free_id`].
     next_tile_id: u64,
 
-    tiles: ahash::HashMap<TileId, Tile<Pane>>,
+    tiles: TileStore<Pane>,
 
     /// Tiles are visible by default, so we only store the invisible ones.
-    invisible: ahash::HashSet<TileId>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::tile_id_hash::serialize_sorted_set")
+    )]
+    invisible: TileIdSet,
+
+    /// See [`Self::set_metadata`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "TileIdMap::is_empty",
+            serialize_with = "crate::tile_id_hash::serialize_sorted_map"
+        )
+    )]
+    pub(super) metadata: TileIdMap<TileMetadata>,
 
     /// Filled in by the layout step at the start of each frame.
     #[cfg_attr(feature = "serde", serde(default, skip))]
-    pub(super) rects: ahash::HashMap<TileId, Rect>,
+    pub(super) rects: RectStore,
+
+    /// Edits made since the last call to [`crate::Tree::take_edit_summary`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) edits: EditSummary,
+
+    /// The panes that got a [`Behavior::pane_ui`] call this frame.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) shown_panes: TileIdSet,
+
+    /// Why each pane that isn't shown was last hidden, see [`VisibilityReason`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) hidden_reasons: TileIdMap<VisibilityReason>,
+
+    /// Lets the layout step skip containers that haven't changed since last frame.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    layout_cache: LayoutCache,
+
+    /// Makes [`Self::parent_of`] fast.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    parents: ParentIndex,
+
+    /// Set while tiles are animating to their new place,
+    /// e.g. after a drop, or after tiles were added or removed.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) transition: Option<LayoutTransition>,
+
+    /// What [`Self::structure_signature`] was at the end of the last frame.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    seen_structure: Option<(usize, u64)>,
+
+    /// Panes to duplicate at the end of the frame, see [`crate::Tree::duplicate`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) duplicate_requests: Vec<TileId>,
+
+    /// Recorded events, if enabled. See [`crate::Tree::enable_events`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) events: Option<Vec<TreeEvent>>,
+
+    /// The container the user resized this frame, see [`crate::TreeResponse::resized`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) resized: Option<TileId>,
+
+    /// The tiles that were in the tree last frame, see [`Behavior::on_tile_inserted`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) known_tiles: TileIdSet,
+
+    /// Panes that were closed, or taken out by [`crate::Tree::undo`], while
+    /// [`crate::Tree::enable_history`] is on, so undo or redo can bring them back.
+    /// `None` while history is off.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Option::default"))]
+    pub(super) graveyard: Option<TileIdMap<Pane>>,
 }
 
 impl<Pane: PartialEq> PartialEq for Tiles<Pane> {
@@ -39,9 +115,22 @@ impl<Pane: PartialEq> PartialEq for Tiles<Pane> {
             next_tile_id: _, // ignored
             tiles,
             invisible,
-            rects: _, // ignore transient state
+            metadata,
+            rects: _,              // ignore transient state
+            edits: _,              // ignore transient state
+            shown_panes: _,        // ignore transient state
+            hidden_reasons: _,     // ignore transient state
+            layout_cache: _,       // ignore transient state
+            parents: _,            // ignore transient state
+            transition: _,         // ignore transient state
+            seen_structure: _,     // ignore transient state
+            duplicate_requests: _, // ignore transient state
+            events: _,             // ignore transient state
+            resized: _,            // ignore transient state
+            known_tiles: _,        // ignore transient state
+            graveyard: _,          // ignore transient state
         } = self;
-        tiles == &other.tiles && invisible == &other.invisible
+        tiles == &other.tiles && invisible == &other.invisible && metadata == &other.metadata
     }
 }
 
@@ -51,7 +140,144 @@ impl<Pane> Default for Tiles<Pane> {
             next_tile_id: 1,
             tiles: Default::default(),
             invisible: Default::default(),
+            metadata: Default::default(),
             rects: Default::default(),
+            edits: Default::default(),
+            shown_panes: Default::default(),
+            hidden_reasons: Default::default(),
+            layout_cache: Default::default(),
+            parents: Default::default(),
+            transition: None,
+            seen_structure: None,
+            duplicate_requests: Vec::new(),
+            events: None,
+            resized: None,
+            known_tiles: Default::default(),
+            graveyard: None,
+        }
+    }
+}
+
+/// The tiles of [`Tiles`] without the panes or any transient state, see [`Tiles::structure`].
+#[derive(Clone)]
+pub(crate) struct TileStructure {
+    /// Panes are left empty.
+    tiles: Vec<(TileId, Tile<()>)>,
+    invisible: TileIdSet,
+}
+
+impl TileStructure {
+    /// The ids of all panes in the structure.
+    pub(crate) fn pane_ids(&self) -> impl Iterator<Item = TileId> + '_ {
+        self.tiles
+            .iter()
+            .filter(|(_, tile)| tile.is_pane())
+            .map(|(tile_id, _)| *tile_id)
+    }
+}
+
+/// How to lay out the tiles, besides the style.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LayoutParams {
+    /// Child rects are snapped to physical pixels.
+    pub pixels_per_point: f32,
+
+    /// See [`crate::Tree::set_right_to_left`].
+    pub right_to_left: bool,
+}
+
+/// The parts of [`Tiles`] that a container needs while it is being laid out.
+///
+/// These are borrowed separately from the tile storage, so containers can be laid out in place.
+pub(crate) struct LayoutContext<'a> {
+    invisible: &'a TileIdSet,
+    edits: &'a mut EditSummary,
+
+    /// Only the part of the tiles inside this rect will be shown, if set.
+    viewport: Option<Rect>,
+
+    /// The nesting depth of the current container.
+    depth: usize,
+
+    params: LayoutParams,
+
+    /// Where the children of the current container go.
+    children: Vec<(TileId, Rect)>,
+
+    /// Children of the current container that got a rect, but whose descendants were not laid out.
+    placeholders: Vec<(TileId, Rect)>,
+
+    /// How many columns the current container got, if it is a grid with automatic columns.
+    auto_column_count: Option<AutoColumnCount>,
+}
+
+impl LayoutContext<'_> {
+    /// See [`Tiles::is_visible`].
+    pub fn is_visible(&self, tile_id: TileId) -> bool {
+        !self.invisible.contains(&tile_id)
+    }
+
+    /// The nesting depth of the current container, where the root is at depth 0.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Round the given coordinate to the nearest physical pixel.
+    pub fn round_to_pixels(&self, point: f32) -> f32 {
+        let pixels_per_point = self.params.pixels_per_point;
+        (point * pixels_per_point).round() / pixels_per_point
+    }
+
+    /// Should horizontal layouts start at the right? See [`crate::Tree::set_right_to_left`].
+    pub fn right_to_left(&self) -> bool {
+        self.params.right_to_left
+    }
+
+    /// Ask [`Behavior::grid_auto_column_count`], remembering the answer for the layout cache.
+    pub fn grid_auto_column_count<Pane>(
+        &mut self,
+        behavior: &dyn Behavior<Pane>,
+        num_visible_children: usize,
+        rect: Rect,
+        gap: f32,
+    ) -> usize {
+        let num_cols = behavior.grid_auto_column_count(num_visible_children, rect, gap);
+        self.auto_column_count = Some(AutoColumnCount {
+            num_visible_children,
+            rect,
+            gap,
+            num_cols,
+        });
+        num_cols
+    }
+
+    /// See [`Tiles::on_edit`].
+    pub fn on_edit<Pane, B: Behavior<Pane> + ?Sized>(
+        &mut self,
+        behavior: &mut B,
+        edit_action: EditAction,
+    ) {
+        self.edits.add(edit_action.clone());
+        behavior.on_edit(edit_action);
+    }
+
+    /// Lay out the given child of the current container (and its descendants) in the given rect.
+    pub fn layout_child(&mut self, tile_id: TileId, rect: Rect) {
+        self.children.push((tile_id, rect));
+    }
+
+    /// Like [`Self::layout_child`], but if the child is outside the viewport
+    /// we only remember its rect, and skip laying out its descendants.
+    ///
+    /// Those will not be shown anyway, since off-screen tiles are culled during ui.
+    pub fn layout_child_in_viewport(&mut self, tile_id: TileId, rect: Rect) {
+        if self
+            .viewport
+            .is_some_and(|viewport| !viewport.intersects(rect))
+        {
+            self.placeholders.push((tile_id, rect));
+        } else {
+            self.children.push((tile_id, rect));
         }
     }
 }
@@ -90,10 +316,36 @@ impl<Pane> Tiles<Pane> {
         }
     }
 
+    /// Note that changing the children of a container through this
+    /// makes [`Self::parent_of`] slow until the next call to [`crate::Tree::ui`].
+    /// Prefer [`Self::get_pane_mut`] if you only want to change a pane.
     pub fn get_mut(&mut self, tile_id: TileId) -> Option<&mut Tile<Pane>> {
+        self.parents.invalidate();
         self.tiles.get_mut(&tile_id)
     }
 
+    /// Put `container` in place of the container with the given id, returning the old one.
+    ///
+    /// Unlike [`Self::get_mut`] this keeps the parent index, so only use it to take a container
+    /// out for a moment and put it back, like [`crate::Tree::ui`] does while showing it.
+    pub(crate) fn replace_container(
+        &mut self,
+        tile_id: TileId,
+        container: Container,
+    ) -> Option<Container> {
+        match self.tiles.get_mut(&tile_id)? {
+            Tile::Container(slot) => Some(std::mem::replace(slot, container)),
+            Tile::Pane(_) => None,
+        }
+    }
+
+    pub fn get_pane_mut(&mut self, tile_id: TileId) -> Option<&mut Pane> {
+        match self.tiles.get_mut(&tile_id)? {
+            Tile::Pane(pane) => Some(pane),
+            Tile::Container(_) => None,
+        }
+    }
+
     /// Get the screen-space rectangle of where a tile is shown.
     ///
     /// This is updated by [`crate::Tree::ui`], so you need to call that first.
@@ -101,7 +353,7 @@ impl<Pane> Tiles<Pane> {
     /// If the tile isn't visible, or is in an inactive tab, this return `None`.
     pub fn rect(&self, tile_id: TileId) -> Option<Rect> {
         if self.is_visible(tile_id) {
-            self.rects.get(&tile_id).copied()
+            self.rects.get(tile_id)
         } else {
             None
         }
@@ -113,31 +365,242 @@ impl<Pane> Tiles<Pane> {
         rect.unwrap_or(egui::Rect::from_min_max(Pos2::ZERO, Pos2::ZERO))
     }
 
-    /// All tiles, in arbitrary order
+    /// All tiles, in a deterministic order
     pub fn iter(&self) -> impl Iterator<Item = (&TileId, &Tile<Pane>)> + '_ {
         self.tiles.iter()
     }
 
-    /// All tiles, in arbitrary order
+    /// All tiles, in a deterministic order
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (&TileId, &mut Tile<Pane>)> + '_ {
+        self.parents.invalidate();
         self.tiles.iter_mut()
     }
 
-    /// All [`TileId`]s, in arbitrary order
+    /// All [`TileId`]s, in a deterministic order
     pub fn tile_ids(&self) -> impl Iterator<Item = TileId> + '_ {
         self.tiles.keys().copied()
     }
 
-    /// All [`Tile`]s in arbitrary order
+    /// All [`Tile`]s, in a deterministic order
     pub fn tiles(&self) -> impl Iterator<Item = &Tile<Pane>> + '_ {
         self.tiles.values()
     }
 
-    /// All [`Tile`]s in arbitrary order
+    /// All [`Tile`]s, in a deterministic order
     pub fn tiles_mut(&mut self) -> impl Iterator<Item = &mut Tile<Pane>> + '_ {
+        self.parents.invalidate();
         self.tiles.values_mut()
     }
 
+    /// Convert each pane into another type, keeping the tile structure and ids.
+    pub fn map_panes<Out>(self, mut f: impl FnMut(TileId, Pane) -> Out) -> Tiles<Out> {
+        self.filter_map_panes(|tile_id, pane| Some(f(tile_id, pane)))
+    }
+
+    /// Like [`Self::map_panes`], but panes for which `f` returns `None` are removed.
+    ///
+    /// This may leave dangling references to the removed panes,
+    /// which are cleaned up by [`crate::Tree::gc`].
+    pub fn filter_map_panes<Out>(
+        self,
+        mut f: impl FnMut(TileId, Pane) -> Option<Out>,
+    ) -> Tiles<Out> {
+        let Self {
+            next_tile_id,
+            tiles,
+            invisible,
+            metadata,
+            rects,
+            edits,
+            shown_panes,
+            hidden_reasons,
+            layout_cache,
+            parents,
+            transition,
+            seen_structure,
+            duplicate_requests,
+            events,
+            resized,
+            known_tiles,
+            graveyard: _,
+        } = self;
+
+        let tiles = tiles
+            .into_iter()
+            .filter_map(|(tile_id, tile)| {
+                let tile = match tile {
+                    Tile::Pane(pane) => Tile::Pane(f(tile_id, pane)?),
+                    Tile::Container(container) => Tile::Container(container),
+                };
+                Some((tile_id, tile))
+            })
+            .collect();
+
+        Tiles {
+            next_tile_id,
+            tiles,
+            invisible,
+            metadata,
+            rects,
+            edits,
+            shown_panes,
+            hidden_reasons,
+            layout_cache,
+            parents,
+            transition,
+            seen_structure,
+            duplicate_requests,
+            events,
+            resized,
+            known_tiles,
+            graveyard: None,
+        }
+    }
+
+    /// Split off the panes, leaving only the tile structure.
+    ///
+    /// Each pane is converted with `f` and stored in the returned side table,
+    /// keyed by its [`TileId`].
+    ///
+    /// Use [`Tiles::join_panes`] to put them back together.
+    pub fn split_panes<Out>(
+        &self,
+        mut f: impl FnMut(TileId, &Pane) -> Out,
+    ) -> (Tiles<()>, std::collections::BTreeMap<TileId, Out>) {
+        let mut panes = std::collections::BTreeMap::new();
+
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|(&tile_id, tile)| {
+                let tile = match tile {
+                    Tile::Pane(pane) => {
+                        panes.insert(tile_id, f(tile_id, pane));
+                        Tile::Pane(())
+                    }
+                    Tile::Container(container) => Tile::Container(container.clone()),
+                };
+                (tile_id, tile)
+            })
+            .collect();
+
+        let structure = Tiles {
+            next_tile_id: self.next_tile_id,
+            tiles,
+            invisible: self.invisible.clone(),
+            metadata: self.metadata.clone(),
+            rects: self.rects.clone(),
+            edits: self.edits.clone(),
+            shown_panes: self.shown_panes.clone(),
+            hidden_reasons: self.hidden_reasons.clone(),
+            layout_cache: self.layout_cache.clone(),
+            parents: self.parents.clone(),
+            transition: self.transition.clone(),
+            seen_structure: self.seen_structure,
+            duplicate_requests: self.duplicate_requests.clone(),
+            events: self.events.clone(),
+            resized: self.resized,
+            known_tiles: self.known_tiles.clone(),
+            graveyard: None,
+        };
+        (structure, panes)
+    }
+
+    /// Tell the behavior about an edit, and record it for [`crate::Tree::take_edit_summary`].
+    pub(crate) fn on_edit<B: Behavior<Pane> + ?Sized>(
+        &mut self,
+        behavior: &mut B,
+        edit_action: EditAction,
+    ) {
+        self.edits.add(edit_action.clone());
+        self.layout_cache.clear();
+        behavior.on_edit(edit_action);
+    }
+
+    /// A copy of the tile structure, without the panes.
+    pub(crate) fn structure(&self) -> TileStructure {
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|(&tile_id, tile)| {
+                let tile = match tile {
+                    Tile::Pane(_) => Tile::Pane(()),
+                    Tile::Container(container) => Tile::Container(container.clone()),
+                };
+                (tile_id, tile)
+            })
+            .collect();
+        TileStructure {
+            tiles,
+            invisible: self.invisible.clone(),
+        }
+    }
+
+    /// Is this the given structure, ignoring the contents of the panes?
+    pub(crate) fn structure_eq(&self, structure: &TileStructure) -> bool {
+        self.invisible == structure.invisible
+            && self.tiles.len() == structure.tiles.len()
+            && structure
+                .tiles
+                .iter()
+                .all(|(tile_id, tile)| match (tile, self.tiles.get(tile_id)) {
+                    (Tile::Pane(()), Some(Tile::Pane(_))) => true,
+                    (Tile::Container(a), Some(Tile::Container(b))) => a.layout_eq(b),
+                    _ => false,
+                })
+    }
+
+    /// Go back to the given structure, keeping the panes that are still in it.
+    ///
+    /// Panes that are not in the structure go to the graveyard, and panes that are only
+    /// in the graveyard come back from it (see [`Self::bury`]).
+    /// Pane slots whose pane is gone for good are left dangling, for [`crate::Tree::gc`] to clean up.
+    /// Everything else (recorded events and edits, which tiles were announced, …) is kept.
+    pub(crate) fn restore_structure(&mut self, structure: &TileStructure) {
+        let is_pane: TileIdMap<bool> = structure
+            .tiles
+            .iter()
+            .map(|(tile_id, tile)| (*tile_id, tile.is_pane()))
+            .collect();
+        let removed = self
+            .tiles
+            .remove_if(|tile_id, tile| is_pane.get(tile_id) != Some(&tile.is_pane()));
+        self.bury(removed);
+        for (tile_id, tile) in &structure.tiles {
+            match tile {
+                Tile::Container(container) => {
+                    self.tiles
+                        .insert(*tile_id, Tile::Container(container.clone()));
+                }
+                Tile::Pane(()) => {
+                    if !self.tiles.contains_key(tile_id) {
+                        if let Some(pane) = self
+                            .graveyard
+                            .as_mut()
+                            .and_then(|graveyard| graveyard.remove(tile_id))
+                        {
+                            self.tiles.insert(*tile_id, Tile::Pane(pane));
+                        }
+                    }
+                }
+            }
+        }
+        self.invisible = structure.invisible.clone();
+        self.parents.rebuild(self.tiles.iter());
+        self.layout_cache.clear();
+    }
+
+    /// Keep the panes among these removed tiles for [`crate::Tree::undo`], if history is on.
+    pub(crate) fn bury(&mut self, removed: impl IntoIterator<Item = (TileId, Tile<Pane>)>) {
+        if let Some(graveyard) = &mut self.graveyard {
+            for (tile_id, tile) in removed {
+                if let Tile::Pane(pane) = tile {
+                    graveyard.insert(tile_id, pane);
+                }
+            }
+        }
+    }
+
     /// Tiles are visible by default.
     ///
     /// Invisible tiles still retain their place in the tile hierarchy.
@@ -149,13 +612,34 @@ impl<Pane> Tiles<Pane> {
     ///
     /// Invisible tiles still retain their place in the tile hierarchy.
     pub fn set_visible(&mut self, tile_id: TileId, visible: bool) {
-        if visible {
-            self.invisible.remove(&tile_id);
+        let changed = if visible {
+            self.invisible.remove(&tile_id)
         } else {
-            self.invisible.insert(tile_id);
+            self.invisible.insert(tile_id)
+        };
+        if changed {
+            self.layout_cache.clear();
         }
     }
 
+    /// Lay out every container from scratch next frame.
+    ///
+    /// Layouts are cached between frames and only redone when something in the tree changes,
+    /// or when the style or the sizes and gaps returned by the [`Behavior`] change
+    /// (e.g. [`Behavior::gap_width`], [`Behavior::tab_bar_height`], [`Behavior::min_size`],
+    /// or [`Behavior::grid_auto_column_count`]), so you rarely need to call this.
+    pub fn invalidate_layout(&mut self) {
+        self.layout_cache.clear();
+    }
+
+    /// Ask for a copy of the given pane to be shown next to it at the end of the frame.
+    ///
+    /// This is how the tab context menu duplicates panes, see [`crate::Tree::duplicate`].
+    pub fn request_duplicate(&mut self, tile_id: TileId) {
+        self.duplicate_requests.push(tile_id);
+    }
+
+    /// Hide the tile if it is visible, and vice versa.
     pub fn toggle_visibility(&mut self, tile_id: TileId) {
         self.set_visible(tile_id, !self.is_visible(tile_id));
     }
@@ -175,7 +659,14 @@ impl<Pane> Tiles<Pane> {
     }
 
     pub fn insert(&mut self, id: TileId, tile: Tile<Pane>) {
-        self.tiles.insert(id, tile);
+        self.parents.add(id, &tile);
+        if let Some(previous) = self.tiles.insert(id, tile) {
+            self.parents.remove(id, &previous);
+            if let Some(tile) = self.tiles.get(&id) {
+                // The previous tile may have shared some children with the new one:
+                self.parents.add(id, tile);
+            }
+        }
     }
 
     /// Remove the tile with the given id from the tiles container.
@@ -184,10 +675,26 @@ impl<Pane> Tiles<Pane> {
     /// leave dangling references. If you want to permanently remove the tile
     /// consider calling [`crate::Tree::remove_recursively`].
     pub fn remove(&mut self, id: TileId) -> Option<Tile<Pane>> {
-        self.tiles.remove(&id)
+        let tile = self.tiles.remove(&id)?;
+        self.parents.remove(id, &tile);
+        Some(tile)
     }
 
+    /// The id the next inserted tile will get.
+    ///
+    /// Until a tile is removed, ids come from a counter stored in [`Self`], starting at 1,
+    /// skipping any ids you have inserted yourself.
+    /// After that the storage of removed tiles is reused first, with an id that has
+    /// a higher generation in its upper 32 bits, so the old id never refers to the new tile.
+    /// Such ids are large (`1 << 32` and up), so don't expect ids to be small or consecutive.
+    /// No random numbers are involved, so this works on any target (no `getrandom`),
+    /// and the same sequence of inserts and removals gives the same ids,
+    /// which keeps tests reproducible.
     pub fn next_free_id(&mut self) -> TileId {
+        if let Some(id) = self.tiles.recycle_id() {
+            return id;
+        }
+
         let mut id = TileId::from_u64(self.next_tile_id);
 
         // Make sure it doesn't collide with an existing id
@@ -205,7 +712,7 @@ impl<Pane> Tiles<Pane> {
     #[must_use]
     pub fn insert_new(&mut self, tile: Tile<Pane>) -> TileId {
         let id = self.next_free_id();
-        self.tiles.insert(id, tile);
+        self.insert(id, tile);
         id
     }
 
@@ -245,9 +752,17 @@ impl<Pane> Tiles<Pane> {
         self.insert_new(Tile::Container(Container::new_grid(children)))
     }
 
+    /// The container the given tile is in, if any.
+    ///
+    /// This is a fast lookup as long as the index built by [`crate::Tree::ui`] is up to date,
+    /// and falls back to scanning all containers otherwise.
     pub fn parent_of(&self, child_id: TileId) -> Option<TileId> {
-        #[allow(clippy::iter_over_hash_type)] // Each tile can only have one parent
-        for (tile_id, tile) in &self.tiles {
+        match self.parents.get(child_id) {
+            Lookup::Parent(parent_id) => return Some(parent_id),
+            Lookup::Root => return None,
+            Lookup::Stale => {}
+        }
+        for (tile_id, tile) in self.tiles.iter() {
             if let Tile::Container(container) = tile {
                 if container.has_child(child_id) {
                     return Some(*tile_id);
@@ -261,7 +776,52 @@ impl<Pane> Tiles<Pane> {
         self.parent_of(tile_id).is_none()
     }
 
+    /// The ancestors of the given tile, starting with its parent and ending with the root.
+    pub fn ancestors(&self, tile_id: TileId) -> impl Iterator<Item = TileId> + '_ {
+        std::iter::successors(self.parent_of(tile_id), |&id| self.parent_of(id))
+            // Protect against cycles:
+            .take(self.tiles.len())
+    }
+
+    /// Is `tile_id` inside of `ancestor_id`, directly or indirectly?
+    pub fn is_descendant_of(&self, tile_id: TileId, ancestor_id: TileId) -> bool {
+        self.ancestors(tile_id).any(|id| id == ancestor_id)
+    }
+
+    /// Animate all tiles from where they are shown now to wherever they are laid out next.
+    pub(super) fn start_transition(&mut self, now: f64, duration: f32) {
+        let from = self
+            .tile_ids()
+            .filter_map(|tile_id| Some((tile_id, self.rects.get(tile_id)?)))
+            .collect();
+        self.transition = Some(LayoutTransition::new(from, now, duration));
+    }
+
+    /// Changes whenever a tile is added or removed.
+    fn structure_signature(&self) -> (usize, u64) {
+        let num_new_ids = self.next_tile_id + self.tiles.num_recycled();
+        (self.tiles.len(), num_new_ids)
+    }
+
+    /// Were tiles added or removed since the last call to [`Self::mark_structure_seen`]?
+    pub(super) fn structure_changed_since_seen(&self) -> bool {
+        self.seen_structure
+            .is_some_and(|seen| seen != self.structure_signature())
+    }
+
+    pub(super) fn mark_structure_seen(&mut self) {
+        self.seen_structure = Some(self.structure_signature());
+    }
+
+    /// Rebuild the index used by [`Self::parent_of`], if it is out of date.
+    pub(super) fn update_parent_index(&mut self) {
+        if !self.parents.is_valid() {
+            self.parents.rebuild(self.tiles.iter());
+        }
+    }
+
     pub(super) fn insert_at(&mut self, insertion_point: InsertionPoint, inserted_id: TileId) {
+        self.parents.invalidate();
         let InsertionPoint {
             parent_id,
             insertion,
@@ -271,6 +831,9 @@ impl<Pane> Tiles<Pane> {
             log::debug!("Failed to insert: could not find parent {parent_id:?}");
             return;
         };
+        if parent_tile.kind() != Some(insertion.kind()) {
+            self.push_event(TreeEvent::ContainerCreated(parent_id));
+        }
 
         match insertion {
             ContainerInsertion::Tabs(index) => {
@@ -342,11 +905,16 @@ impl<Pane> Tiles<Pane> {
     ///
     /// Will also call [`Behavior::retain_pane`] to check if a users wants to remove a pane.
     ///
-    /// Finally free up any tiles that are no longer reachable from the root.
-    pub(super) fn gc_root(&mut self, behavior: &mut dyn Behavior<Pane>, root_id: Option<TileId>) {
+    /// Finally free up any tiles that are no longer reachable from any of the given roots.
+    pub(super) fn gc_root(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        root_ids: impl IntoIterator<Item = TileId>,
+    ) {
+        self.parents.invalidate();
         let mut visited = Default::default();
 
-        if let Some(root_id) = root_id {
+        for root_id in root_ids {
             // We ignore the returned root action, because we will never remove the root.
             let _root_action = self.gc_tile_id(behavior, &mut visited, root_id);
         }
@@ -365,57 +933,243 @@ impl<Pane> Tiles<Pane> {
         }
 
         self.invisible.retain(|tile_id| visited.contains(tile_id));
-        self.tiles.retain(|tile_id, _| visited.contains(tile_id));
+        self.metadata.retain(|tile_id, _| visited.contains(tile_id));
+        let removed = self
+            .tiles
+            .remove_if(|tile_id, _| !visited.contains(tile_id));
+        self.bury(removed);
+        self.layout_cache
+            .retain(|tile_id| visited.contains(&tile_id));
     }
 
     /// Detect cycles, duplications, and other invalid state, and remove them.
     fn gc_tile_id(
         &mut self,
         behavior: &mut dyn Behavior<Pane>,
-        visited: &mut ahash::HashSet<TileId>,
+        visited: &mut TileIdSet,
         tile_id: TileId,
     ) -> GcAction {
-        let Some(mut tile) = self.tiles.remove(&tile_id) else {
+        if !self.tiles.contains_key(&tile_id) {
             return GcAction::Remove;
-        };
+        }
         if !visited.insert(tile_id) {
             log::warn!("Cycle or duplication detected");
             return GcAction::Remove;
         }
 
-        match &mut tile {
-            Tile::Pane(pane) => {
+        let children: Vec<TileId> = match self.tiles.get_mut(&tile_id) {
+            Some(Tile::Pane(pane)) => {
                 if !behavior.retain_pane(pane) {
+                    self.tiles.remove(&tile_id);
                     return GcAction::Remove;
                 }
+                return GcAction::Keep;
             }
-            Tile::Container(container) => {
-                container
-                    .retain(|child| self.gc_tile_id(behavior, visited, child) == GcAction::Keep);
-            }
+            Some(Tile::Container(container)) => container.children().copied().collect(),
+            None => return GcAction::Remove,
+        };
+
+        let keep: Vec<bool> = children
+            .into_iter()
+            .map(|child| self.gc_tile_id(behavior, visited, child) == GcAction::Keep)
+            .collect();
+
+        if let Some(Tile::Container(container)) = self.tiles.get_mut(&tile_id) {
+            let mut keep = keep.into_iter();
+            container.retain(|_| keep.next().unwrap_or(true));
         }
-        self.tiles.insert(tile_id, tile);
         GcAction::Keep
     }
 
+    /// A hash of everything that affects the layout of the given tile and its visible descendants.
+    ///
+    /// That includes the gap each container puts between its children, which depends on its depth.
+    fn layout_structure_hash(
+        &self,
+        root: TileId,
+        root_depth: usize,
+        gap_at_depth: impl Fn(usize) -> f32,
+    ) -> u64 {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = ahash::AHasher::default();
+        let mut stack = vec![(root, root_depth)];
+        while let Some((tile_id, depth)) = stack.pop() {
+            tile_id.hash(&mut hasher);
+            match self.tiles.get(&tile_id) {
+                Some(Tile::Pane(_)) => 0_u8.hash(&mut hasher),
+                Some(Tile::Container(container)) => {
+                    1_u8.hash(&mut hasher);
+                    container.hash_layout(&mut hasher);
+                    // The behavior may want different gaps at different depths:
+                    gap_at_depth(depth).to_bits().hash(&mut hasher);
+                    for &child in container.children() {
+                        let visible = self.is_visible(child);
+                        visible.hash(&mut hasher);
+                        if visible {
+                            stack.push((child, depth + 1));
+                        }
+                    }
+                }
+                None => 2_u8.hash(&mut hasher),
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Lay out the given tile and all its visible descendants, starting with the given rect.
+    ///
+    /// Each tile is laid out in place: the tile storage is borrowed separately from
+    /// the rest of [`Tiles`], and each container reports where its children go
+    /// via [`LayoutContext::layout_child`].
+    ///
+    /// If nothing changed since the last time this tile was laid out,
+    /// the whole pass is skipped and the rects from last time are reused.
+    ///
+    /// If a `viewport` is given, big containers (grids) only lay out
+    /// the descendants of the children that are inside of it.
+    ///
+    /// The edges between children are snapped to physical pixels,
+    /// so that thin dividers don't blur at fractional scale factors.
     pub(super) fn layout_tile(
         &mut self,
         style: &egui::Style,
+        params: LayoutParams,
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
+        viewport: Option<Rect>,
         tile_id: TileId,
     ) {
-        let Some(mut tile) = self.tiles.remove(&tile_id) else {
-            log::debug!("Failed to find tile {tile_id:?} during layout");
+        profile_scope!("Tiles::layout_tile");
+        self.layout_cache
+            .check_style(style, params, LayoutValues::new(behavior, style));
+
+        let depth = self.ancestors(tile_id).count();
+        let structure = self.layout_structure_hash(tile_id, depth, |depth| {
+            behavior.gap_width_at_depth(style, depth)
+        });
+        if let Some(cached) = self
+            .layout_cache
+            .get_tree(tile_id, rect, viewport, structure, behavior)
+        {
+            for &(tile_id, rect) in cached {
+                let rect = match &self.transition {
+                    Some(transition) => transition.rect(tile_id, rect),
+                    None => rect,
+                };
+                self.rects.insert(tile_id, rect);
+            }
             return;
-        };
-        self.rects.insert(tile_id, rect);
+        }
 
-        if let Tile::Container(container) = &mut tile {
-            container.layout(self, style, behavior, rect);
+        let (laid_out, auto_column_counts) =
+            self.layout_tile_uncached(style, params, behavior, rect, viewport, tile_id, depth);
+        if let Some(transition) = &self.transition {
+            for &(tile_id, rect) in &laid_out {
+                self.rects.insert(tile_id, transition.rect(tile_id, rect));
+            }
         }
 
-        self.tiles.insert(tile_id, tile);
+        // Laying out can change the tree (e.g. which tab is active),
+        // so we hash it again to get what next frame will see if nothing else changes:
+        let structure = self.layout_structure_hash(tile_id, depth, |depth| {
+            behavior.gap_width_at_depth(style, depth)
+        });
+        self.layout_cache.insert_tree(
+            tile_id,
+            rect,
+            viewport,
+            structure,
+            laid_out,
+            auto_column_counts,
+        );
+    }
+
+    /// Returns every tile that was laid out, and where,
+    /// and the column counts of the grids with automatic columns.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_tile_uncached(
+        &mut self,
+        style: &egui::Style,
+        params: LayoutParams,
+        behavior: &mut dyn Behavior<Pane>,
+        rect: Rect,
+        viewport: Option<Rect>,
+        tile_id: TileId,
+        depth: usize,
+    ) -> (Vec<(TileId, Rect)>, Vec<AutoColumnCount>) {
+        let Self {
+            tiles,
+            invisible,
+            rects,
+            edits,
+            layout_cache,
+            ..
+        } = self;
+
+        let mut ctx = LayoutContext {
+            invisible,
+            edits,
+            viewport,
+            depth,
+            params,
+            children: Vec::new(),
+            placeholders: Vec::new(),
+            auto_column_count: None,
+        };
+
+        let mut laid_out = Vec::new();
+        let mut auto_column_counts = Vec::new();
+        let mut stack = vec![(tile_id, rect, depth)];
+        while let Some((tile_id, rect, depth)) = stack.pop() {
+            let Some(tile) = tiles.get_mut(&tile_id) else {
+                log::debug!("Failed to find tile {tile_id:?} during layout");
+                continue;
+            };
+            rects.insert(tile_id, rect);
+            laid_out.push((tile_id, rect));
+
+            if let Tile::Container(container) = tile {
+                let area = LayoutArea {
+                    rect,
+                    viewport,
+                    depth,
+                    gap: behavior.gap_width_at_depth(style, depth),
+                };
+                if let Some(cached) = layout_cache.get(tile_id, area, container, behavior) {
+                    // Nothing changed since last time, so the children go in the same places:
+                    auto_column_counts.extend(cached.auto_column_count);
+                    for &(child_id, child_rect) in &cached.placeholders {
+                        rects.insert(child_id, child_rect);
+                        laid_out.push((child_id, child_rect));
+                    }
+                    let children = cached.children.iter().rev();
+                    stack.extend(children.map(|&(child_id, rect)| (child_id, rect, depth + 1)));
+                    continue;
+                }
+
+                ctx.depth = depth;
+                container.layout(&mut ctx, style, behavior, rect);
+                let auto_column_count = ctx.auto_column_count.take();
+                auto_column_counts.extend(auto_column_count);
+                layout_cache.insert(
+                    tile_id,
+                    area,
+                    container,
+                    auto_column_count,
+                    &ctx.children,
+                    &ctx.placeholders,
+                );
+                for (child_id, child_rect) in ctx.placeholders.drain(..) {
+                    rects.insert(child_id, child_rect);
+                    laid_out.push((child_id, child_rect));
+                }
+                // Reversed, so that the children are laid out in order:
+                let children = ctx.children.drain(..).rev();
+                stack.extend(children.map(|(child_id, rect)| (child_id, rect, depth + 1)));
+            }
+        }
+        (laid_out, auto_column_counts)
     }
 
     /// Simplify the tree, perhaps culling empty containers,
@@ -425,24 +1179,61 @@ impl<Pane> Tiles<Pane> {
     /// This is often undesired, so this function can be used to clean up the tree.
     ///
     /// What simplifications are allowed is controlled by the [`SimplificationOptions`].
+    /// What was changed is added to `report`.
     pub(super) fn simplify(
         &mut self,
         options: &SimplificationOptions,
+        report: &mut SimplifyReport,
         it: TileId,
         parent_kind: Option<ContainerKind>,
     ) -> SimplifyAction {
+        self.parents.invalidate();
+        self.simplify_tile(options, report, &mut Default::default(), it, parent_kind)
+    }
+
+    fn simplify_tile(
+        &mut self,
+        options: &SimplificationOptions,
+        report: &mut SimplifyReport,
+        in_progress: &mut TileIdSet,
+        it: TileId,
+        parent_kind: Option<ContainerKind>,
+    ) -> SimplifyAction {
+        let (kind, children): (ContainerKind, Vec<TileId>) = match self.tiles.get(&it) {
+            Some(Tile::Pane(_)) => return SimplifyAction::Keep,
+            Some(Tile::Container(container)) if in_progress.insert(it) => {
+                (container.kind(), container.children().copied().collect())
+            }
+            Some(Tile::Container(_)) => {
+                log::debug!("Cycle detected at tile {it:?} during simplify");
+                return SimplifyAction::Remove;
+            }
+            None => {
+                log::debug!("Failed to find tile {it:?} during simplify");
+                return SimplifyAction::Remove;
+            }
+        };
+
+        let child_actions: Vec<SimplifyAction> = children
+            .into_iter()
+            .map(|child| self.simplify_tile(options, report, in_progress, child, Some(kind)))
+            .collect();
+        in_progress.remove(&it);
+
+        // Take the container out while we look at (and absorb) its children.
+        // Nothing in here calls out to user code, so the tile can't get lost.
         let Some(mut tile) = self.tiles.remove(&it) else {
-            log::debug!("Failed to find tile {it:?} during simplify");
             return SimplifyAction::Remove;
         };
 
         if let Tile::Container(container) = &mut tile {
-            let kind = container.kind();
-            container.simplify_children(|child| self.simplify(options, child, Some(kind)));
+            let mut child_actions = child_actions.into_iter();
+            container.simplify_children(|_| child_actions.next().unwrap_or(SimplifyAction::Keep));
 
             if kind == ContainerKind::Tabs {
                 if options.prune_empty_tabs && container.is_empty() {
                     log::trace!("Simplify: removing empty tabs container");
+                    report.pruned.push(it);
                     return SimplifyAction::Remove;
                 }
 
@@ -457,11 +1248,38 @@ impl<Pane> Tiles<Pane> {
                             // Keep it, even though we only have one child
                         } else {
                             log::trace!("Simplify: collapsing single-child tabs container");
+                            report.collapsed.push(it);
                             return SimplifyAction::Replace(only_child);
                         }
                     }
                 }
             } else {
+                if options.join_nested_grids && kind == ContainerKind::Grid {
+                    let only_grid_child = container.only_child().filter(|only_child| {
+                        matches!(
+                            self.tiles.get(only_child),
+                            Some(Tile::Container(Container::Grid(_)))
+                        )
+                    });
+                    if let Some(only_child) = only_grid_child {
+                        if let Some(Tile::Container(inner)) = self.tiles.remove(&only_child) {
+                            log::trace!("Simplify: absorbing nested grid");
+                            *container = inner;
+                            report.joined.push(only_child);
+                        }
+                    }
+                }
+
+                if options.convert_single_line_grids {
+                    if let Container::Grid(grid) = container {
+                        if let Some(linear) = grid.to_linear() {
+                            log::trace!("Simplify: turning single-line grid into linear container");
+                            *container = Container::Linear(linear);
+                            report.converted.push(it);
+                        }
+                    }
+                }
+
                 if options.join_nested_linear_containers {
                     if let Container::Linear(parent) = container {
                         let mut new_children = Vec::with_capacity(parent.children.len());
@@ -489,6 +1307,7 @@ impl<Pane> Tiles<Pane> {
                                     }
 
                                     self.tiles.remove(&child_id);
+                                    report.joined.push(child_id);
                                 } else {
                                     // keep the child
                                     new_children.push(child_id);
@@ -503,11 +1322,13 @@ impl<Pane> Tiles<Pane> {
 
                 if options.prune_empty_containers && container.is_empty() {
                     log::trace!("Simplify: removing empty container tile");
+                    report.pruned.push(it);
                     return SimplifyAction::Remove;
                 }
                 if options.prune_single_child_containers {
                     if let Some(only_child) = container.only_child() {
                         log::trace!("Simplify: collapsing single-child container tile");
+                        report.collapsed.push(it);
                         return SimplifyAction::Replace(only_child);
                     }
                 }
@@ -519,31 +1340,58 @@ impl<Pane> Tiles<Pane> {
     }
 
     pub(super) fn make_all_panes_children_of_tabs(&mut self, parent_is_tabs: bool, it: TileId) {
-        let Some(mut tile) = self.tiles.remove(&it) else {
-            log::debug!("Failed to find tile {it:?} during make_all_panes_children_of_tabs");
-            return;
-        };
+        self.wrap_panes_in_tabs(
+            parent_is_tabs,
+            it,
+            usize::MAX,
+            &mut |_, _| true,
+            &mut Vec::new(),
+        );
+    }
 
-        match &mut tile {
-            Tile::Pane(_) => {
-                if !parent_is_tabs {
+    /// Give each pane that isn't in a [`Tabs`] container one of its own,
+    /// going at most `max_depth` levels down, and only if `should_wrap` agrees.
+    ///
+    /// The [`Tabs`] container takes the place (and [`TileId`]) of the pane,
+    /// which moves to a new [`TileId`]. Each such `(old, new)` pair is added to `wrapped`.
+    pub(super) fn wrap_panes_in_tabs(
+        &mut self,
+        parent_is_tabs: bool,
+        it: TileId,
+        max_depth: usize,
+        should_wrap: &mut dyn FnMut(TileId, &Pane) -> bool,
+        wrapped: &mut Vec<(TileId, TileId)>,
+    ) {
+        self.parents.invalidate();
+        match self.tiles.get(&it) {
+            Some(Tile::Pane(pane)) => {
+                if !parent_is_tabs && should_wrap(it, pane) {
                     // Add tabs to this pane:
                     log::trace!("Auto-adding Tabs-parent to pane {it:?}");
-                    let new_id = self.insert_new(tile);
-                    self.tiles
-                        .insert(it, Tile::Container(Container::new_tabs(vec![new_id])));
-                    return;
+                    let new_id = self.next_free_id();
+                    if let Some(pane) = self
+                        .tiles
+                        .insert(it, Tile::Container(Container::new_tabs(vec![new_id])))
+                    {
+                        self.tiles.insert(new_id, pane);
+                        wrapped.push((it, new_id));
+                    }
                 }
             }
-            Tile::Container(container) => {
+            Some(Tile::Container(container)) => {
+                if max_depth == 0 {
+                    return;
+                }
                 let is_tabs = container.kind() == ContainerKind::Tabs;
-                for &child in container.children() {
-                    self.make_all_panes_children_of_tabs(is_tabs, child);
+                let children: Vec<TileId> = container.children().copied().collect();
+                for child in children {
+                    self.wrap_panes_in_tabs(is_tabs, child, max_depth - 1, should_wrap, wrapped);
                 }
             }
+            None => {
+                log::debug!("Failed to find tile {it:?} during make_all_panes_children_of_tabs");
+            }
         }
-
-        self.tiles.insert(it, tile);
     }
 
     /// Returns true if the active tile was found in this tree.
@@ -552,23 +1400,37 @@ impl<Pane> Tiles<Pane> {
         it: TileId,
         should_activate: &mut dyn FnMut(TileId, &Tile<Pane>) -> bool,
     ) -> bool {
-        let Some(mut tile) = self.tiles.remove(&it) else {
+        self.make_tile_active(&mut Default::default(), it, should_activate)
+    }
+
+    fn make_tile_active(
+        &mut self,
+        in_progress: &mut TileIdSet,
+        it: TileId,
+        should_activate: &mut dyn FnMut(TileId, &Tile<Pane>) -> bool,
+    ) -> bool {
+        let Some(tile) = self.tiles.get(&it) else {
             log::debug!("Failed to find tile {it:?} during make_active");
             return false;
         };
+        if !in_progress.insert(it) {
+            log::debug!("Cycle detected at tile {it:?} during make_active");
+            return false;
+        }
 
-        let mut activate = should_activate(it, &tile);
+        let mut activate = should_activate(it, tile);
 
-        if let Tile::Container(container) = &mut tile {
+        if let Some(Tile::Container(container)) = self.tiles.get(&it) {
+            let children: Vec<TileId> = container.children().copied().collect();
             let mut active_child = None;
-            for &child in container.children() {
-                if self.make_active(child, should_activate) {
+            for child in children {
+                if self.make_tile_active(in_progress, child, should_activate) {
                     active_child = Some(child);
                 }
             }
 
             if let Some(active_child) = active_child {
-                if let Container::Tabs(tabs) = container {
+                if let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get_mut(&it) {
                     tabs.set_active(active_child);
                 }
             }
@@ -576,13 +1438,30 @@ impl<Pane> Tiles<Pane> {
             activate |= active_child.is_some();
         }
 
-        self.tiles.insert(it, tile);
+        in_progress.remove(&it);
         activate
     }
 }
 
+impl<Pane: Clone> Tiles<Pane> {
+    /// Insert a new pane tile holding a clone of the pane in the given tile.
+    ///
+    /// The new tile is not placed anywhere yet, so add it to a container.
+    /// If your pane is a handle (see "Shared panes" in the crate docs),
+    /// both tiles will show the same underlying view.
+    ///
+    /// Returns `None` if the given tile is not a pane.
+    #[must_use]
+    pub fn insert_pane_copy(&mut self, tile_id: TileId) -> Option<TileId> {
+        let pane = self.get_pane(&tile_id)?.clone();
+        Some(self.insert_pane(pane))
+    }
+}
+
 impl<Pane: PartialEq> Tiles<Pane> {
     /// Find the tile with the given pane.
+    ///
+    /// If the same pane is shown in several tiles, any one of them is returned.
     pub fn find_pane(&self, needle: &Pane) -> Option<TileId> {
         self.tiles
             .iter()
@@ -596,3 +1475,13 @@ impl<Pane: PartialEq> Tiles<Pane> {
             .map(|(tile_id, _)| *tile_id)
     }
 }
+
+impl Tiles<()> {
+    /// Fill in the panes of a tile structure created with [`Tiles::split_panes`].
+    ///
+    /// `pane` is called with the [`TileId`] of each pane slot.
+    /// Slots for which it returns `None` are removed.
+    pub fn join_panes<Pane>(self, mut pane: impl FnMut(TileId) -> Option<Pane>) -> Tiles<Pane> {
+        self.filter_map_panes(|tile_id, ()| pane(tile_id))
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{Container, LinearDir, Tile, TileId, Tree};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flip {
+    Transpose,
+    RotateClockwise,
+}
+
+impl<Pane> Tree<Pane> {
+    /// Swap horizontal and vertical in the given container,
+    /// and in all containers below it if `recursive`.
+    ///
+    /// The children keep their order and shares, so left becomes top and right becomes bottom.
+    /// Grids swap their rows and columns. Grids with template areas are left as they are.
+    /// [`crate::Tabs`] have no direction, but are passed through when `recursive`.
+    ///
+    /// Useful when going from a landscape to a portrait monitor.
+    pub fn transpose(&mut self, container_id: TileId, recursive: bool) {
+        self.flip_containers(container_id, recursive, Flip::Transpose);
+    }
+
+    /// Rotate the layout of the given container and everything in it 90° clockwise.
+    ///
+    /// Like [`Self::transpose`], except that what was on top ends up on the right,
+    /// like turning a printed copy of the layout.
+    pub fn rotate_clockwise(&mut self, container_id: TileId) {
+        self.flip_containers(container_id, true, Flip::RotateClockwise);
+    }
+
+    fn flip_containers(&mut self, tile_id: TileId, recursive: bool, flip: Flip) {
+        let Some(Tile::Container(container)) = self.tiles.get_mut(tile_id) else {
+            return;
+        };
+        let rotate = flip == Flip::RotateClockwise;
+        match container {
+            Container::Tabs(_) => {}
+            Container::Linear(linear) => {
+                linear.dir = match linear.dir {
+                    LinearDir::Horizontal => LinearDir::Vertical,
+                    LinearDir::Vertical => {
+                        if rotate {
+                            // Top to right:
+                            linear.children.reverse();
+                        }
+                        LinearDir::Horizontal
+                    }
+                };
+            }
+            Container::Grid(grid) => {
+                grid.transpose(rotate);
+            }
+        }
+
+        if recursive {
+            for child in container.children_vec() {
+                self.flip_containers(child, recursive, flip);
+            }
+        }
+    }
+}
@@ -1,13 +1,29 @@
-use egui::{NumExt as _, Rect, Ui};
-
-use crate::behavior::EditAction;
-use crate::{ContainerInsertion, ContainerKind, UiResponse};
+use egui::{vec2, Color32, NumExt as _, Rect, Ui};
+
+use crate::behavior::{EditAction, EditSummary, VisibilityReason};
+use crate::filter::TreeFilter;
+use crate::format_debug::write_tile_tree;
+use crate::history::History;
+use crate::metadata::TileMetadata;
+use crate::tab_selection::TabSelection;
+use crate::tile_id_hash::{TileIdMap, TileIdSet};
+use crate::tiles::LayoutParams;
+use crate::{
+    ContainerInsertion, ContainerKind, ContainerOverflow, DraggedTile, Edge, EditMode, TileDrop,
+    TreeEvent, TreeResponse, UiResponse,
+};
 
 use super::{
-    Behavior, Container, DropContext, InsertionPoint, SimplificationOptions, SimplifyAction, Tile,
-    TileId, Tiles,
+    Behavior, Container, DropContext, DropOperation, InsertionPoint, NormalizePolicy,
+    SimplificationOptions, SimplifyAction, SimplifyReport, Tile, TileId, Tiles,
 };
 
+/// The version of the serialization format of [`Tree`].
+///
+/// This is bumped whenever the format changes in a way that requires [`Tree::migrate`]
+/// to upgrade older layouts.
+pub const FORMAT_VERSION: u32 = 1;
+
 /// The top level type. Contains all persistent state, including layouts and sizes.
 ///
 /// You'll usually construct this once and then store it, calling [`Tree::ui`] each frame.
@@ -26,9 +42,15 @@ use super::{
 ///
 /// let tree = Tree::new("my_tree", root, tiles);
 /// ```
-#[derive(Clone, PartialEq)]
+#[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Tree<Pane> {
+    /// The [`FORMAT_VERSION`] this tree was created with.
+    ///
+    /// Trees serialized before the format was versioned deserialize as version `0`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    format_version: u32,
+
     /// The constant, globally unique id of this tree.
     pub(crate) id: egui::Id,
 
@@ -42,7 +64,8 @@ pub struct Tree<Pane> {
     #[cfg_attr(
         feature = "serde",
         serde(serialize_with = "serialize_f32_infinity_as_null"),
-        serde(deserialize_with = "deserialize_f32_null_as_infinity")
+        serde(deserialize_with = "deserialize_f32_null_as_infinity"),
+        serde(default = "f32_infinity")
     )]
     height: f32,
 
@@ -50,9 +73,99 @@ pub struct Tree<Pane> {
     #[cfg_attr(
         feature = "serde",
         serde(serialize_with = "serialize_f32_infinity_as_null"),
-        serde(deserialize_with = "deserialize_f32_null_as_infinity")
+        serde(deserialize_with = "deserialize_f32_null_as_infinity"),
+        serde(default = "f32_infinity")
     )]
     width: f32,
+
+    /// Tiles that have been taken out of the tree and collapsed into a strip along one of its edges.
+    ///
+    /// See [`Self::auto_hide`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) auto_hidden: Vec<(TileId, Edge)>,
+
+    /// The tile currently shown as a modal over the rest of the tree, if any.
+    ///
+    /// This is persisted, so that a restored layout shows the same modal.
+    ///
+    /// See [`Self::enter_modal`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    modal: Option<TileId>,
+
+    /// Groups of tabs that are activated together. See [`Self::link_tabs`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub(crate) tab_links: Vec<Vec<TileId>>,
+
+    /// Opt-in undo/redo history. See [`Self::enable_history`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) history: Option<Box<History>>,
+
+    /// Kept across frames, so the drop context doesn't need to reallocate it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tab_button_rects: TileIdMap<Rect>,
+
+    /// See [`Self::focused_pane`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) focused: Option<TileId>,
+
+    /// See [`Self::set_edit_mode`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) edit_mode: EditMode,
+
+    /// See [`Self::set_debug_overlay`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) debug_overlay: bool,
+
+    /// See [`Self::set_right_to_left`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) right_to_left: bool,
+
+    /// See [`Self::set_filter`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) filter: Option<TreeFilter>,
+
+    /// See [`Self::selected_tabs`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) tab_selection: Option<TabSelection>,
+}
+
+impl<Pane: PartialEq> PartialEq for Tree<Pane> {
+    fn eq(&self, other: &Self) -> bool {
+        let Self {
+            format_version,
+            id,
+            root,
+            tiles,
+            height,
+            width,
+            auto_hidden,
+            modal,
+            tab_links,
+            history: _,          // ignore transient state
+            tab_button_rects: _, // ignore transient state
+            focused: _,          // ignore transient state
+            edit_mode: _,        // ignore transient state
+            debug_overlay: _,    // ignore transient state
+            right_to_left: _,    // ignore transient state
+            filter: _,           // ignore transient state
+            tab_selection: _,    // ignore transient state
+        } = self;
+        format_version == &other.format_version
+            && id == &other.id
+            && root == &other.root
+            && tiles == &other.tiles
+            && height == &other.height
+            && width == &other.width
+            && auto_hidden == &other.auto_hidden
+            && modal == &other.modal
+            && tab_links == &other.tab_links
+    }
 }
 
 // Workaround for JSON which doesn't support infinity, because JSON is stupid.
@@ -68,6 +181,11 @@ fn serialize_f32_infinity_as_null<S: serde::Serializer>(
     }
 }
 
+#[cfg(feature = "serde")]
+fn f32_infinity() -> f32 {
+    f32::INFINITY
+}
+
 #[cfg(feature = "serde")]
 fn deserialize_f32_null_as_infinity<'de, D: serde::Deserializer<'de>>(
     des: D,
@@ -81,41 +199,38 @@ impl<Pane: std::fmt::Debug> std::fmt::Debug for Tree<Pane> {
         // Print a hierarchical view of the tree:
         fn format_tile<Pane: std::fmt::Debug>(
             f: &mut std::fmt::Formatter<'_>,
-            tiles: &Tiles<Pane>,
-            indent: usize,
             tile_id: TileId,
+            _parent: Option<&Container>,
+            tile: Option<&Tile<Pane>>,
         ) -> std::fmt::Result {
-            write!(f, "{} {tile_id:?}: ", "  ".repeat(indent))?;
-            if let Some(tile) = tiles.get(tile_id) {
-                match tile {
-                    Tile::Pane(pane) => writeln!(f, "Pane {pane:?}"),
-                    Tile::Container(container) => {
-                        writeln!(
-                            f,
-                            "{}",
-                            match container {
-                                Container::Tabs(_) => "Tabs",
-                                Container::Linear(_) => "Linear",
-                                Container::Grid(_) => "Grid",
-                            }
-                        )?;
-                        for &child in container.children() {
-                            format_tile(f, tiles, indent + 1, child)?;
-                        }
-                        Ok(())
-                    }
-                }
-            } else {
-                writeln!(f, "DANGLING")
+            write!(f, " {tile_id:?}: ")?;
+            match tile {
+                None => write!(f, "DANGLING"),
+                Some(Tile::Pane(pane)) => write!(f, "Pane {pane:?}"),
+                Some(Tile::Container(Container::Tabs(_))) => write!(f, "Tabs"),
+                Some(Tile::Container(Container::Linear(_))) => write!(f, "Linear"),
+                Some(Tile::Container(Container::Grid(_))) => write!(f, "Grid"),
             }
         }
 
         let Self {
+            format_version: _,
             id,
             root,
             tiles,
             width,
             height,
+            auto_hidden,
+            modal: _,
+            tab_links: _,
+            history: _,
+            tab_button_rects: _,
+            focused: _,
+            edit_mode: _,
+            debug_overlay: _,
+            right_to_left: _,
+            filter: _,
+            tab_selection: _,
         } = self;
 
         if let Some(root) = root {
@@ -123,7 +238,11 @@ impl<Pane: std::fmt::Debug> std::fmt::Debug for Tree<Pane> {
             writeln!(f, "    id: {id:?}")?;
             writeln!(f, "    width: {width:?}")?;
             writeln!(f, "    height: {height:?}")?;
-            format_tile(f, tiles, 1, *root)?;
+            write_tile_tree(f, tiles, 1, *root, None, &mut format_tile)?;
+            for &(tile_id, edge) in auto_hidden {
+                writeln!(f, "  auto-hidden at {edge:?}:")?;
+                write_tile_tree(f, tiles, 2, tile_id, None, &mut format_tile)?;
+            }
             write!(f, "}}")
         } else {
             writeln!(f, "Tree {{ }}")
@@ -140,11 +259,23 @@ impl<Pane> Tree<Pane> {
     /// This is so that the same tree can be added to different [`egui::Ui`]s (if you want).
     pub fn empty(id: impl Into<egui::Id>) -> Self {
         Self {
+            format_version: FORMAT_VERSION,
             id: id.into(),
             root: None,
             tiles: Default::default(),
             width: f32::INFINITY,
             height: f32::INFINITY,
+            auto_hidden: Vec::new(),
+            modal: None,
+            tab_links: Vec::new(),
+            history: None,
+            tab_button_rects: Default::default(),
+            focused: None,
+            edit_mode: EditMode::Normal,
+            debug_overlay: false,
+            right_to_left: false,
+            filter: None,
+            tab_selection: None,
         }
     }
 
@@ -155,11 +286,23 @@ impl<Pane> Tree<Pane> {
     /// This is so that the same tree can be added to different [`egui::Ui`]s (if you want).
     pub fn new(id: impl Into<egui::Id>, root: TileId, tiles: Tiles<Pane>) -> Self {
         Self {
+            format_version: FORMAT_VERSION,
             id: id.into(),
             root: Some(root),
             tiles,
             width: f32::INFINITY,
             height: f32::INFINITY,
+            auto_hidden: Vec::new(),
+            modal: None,
+            tab_links: Vec::new(),
+            history: None,
+            tab_button_rects: Default::default(),
+            focused: None,
+            edit_mode: EditMode::Normal,
+            debug_overlay: false,
+            right_to_left: false,
+            filter: None,
+            tab_selection: None,
         }
     }
 
@@ -209,21 +352,203 @@ impl<Pane> Tree<Pane> {
         Self::new(id, root, tiles)
     }
 
+    /// Reset the layout to that of `template`, while keeping the existing panes.
+    ///
+    /// The template is a tree of keys rather than panes.
+    /// Each existing pane is matched to a slot in the template using `key_of`.
+    /// Panes without a matching slot are dropped,
+    /// and slots without a matching pane are filled using `create`.
+    ///
+    /// The resulting tree uses the same [`TileId`]s as the template.
+    /// A kept pane keeps its metadata (see [`Tiles::set_metadata`]),
+    /// the auto-hidden tiles and tab links are those of the template,
+    /// and the focus, tab selection, modal and undo history are cleared.
+    /// Recorded events and edits are kept.
+    ///
+    /// ```
+    /// use egui_tiles::Tree;
+    ///
+    /// struct Pane {
+    ///     name: &'static str,
+    ///     scroll: f32, // some state we don't want to lose
+    /// }
+    ///
+    /// let default_layout = Tree::new_horizontal("default", vec!["left", "right"]);
+    ///
+    /// let mut tree = Tree::new_tabs("my_tree", vec![Pane { name: "right", scroll: 42.0 }]);
+    /// tree.reset_layout(&default_layout, |pane| pane.name, |&name| Pane { name, scroll: 0.0 });
+    /// ```
+    pub fn reset_layout<Key: Eq + std::hash::Hash>(
+        &mut self,
+        template: &Tree<Key>,
+        mut key_of: impl FnMut(&Pane) -> Key,
+        mut create: impl FnMut(&Key) -> Pane,
+    ) {
+        // The metadata of a pane moves along with it:
+        let mut existing_panes: ahash::HashMap<Key, (Pane, Option<TileMetadata>)> =
+            Default::default();
+        let tile_ids: Vec<TileId> = self.tiles.tile_ids().collect();
+        for tile_id in tile_ids {
+            self.tiles.set_visible(tile_id, true);
+            let metadata = self.tiles.metadata.remove(&tile_id);
+            if let Some(Tile::Pane(pane)) = self.tiles.remove(tile_id) {
+                existing_panes
+                    .entry(key_of(&pane))
+                    .or_insert((pane, metadata));
+            }
+        }
+
+        // Reset in place, so recorded events and edits are kept:
+        self.tiles.metadata.clear();
+        self.tiles.rects.clear();
+        self.tiles.transition = None;
+        self.tiles.invalidate_layout();
+        for (&tile_id, tile) in template.tiles.iter() {
+            let mut metadata = template.tiles.metadata.get(&tile_id).cloned();
+            let tile = match tile {
+                Tile::Pane(key) => match existing_panes.remove(key) {
+                    Some((pane, pane_metadata)) => {
+                        metadata = pane_metadata.or(metadata);
+                        Tile::Pane(pane)
+                    }
+                    None => Tile::Pane(create(key)),
+                },
+                Tile::Container(container) => Tile::Container(container.clone()),
+            };
+            self.tiles.insert(tile_id, tile);
+            self.tiles
+                .set_visible(tile_id, template.tiles.is_visible(tile_id));
+            if let Some(metadata) = metadata {
+                self.tiles.metadata.insert(tile_id, metadata);
+            }
+        }
+
+        self.root = template.root;
+        self.auto_hidden = template.auto_hidden.clone();
+        self.tab_links = template.tab_links.clone();
+        self.modal = None;
+        self.focused = None;
+        self.tab_selection = None;
+
+        // The old layout used the same ids for other tiles, so it can't be undone into:
+        self.clear_history();
+    }
+
+    /// Convert each pane into another type, keeping the layout.
+    pub fn map_panes<Out>(self, mut f: impl FnMut(TileId, Pane) -> Out) -> Tree<Out> {
+        self.filter_map_panes(|tile_id, pane| Some(f(tile_id, pane)))
+    }
+
+    /// Like [`Self::map_panes`], but panes for which `f` returns `None` are removed from the layout.
+    pub fn filter_map_panes<Out>(self, f: impl FnMut(TileId, Pane) -> Option<Out>) -> Tree<Out> {
+        let Self {
+            format_version,
+            id,
+            root,
+            tiles,
+            height,
+            width,
+            auto_hidden,
+            modal,
+            tab_links,
+            history: _, // the history can't be converted
+            tab_button_rects: _,
+            focused,
+            edit_mode,
+            debug_overlay,
+            right_to_left,
+            filter,
+            tab_selection,
+        } = self;
+        Tree {
+            format_version,
+            id,
+            root,
+            tiles: tiles.filter_map_panes(f),
+            height,
+            width,
+            auto_hidden,
+            modal,
+            tab_links,
+            history: None,
+            tab_button_rects: Default::default(),
+            focused,
+            edit_mode,
+            debug_overlay,
+            right_to_left,
+            filter,
+            tab_selection,
+        }
+    }
+
+    /// Split the tree into its layout and a side table of panes.
+    ///
+    /// The layout (ids, container kinds, shares, active tabs, …) can be serialized on its own,
+    /// while each pane is converted with `f`, e.g. into a key or a serialized blob,
+    /// so it can be stored and versioned elsewhere.
+    ///
+    /// Use [`Tree::join_panes`] to put them back together.
+    pub fn split_panes<Out>(
+        &self,
+        f: impl FnMut(TileId, &Pane) -> Out,
+    ) -> (Tree<()>, std::collections::BTreeMap<TileId, Out>) {
+        let (tiles, panes) = self.tiles.split_panes(f);
+        let layout = Tree {
+            format_version: self.format_version,
+            id: self.id,
+            root: self.root,
+            tiles,
+            height: self.height,
+            width: self.width,
+            auto_hidden: self.auto_hidden.clone(),
+            modal: self.modal,
+            tab_links: self.tab_links.clone(),
+            history: None,
+            tab_button_rects: Default::default(),
+            focused: self.focused,
+            edit_mode: self.edit_mode,
+            debug_overlay: self.debug_overlay,
+            right_to_left: self.right_to_left,
+            filter: self.filter.clone(),
+            tab_selection: self.tab_selection.clone(),
+        };
+        (layout, panes)
+    }
+
     /// Remove the given tile and all child tiles, recursively.
     ///
     /// This also removes the tile id from the parent's list of children.
     ///
     /// All removed tiles are returned in unspecified order.
     pub fn remove_recursively(&mut self, id: TileId) -> Vec<Tile<Pane>> {
+        self.take_recursively(id)
+            .into_iter()
+            .map(|(_, tile)| tile)
+            .collect()
+    }
+
+    /// Like [`Self::remove_recursively`], but for when the user closes the tile:
+    /// the removed panes are kept for [`Self::undo`] if history is enabled.
+    pub(crate) fn close_recursively(&mut self, id: TileId) {
+        let removed = self.take_recursively(id);
+        self.tiles.bury(removed);
+    }
+
+    fn take_recursively(&mut self, id: TileId) -> Vec<(TileId, Tile<Pane>)> {
         // Remove the top-most tile_id from its parent
         self.remove_tile_id_from_parent(id);
+        self.auto_hidden.retain(|&(tile_id, _)| tile_id != id);
 
         let mut removed_tiles = vec![];
         self.remove_recursively_impl(id, &mut removed_tiles);
         removed_tiles
     }
 
-    fn remove_recursively_impl(&mut self, id: TileId, removed_tiles: &mut Vec<Tile<Pane>>) {
+    fn remove_recursively_impl(
+        &mut self,
+        id: TileId,
+        removed_tiles: &mut Vec<(TileId, Tile<Pane>)>,
+    ) {
         // We can safely use the raw `tiles.remove` API here because either the parent was cleaned
         // up explicitly from `remove_recursively` or the parent is also being removed so there's
         // no reason to clean it up.
@@ -233,10 +558,51 @@ impl<Pane> Tree<Pane> {
                     self.remove_recursively_impl(child_id, removed_tiles);
                 }
             }
-            removed_tiles.push(tile);
+            removed_tiles.push((id, tile));
         }
     }
 
+    /// The [`FORMAT_VERSION`] this tree was created with,
+    /// or deserialized from if [`Self::migrate`] hasn't been called yet.
+    #[inline]
+    pub fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
+    /// Upgrade a tree deserialized from an older [`FORMAT_VERSION`] to the current one.
+    ///
+    /// Missing fields are already filled in with defaults during deserialization;
+    /// this fixes up anything that needs more than that.
+    /// So far that is only resetting the invalid shares (zero, negative, or not finite)
+    /// that layouts saved before versioning (format version 0) could contain.
+    /// Nothing has been renamed yet; if something is, the old name will keep loading
+    /// as a serde alias, so no migration step is needed for that.
+    ///
+    /// This is also called at the start of [`Self::ui`],
+    /// but you may want to call it yourself right after deserializing.
+    pub fn migrate(&mut self) {
+        if FORMAT_VERSION <= self.format_version {
+            return;
+        }
+
+        log::debug!(
+            "Migrating tree from format version {} to {FORMAT_VERSION}",
+            self.format_version
+        );
+
+        if self.format_version < 1 {
+            // Before versioning, shares were not validated when resizing,
+            // so broken layouts could be persisted.
+            for tile in self.tiles.tiles_mut() {
+                if let Tile::Container(container) = tile {
+                    container.reset_invalid_shares();
+                }
+            }
+        }
+
+        self.format_version = FORMAT_VERSION;
+    }
+
     /// The globally unique id used by this `Tree`.
     #[inline]
     pub fn id(&self) -> egui::Id {
@@ -273,6 +639,15 @@ impl<Pane> Tree<Pane> {
         self.tiles.set_visible(tile_id, visible);
     }
 
+    /// Hide the tile if it is visible, and vice versa.
+    ///
+    /// Useful for e.g. a "toggle the console" button:
+    /// the tile keeps its place and size while hidden,
+    /// and the other tiles take up its space in the meantime.
+    pub fn toggle_visibility(&mut self, tile_id: TileId) {
+        self.tiles.toggle_visibility(tile_id);
+    }
+
     /// All visible tiles.
     ///
     /// This excludes all tiles that invisible or are inactive tabs, recursively.
@@ -288,24 +663,230 @@ impl<Pane> Tree<Pane> {
         tiles
     }
 
+    /// Is the tile shown, i.e. on the active path from the root?
+    ///
+    /// That means the tile and all its ancestors are visible (see [`Self::is_visible`]),
+    /// every [`crate::Tabs`] above it has it (or one of its ancestors) as the active tab,
+    /// and no [`crate::Linear`] above it has it folded away (see [`crate::Linear::collapsible`]).
+    /// Auto-hidden tiles count as not shown, even while their flyout is open.
+    ///
+    /// Unlike [`Self::active_tiles`] this only looks at the ancestors,
+    /// so it is cheap enough to e.g. skip background work for panes nobody can see.
+    pub fn is_shown(&self, tile_id: TileId) -> bool {
+        let mut child = None;
+        for tile_id in std::iter::once(tile_id).chain(self.tiles.ancestors(tile_id)) {
+            let Some(tile) = self.tiles.get(tile_id) else {
+                return false;
+            };
+            if !self.is_visible(tile_id) {
+                return false;
+            }
+            let hides_child = match (tile, child) {
+                (Tile::Container(Container::Tabs(tabs)), Some(child)) => tabs.active != Some(child),
+                (Tile::Container(Container::Linear(linear)), Some(child)) => {
+                    linear.is_collapsed(child)
+                }
+                _ => false,
+            };
+            if hides_child {
+                return false;
+            }
+            child = Some(tile_id);
+        }
+        child.is_some_and(|top| self.is_root(top))
+    }
+
+    /// Why the given pane isn't shown, assuming it isn't.
+    pub(crate) fn hidden_reason(&self, tile_id: TileId) -> VisibilityReason {
+        let mut child = None;
+        for tile_id in std::iter::once(tile_id).chain(self.tiles.ancestors(tile_id)) {
+            if !self.is_visible(tile_id) {
+                return VisibilityReason::Invisible;
+            }
+            match (self.tiles.get(tile_id), child) {
+                (Some(Tile::Container(Container::Tabs(tabs))), Some(child))
+                    if tabs.active != Some(child) =>
+                {
+                    return VisibilityReason::TabSwitched;
+                }
+                (Some(Tile::Container(Container::Linear(linear))), Some(child))
+                    if linear.is_collapsed(child) =>
+                {
+                    return VisibilityReason::Collapsed;
+                }
+                _ => {}
+            }
+            child = Some(tile_id);
+        }
+        if child.is_some_and(|top| self.is_auto_hidden(top)) {
+            VisibilityReason::AutoHidden
+        } else {
+            VisibilityReason::OutOfView
+        }
+    }
+
+    /// Collapse the given tile into a thin strip of labels along an edge of the tree.
+    ///
+    /// The tile is taken out of its parent container, and slides out over the rest of the tree
+    /// when its label is hovered or clicked, like an auto-hidden tool window in an IDE.
+    /// Use [`Self::pin`] to dock it back into the tree.
+    pub fn auto_hide(&mut self, tile_id: TileId, edge: Edge) {
+        if self.is_root(tile_id) {
+            log::warn!("Cannot auto-hide the root tile {tile_id:?}");
+            return;
+        }
+        if self.tiles.get(tile_id).is_none() {
+            log::warn!("Cannot auto-hide missing tile {tile_id:?}");
+            return;
+        }
+
+        self.remove_tile_id_from_parent(tile_id);
+        self.auto_hidden.retain(|&(id, _)| id != tile_id);
+        self.auto_hidden.push((tile_id, edge));
+    }
+
+    /// Dock an auto-hidden tile back into the tree, along the edge it was hidden at.
+    ///
+    /// Does nothing if the tile isn't auto-hidden.
+    pub fn pin(&mut self, tile_id: TileId) {
+        let Some(index) = self.auto_hidden.iter().position(|&(id, _)| id == tile_id) else {
+            return;
+        };
+        let (_, edge) = self.auto_hidden.remove(index);
+
+        if let Some(root) = self.root {
+            let insertion = match edge {
+                Edge::Left => ContainerInsertion::Horizontal(0),
+                Edge::Right => ContainerInsertion::Horizontal(usize::MAX),
+                Edge::Top => ContainerInsertion::Vertical(0),
+                Edge::Bottom => ContainerInsertion::Vertical(usize::MAX),
+            };
+            self.tiles
+                .insert_at(InsertionPoint::new(root, insertion), tile_id);
+        } else {
+            self.root = Some(tile_id);
+        }
+    }
+
+    /// Is the given tile collapsed into one of the auto-hide strips?
+    pub fn is_auto_hidden(&self, tile_id: TileId) -> bool {
+        self.auto_hidden.iter().any(|&(id, _)| id == tile_id)
+    }
+
+    /// All auto-hidden tiles, and the edge they are hidden at.
+    pub fn auto_hidden(&self) -> impl Iterator<Item = (TileId, Edge)> + '_ {
+        self.auto_hidden.iter().copied()
+    }
+
+    /// Present the given tile as a modal over the rest of the tree.
+    ///
+    /// The tile stays where it is in the tree, but is shown on top of a backdrop
+    /// (see [`Behavior::paint_modal_backdrop`]) in the rect given by [`Behavior::modal_rect`].
+    /// The rest of the tree is disabled until [`Self::exit_modal`] is called.
+    pub fn enter_modal(&mut self, tile_id: TileId) {
+        if self.tiles.get(tile_id).is_some() {
+            self.modal = Some(tile_id);
+        } else {
+            log::warn!("Cannot show missing tile {tile_id:?} as modal");
+        }
+    }
+
+    /// Stop showing the modal tile (if any), returning it.
+    ///
+    /// The tile is left where it was in the tree.
+    pub fn exit_modal(&mut self) -> Option<TileId> {
+        self.modal.take()
+    }
+
+    /// The tile currently shown as a modal, if any.
+    #[inline]
+    pub fn modal(&self) -> Option<TileId> {
+        self.modal
+    }
+
     /// Show the tree in the given [`Ui`].
     ///
     /// The tree will use upp all the available space - nothing more, nothing less.
-    pub fn ui(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &mut Ui) {
+    ///
+    /// Returns what the user did with the tree this frame.
+    pub fn ui(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &mut Ui) -> TreeResponse {
+        self.ui_impl(behavior, ui, false).0
+    }
+
+    /// Shared implementation of [`Self::ui`] and [`Self::ui_deferred`].
+    ///
+    /// If `defer_panes` is set, the docked panes are returned instead of shown.
+    fn ui_impl(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+        defer_panes: bool,
+    ) -> (TreeResponse, Vec<(TileId, Rect)>) {
+        profile_scope!("Tree::ui");
+        self.migrate();
+        self.tiles.resized = None;
+
+        let now = ui.input(|i| i.time);
+        if behavior.animate_insertions_and_removals() && self.tiles.structure_changed_since_seen() {
+            // Tiles were added or removed since last frame, so let the others make room smoothly:
+            self.tiles
+                .start_transition(now, behavior.layout_animation_duration(ui.style()));
+        }
+
         self.simplify(&behavior.simplification_options());
 
         self.gc(behavior);
+        self.tiles.update_parent_index();
+        self.validate_focus();
+        self.validate_tab_selection();
+        self.announce_inserted_tiles(behavior);
+        self.update_filter_matches(behavior);
 
         self.tiles.rects.clear();
+        let transition_in_progress = self
+            .tiles
+            .transition
+            .as_mut()
+            .is_some_and(|transition| transition.update(now, |t| behavior.animation_easing(t)));
+        if transition_in_progress {
+            ui.ctx().request_repaint();
+        } else {
+            self.tiles.transition = None;
+        }
+
+        let previously_shown_panes = std::mem::take(&mut self.tiles.shown_panes);
+
+        if let Some(modal) = self.modal {
+            if self.tiles.get(modal).is_none() {
+                // The modal tile was removed from the tree (or simplified away)
+                self.modal = None;
+            }
+        }
 
         // Check if anything is being dragged:
+        let dragged_tile_id = self
+            .dragged_id(ui.ctx())
+            .filter(|&tile_id| self.edit_permissions(behavior, tile_id).drag);
+        if let Some(tile_id) = dragged_tile_id {
+            // Let other widgets know what is being dragged:
+            let payload = DraggedTile {
+                tree_id: self.id,
+                tile_id,
+            };
+            egui::DragAndDrop::set_payload(ui.ctx(), payload);
+        }
         let mut drop_context = DropContext {
-            enabled: true,
-            dragged_tile_id: self.dragged_id(ui.ctx()),
+            // No need to look for drop targets when nothing is being dragged:
+            enabled: self.modal.is_none() && dragged_tile_id.is_some(),
+            dragged_tile_id,
             mouse_pos: ui.input(|i| i.pointer.interact_pos()),
             best_dist_sq: f32::INFINITY,
             best_insertion: None,
             preview_rect: None,
+            tab_button_rects: std::mem::take(&mut self.tab_button_rects),
+            no_drop_into: Default::default(),
+            right_to_left: self.right_to_left,
+            deferred_panes: defer_panes.then(Vec::new),
         };
 
         let mut rect = ui.available_rect_before_wrap();
@@ -315,14 +896,246 @@ impl<Pane> Tree<Pane> {
         if self.width.is_finite() {
             rect.set_width(self.width);
         }
+        let docked_rect = self.rect_without_auto_hide_strips(behavior, ui.style(), rect);
+        let min_size = self.min_size(behavior, ui.style());
+        let over_constrained =
+            docked_rect.width() < min_size.x || docked_rect.height() < min_size.y;
         if let Some(root) = self.root {
-            self.tiles.layout_tile(ui.style(), behavior, rect, root);
+            // Big grids in a scroll area only lay out what can be seen:
+            let viewport = behavior.cull_off_screen_tiles().then(|| ui.clip_rect());
+            self.tiles.layout_tile(
+                ui.style(),
+                self.layout_params(ui.ctx().pixels_per_point()),
+                behavior,
+                docked_rect,
+                viewport,
+                root,
+            );
 
             self.tile_ui(behavior, &mut drop_context, ui, root);
         }
 
-        self.preview_dragged_tile(behavior, &drop_context, ui);
+        // Only the docked panes are deferred. The overlays below are in layers of their own,
+        // so their panes are shown right away:
+        let deferred_panes = drop_context.deferred_panes.take().unwrap_or_default();
+        let dropped = self.preview_dragged_tile(behavior, &drop_context, ui);
+        self.tab_button_rects = drop_context.tab_button_rects;
+        self.auto_hide_ui(behavior, ui, rect);
+        self.modal_ui(behavior, ui, rect);
+        self.duplicate_requested(behavior);
+        self.debug_overlay_ui(behavior, ui);
+        self.jump_to_pane_ui(behavior, ui, rect);
+        self.notify_shown_and_hidden_panes(behavior, &previously_shown_panes);
+        self.record_history(ui);
+
+        let response = TreeResponse {
+            hovered_pane: self.hovered_pane(ui),
+            focused_pane: self.focused,
+            resized: self.tiles.resized,
+            dropped,
+            structure_changed: self.tiles.structure_changed_since_seen(),
+            over_constrained,
+        };
+        self.tiles.mark_structure_seen();
         ui.advance_cursor_after_rect(rect);
+        (response, deferred_panes)
+    }
+
+    /// The docked pane under the mouse pointer, if any.
+    ///
+    /// Flyouts and modals are in layers of their own, so they hide the panes below them.
+    fn hovered_pane(&self, ui: &Ui) -> Option<TileId> {
+        self.tiles.shown_panes.iter().copied().find(|&tile_id| {
+            self.tiles
+                .rect(tile_id)
+                .is_some_and(|rect| ui.rect_contains_pointer(rect))
+        })
+    }
+
+    /// Like [`Self::ui`], but without calling [`Behavior::pane_ui`] for the docked panes.
+    ///
+    /// Instead, this returns where each shown pane goes, in the order they would have been shown.
+    /// Show them yourself afterwards, e.g. in `ui.new_child(egui::UiBuilder::new().max_rect(rect))`.
+    /// This lets you prepare expensive pane contents (perhaps in parallel) after the layout is known.
+    ///
+    /// Panes in auto-hide flyouts, modals, and drag previews are still shown right away.
+    /// To let the user drag a deferred pane,
+    /// call `ui.ctx().set_dragged_id(tile_id.egui_id(tree.id()))` when they start dragging it,
+    /// or set a [`DraggedTile`] payload.
+    pub fn ui_deferred(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+    ) -> Vec<(TileId, Rect)> {
+        self.ui_impl(behavior, ui, true).1
+    }
+
+    /// Where each tile would go if the tree was shown in the given rect, without any [`Ui`].
+    ///
+    /// Nothing is painted and there is no interaction, and any ongoing animation is skipped,
+    /// so this is useful for testing layouts, or for laying out a tree on a server
+    /// (e.g. to export a dashboard as an image).
+    ///
+    /// Only the shown tiles get a rect, so e.g. inactive tabs and invisible tiles are left out.
+    /// The dividers are snapped to whole points, as if `pixels_per_point` was 1.
+    pub fn compute_layout(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        style: &egui::Style,
+        rect: Rect,
+    ) -> std::collections::BTreeMap<TileId, Rect> {
+        let Some(root) = self.root.filter(|&root| self.is_visible(root)) else {
+            return Default::default();
+        };
+
+        let transition = self.tiles.transition.take();
+        self.tiles.rects.clear();
+        let docked_rect = self.rect_without_auto_hide_strips(behavior, style, rect);
+        self.tiles.layout_tile(
+            style,
+            self.layout_params(1.0),
+            behavior,
+            docked_rect,
+            None,
+            root,
+        );
+        self.tiles.transition = transition;
+
+        self.tiles
+            .tile_ids()
+            .filter_map(|tile_id| Some((tile_id, self.tiles.rect(tile_id)?)))
+            .collect()
+    }
+
+    /// The edits the user made since the last call to this, e.g. in [`Self::ui`].
+    ///
+    /// Use this to find out when to save the layout, without comparing the whole tree each frame.
+    pub fn take_edit_summary(&mut self) -> EditSummary {
+        std::mem::take(&mut self.tiles.edits)
+    }
+
+    /// Call [`Behavior::on_pane_shown`] and [`Behavior::on_pane_hidden`]
+    /// for the panes that started or stopped being shown this frame.
+    fn notify_shown_and_hidden_panes(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        previously_shown_panes: &TileIdSet,
+    ) {
+        let mut shown: Vec<TileId> = self
+            .tiles
+            .shown_panes
+            .difference(previously_shown_panes)
+            .copied()
+            .collect();
+        let mut hidden: Vec<TileId> = previously_shown_panes
+            .difference(&self.tiles.shown_panes)
+            .copied()
+            .collect();
+        shown.sort();
+        hidden.sort();
+
+        for tile_id in hidden {
+            let reason = self.hidden_reason(tile_id);
+            if let Some(pane) = self.tiles.get_pane_mut(tile_id) {
+                behavior.on_pane_hidden(tile_id, pane, reason);
+                self.tiles.hidden_reasons.insert(tile_id, reason);
+                self.tiles
+                    .push_event(TreeEvent::PaneHidden { tile_id, reason });
+            }
+        }
+        for tile_id in shown {
+            let reason = self
+                .tiles
+                .hidden_reasons
+                .remove(&tile_id)
+                .unwrap_or(VisibilityReason::Inserted);
+            if let Some(pane) = self.tiles.get_pane_mut(tile_id) {
+                behavior.on_pane_shown(tile_id, pane, reason);
+                self.tiles
+                    .push_event(TreeEvent::PaneShown { tile_id, reason });
+            }
+        }
+    }
+
+    /// Show a pane that is hidden behind an inactive tab,
+    /// if [`Behavior::render_inactive`] asks for it.
+    ///
+    /// If the inactive tab is a container, this recurses into all its visible panes,
+    /// giving each of them the whole `rect` since hidden containers are not laid out.
+    pub(super) fn inactive_pane_ui(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+        rect: Rect,
+        tile_id: TileId,
+    ) {
+        if let Some(container) = self.tiles.get_container(tile_id) {
+            for child in container.children_vec() {
+                if self.is_visible(child) {
+                    self.inactive_pane_ui(behavior, ui, rect, child);
+                }
+            }
+            return;
+        }
+
+        let Some(pane) = self.tiles.get_pane_mut(tile_id) else {
+            return;
+        };
+        if !behavior.render_inactive(tile_id, pane) {
+            return;
+        }
+
+        let mut ui = ui.new_child(
+            egui::UiBuilder::new()
+                .id_salt(tile_id)
+                .max_rect(rect)
+                .invisible(),
+        );
+        // Nobody can start dragging an invisible pane:
+        let _ignored: UiResponse = behavior.pane_ui(&mut ui, tile_id, pane);
+    }
+
+    /// Show the modal tile (if any) on top of a backdrop covering the whole tree.
+    fn modal_ui(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &Ui, tree_rect: Rect) {
+        let Some(modal_id) = self.modal else {
+            return;
+        };
+
+        egui::Area::new(self.id.with("modal"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(tree_rect.min)
+            .show(ui.ctx(), |ui| {
+                // Block all interaction with the tree behind the modal:
+                let backdrop_response = ui.allocate_rect(tree_rect, egui::Sense::click());
+
+                let modal_rect = behavior.modal_rect(tree_rect);
+                behavior.paint_modal_backdrop(ui.visuals(), ui.painter(), tree_rect, modal_rect);
+
+                // While showing the modal tile itself, the tree should not be disabled:
+                self.modal = None;
+
+                self.tiles.layout_tile(
+                    ui.style(),
+                    self.layout_params(ui.ctx().pixels_per_point()),
+                    behavior,
+                    modal_rect,
+                    None,
+                    modal_id,
+                );
+
+                let mut drop_context = DropContext::disabled();
+                self.tile_ui(behavior, &mut drop_context, ui, modal_id);
+
+                self.modal = Some(modal_id);
+
+                let clicked_outside = backdrop_response.clicked()
+                    && backdrop_response
+                        .interact_pointer_pos()
+                        .is_some_and(|pos| !modal_rect.contains(pos));
+                if clicked_outside && behavior.close_modal_on_backdrop_click() {
+                    self.modal = None;
+                }
+            });
     }
 
     /// Sets the exact height that can be used by the tree.
@@ -349,6 +1162,32 @@ impl<Pane> Tree<Pane> {
         }
     }
 
+    /// Mirror the layout for right-to-left languages.
+    ///
+    /// Horizontal containers and tab bars then start at the right,
+    /// so the first child of a [`crate::Linear`] is shown rightmost,
+    /// and the first tab is the rightmost tab.
+    /// You can set this from the egui layout direction,
+    /// e.g. `tree.set_right_to_left(ui.layout().main_dir() == egui::Direction::RightToLeft)`.
+    ///
+    /// This is not persisted.
+    pub fn set_right_to_left(&mut self, right_to_left: bool) {
+        self.right_to_left = right_to_left;
+    }
+
+    /// See [`Self::set_right_to_left`].
+    pub fn is_right_to_left(&self) -> bool {
+        self.right_to_left
+    }
+
+    /// How to lay out the tiles when shown with the given pixels per point.
+    pub(crate) fn layout_params(&self, pixels_per_point: f32) -> LayoutParams {
+        LayoutParams {
+            pixels_per_point,
+            right_to_left: self.right_to_left,
+        }
+    }
+
     pub(super) fn tile_ui(
         &mut self,
         behavior: &mut dyn Behavior<Pane>,
@@ -356,16 +1195,21 @@ impl<Pane> Tree<Pane> {
         ui: &Ui,
         tile_id: TileId,
     ) {
-        if !self.is_visible(tile_id) {
+        if !self.is_visible(tile_id) || self.modal == Some(tile_id) {
             return;
         }
+        profile_scope!("Tree::tile_ui");
         // NOTE: important that we get the rect and tile in two steps,
         // otherwise we could loose the tile when there is no rect.
         let Some(rect) = self.tiles.rect(tile_id) else {
             log::debug!("Failed to find rect for tile {tile_id:?} during ui");
             return;
         };
-        let Some(mut tile) = self.tiles.remove(tile_id) else {
+        if behavior.cull_off_screen_tiles() && !ui.clip_rect().intersects(rect) {
+            // Scrolled out of view, or otherwise clipped away.
+            return;
+        }
+        let Some(tile) = self.tiles.get(tile_id) else {
             log::debug!("Failed to find tile {tile_id:?} during ui");
             return;
         };
@@ -375,10 +1219,41 @@ impl<Pane> Tree<Pane> {
             // Can't drag a tile onto self or any children
             drop_context.enabled = false;
         }
-        drop_context.on_tile(behavior, ui.style(), tile_id, rect, &tile);
+        if drop_context.enabled
+            && (!self.edit_permissions(behavior, tile_id).drop_into
+                || self.rejects_drop(behavior, tile_id, drop_context.dragged_tile_id))
+        {
+            drop_context.no_drop_into.insert(tile_id);
+        }
+        drop_context.on_tile(behavior, ui.style(), tile_id, rect, tile);
+
+        // Panes are shown in place. A container needs the whole tree for its children,
+        // so we take it out while showing it, leaving an empty container of the same kind
+        // in its place, and put it back afterwards.
+        let container = match tile {
+            Tile::Pane(_) => None,
+            Tile::Container(container) => {
+                let placeholder = Container::new(container.kind(), vec![]);
+                self.tiles.replace_container(tile_id, placeholder)
+            }
+        };
+
+        let depth = self.tiles.ancestors(tile_id).count();
+        let draggable = self.edit_permissions(behavior, tile_id).drag;
+        let rounding = behavior.tile_rounding(ui.style(), depth);
+        let shadow = behavior.tile_shadow(ui.style(), depth);
+        if shadow != egui::Shadow::NONE {
+            // Painted in the parent, since the shadow reaches outside the tile:
+            ui.painter().add(shadow.as_shape(rect, rounding));
+            ui.painter()
+                .rect_filled(rect, rounding, ui.visuals().panel_fill);
+        }
 
         // Each tile gets its own `Ui`, nested inside each other, with proper clip rectangles.
-        let enabled = ui.is_enabled();
+        // Everything behind a modal is disabled.
+        let enabled = ui.is_enabled() && self.modal.is_none();
+        let clip_rect = rect.intersect(ui.clip_rect());
+        let opacity = ui.opacity();
         let mut ui = egui::Ui::new(
             ui.ctx().clone(),
             ui.id().with(tile_id),
@@ -386,22 +1261,126 @@ impl<Pane> Tree<Pane> {
                 .layer_id(ui.layer_id())
                 .max_rect(rect),
         );
+        // Stay inside the parent's clip rect, so nested tiles know when they are off-screen,
+        // and fade along with the parent, e.g. during a tab switch:
+        ui.set_clip_rect(clip_rect);
+        ui.multiply_opacity(opacity);
 
         ui.add_enabled_ui(enabled, |ui| {
-            match &mut tile {
-                Tile::Pane(pane) => {
-                    if behavior.pane_ui(ui, tile_id, pane) == UiResponse::DragStarted {
-                        ui.ctx().set_dragged_id(tile_id.egui_id(self.id));
+            if let Some(mut container) = container {
+                let bg_color =
+                    behavior.container_bg_color(ui.visuals(), &self.tiles, tile_id, depth);
+                if bg_color != Color32::TRANSPARENT {
+                    ui.painter().rect_filled(rect, rounding, bg_color);
+                }
+                container.ui(self, behavior, drop_context, ui, rect, tile_id);
+                let kind = container.kind();
+                let placeholder = self.tiles.replace_container(tile_id, container);
+                // Keep any children that were added to the placeholder in the meantime:
+                let added = placeholder.map(|p| p.children_vec()).unwrap_or_default();
+                if !added.is_empty() {
+                    if let Some(Tile::Container(container)) = self.tiles.get_mut(tile_id) {
+                        for child in added {
+                            container.add_child(child);
+                        }
                     }
                 }
-                Tile::Container(container) => {
-                    container.ui(self, behavior, drop_context, ui, rect, tile_id);
+                if behavior.highlight_focused_group() && self.focused_group() == Some(tile_id) {
+                    let tab_bar_rect = (kind == ContainerKind::Tabs).then(|| {
+                        let mut tab_bar_rect = rect;
+                        tab_bar_rect.max.y = rect.top() + behavior.tab_bar_height(ui.style());
+                        tab_bar_rect
+                    });
+                    behavior.paint_focused_group_accent(
+                        ui.painter(),
+                        ui.visuals(),
+                        rect,
+                        tab_bar_rect,
+                    );
                 }
-            };
+            } else if let Some(deferred_panes) = &mut drop_context.deferred_panes {
+                deferred_panes.push((tile_id, rect));
+                self.tiles.shown_panes.insert(tile_id);
+            } else if let Some(pane) = self.tiles.get_pane_mut(tile_id) {
+                // Only the drag handle (if any) drags the pane, leaving the rest to `pane_ui`:
+                let mut pane_rect = rect;
+                let mut handle_drag_started = false;
+                let handle_height = behavior
+                    .pane_drag_handle_height(ui.style(), tile_id, pane)
+                    .at_most(rect.height());
+                if handle_height > 0.0 {
+                    let mut handle_rect = rect;
+                    handle_rect.max.y = rect.top() + handle_height;
+                    pane_rect.min.y = handle_rect.bottom();
+
+                    let handle_id = tile_id.egui_id(self.id).with("drag_handle");
+                    let sense = if draggable {
+                        egui::Sense::click_and_drag()
+                    } else {
+                        egui::Sense::click()
+                    };
+                    let mut response = ui.interact(handle_rect, handle_id, sense);
+                    if draggable {
+                        response = response.on_hover_cursor(egui::CursorIcon::Grab);
+                    }
+                    behavior.pane_drag_handle_ui(
+                        &mut ui.new_child(egui::UiBuilder::new().max_rect(handle_rect)),
+                        tile_id,
+                        pane,
+                        &response,
+                    );
+                    handle_drag_started = response.drag_started();
+                }
+
+                behavior.paint_pane_background(ui.painter(), pane_rect, tile_id, pane);
+                let frame = behavior.pane_frame(ui.style(), tile_id, pane);
+                let busy = behavior.is_pane_busy(tile_id, pane);
+                let response = if frame == egui::Frame::none() && pane_rect == rect && !busy {
+                    behavior.pane_ui(ui, tile_id, pane)
+                } else {
+                    let frame_rect = pane_rect - frame.outer_margin;
+                    ui.painter().add(frame.paint(frame_rect));
+                    let mut content_ui = ui.new_child(
+                        egui::UiBuilder::new().max_rect(frame_rect - frame.inner_margin),
+                    );
+                    if busy {
+                        content_ui.disable();
+                    }
+                    behavior.pane_ui(&mut content_ui, tile_id, pane)
+                };
+                if busy {
+                    // Swallow clicks and drags that would otherwise reach the pane:
+                    let busy_id = tile_id.egui_id(self.id).with("busy");
+                    ui.interact(pane_rect, busy_id, egui::Sense::click_and_drag());
+
+                    let mut busy_ui =
+                        ui.new_child(egui::UiBuilder::new().id_salt(busy_id).max_rect(pane_rect));
+                    busy_ui.set_clip_rect(pane_rect.intersect(ui.clip_rect()));
+                    behavior.busy_pane_ui(&mut busy_ui, tile_id);
+                }
+                if (response == UiResponse::DragStarted || handle_drag_started) && draggable {
+                    ui.ctx().set_dragged_id(tile_id.egui_id(self.id));
+                }
+                self.tiles.shown_panes.insert(tile_id);
+                if ui.input(|i| i.pointer.any_pressed()) && ui.rect_contains_pointer(rect) {
+                    self.focused = Some(tile_id);
+                }
+                let unfocused = behavior.dim_unfocused_panes()
+                    && self.focused.is_some_and(|focused| focused != tile_id);
+                if unfocused || self.matches_filter(tile_id) == Some(false) {
+                    let color = behavior.unfocused_pane_overlay_color(ui.visuals());
+                    ui.painter().rect_filled(rect, rounding, color);
+                }
+            }
+
+            if rounding != egui::Rounding::ZERO {
+                // The corners show the gap color of the parent, just like the gaps around the tile:
+                let color = behavior.gap_color(ui.style(), depth.saturating_sub(1));
+                paint_corner_masks(ui.painter(), rect, rounding, color);
+            }
 
             behavior.paint_on_top_of_tile(ui.painter(), ui.style(), tile_id, rect);
 
-            self.tiles.insert(tile_id, tile);
             drop_context.enabled = drop_context_was_enabled;
         });
     }
@@ -427,11 +1406,12 @@ impl<Pane> Tree<Pane> {
         behavior: &mut dyn Behavior<Pane>,
         drop_context: &DropContext,
         ui: &mut Ui,
-    ) {
+    ) -> Option<TileDrop> {
+        profile_scope!("Tree::preview_dragged_tile");
         let (Some(mouse_pos), Some(dragged_tile_id)) =
             (drop_context.mouse_pos, drop_context.dragged_tile_id)
         else {
-            return;
+            return None;
         };
 
         ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::Grabbing);
@@ -445,19 +1425,50 @@ impl<Pane> Tree<Pane> {
                 behavior.drag_ui(&self.tiles, ui, dragged_tile_id);
             });
 
-        if let Some(preview_rect) = drop_context.preview_rect {
-            let preview_rect = smooth_preview_rect(ui.ctx(), dragged_tile_id, preview_rect);
-
-            let parent_rect = drop_context
-                .best_insertion
-                .and_then(|insertion_point| self.tiles.rect(insertion_point.parent_id));
+        let parent_rect = drop_context
+            .best_insertion
+            .and_then(|insertion_point| self.tiles.rect(insertion_point.parent_id));
+
+        // Only show the part of the drop target that the behavior asks for:
+        let indicator_rect = match (drop_context.best_insertion, drop_context.preview_rect) {
+            (Some(insertion_point), Some(preview_rect)) => {
+                let operation = parent_rect.map_or(DropOperation::Tab, |parent_rect| {
+                    insertion_point.operation(preview_rect, parent_rect)
+                });
+                let line_width = 2.0 * behavior.drag_preview_stroke(ui.visuals()).width;
+                let indicator = behavior.drop_indicator(ui.visuals(), operation);
+                Some(indicator.rect(preview_rect, operation, line_width.at_least(2.0)))
+            }
+            (_, preview_rect) => preview_rect,
+        };
 
-            behavior.paint_drag_preview(ui.visuals(), ui.painter(), parent_rect, preview_rect);
+        let preview = smooth_preview(
+            ui.ctx(),
+            dragged_tile_id,
+            indicator_rect,
+            behavior.drag_preview_move_time(),
+            behavior.drag_preview_fade_time(ui.style()),
+        );
+        if let Some(SmoothPreview {
+            rect: preview_rect,
+            opacity,
+        }) = preview
+        {
+            let mut painter = ui.painter().clone();
+            painter.multiply_opacity(opacity);
+            behavior.paint_drag_preview(ui.visuals(), &painter, parent_rect, preview_rect);
 
-            if behavior.preview_dragged_panes() {
+            // Only preview the pane where it will actually land, not while fading out:
+            if behavior.preview_dragged_panes() && drop_context.preview_rect.is_some() {
                 // TODO(emilk): add support for previewing containers too.
                 if preview_rect.width() > 32.0 && preview_rect.height() > 32.0 {
-                    if let Some(Tile::Pane(pane)) = self.tiles.get_mut(dragged_tile_id) {
+                    if let Some(pane) = self.tiles.get_pane_mut(dragged_tile_id) {
+                        behavior.paint_pane_background(
+                            ui.painter(),
+                            preview_rect,
+                            dragged_tile_id,
+                            pane,
+                        );
                         // Intentionally ignore the response, since the user cannot possibly
                         // begin a drag on the preview pane.
                         let _ignored: UiResponse = behavior.pane_ui(
@@ -468,23 +1479,60 @@ impl<Pane> Tree<Pane> {
                     }
                 }
             }
+
+            if let (Some(insertion_point), Some(parent_rect)) =
+                (drop_context.best_insertion, parent_rect)
+            {
+                let operation = insertion_point.operation(preview_rect, parent_rect);
+                behavior.paint_drop_operation_icon(ui.visuals(), &painter, preview_rect, operation);
+            }
         }
 
+        let mut dropped = None;
         if ui.input(|i| i.pointer.any_released()) {
             if let Some(insertion_point) = drop_context.best_insertion {
-                behavior.on_edit(EditAction::TileDropped);
+                let insertion_point =
+                    self.fit_insertion(behavior, insertion_point, dragged_tile_id);
+                if behavior.animate_drops() {
+                    let now = ui.input(|i| i.time);
+                    self.tiles
+                        .start_transition(now, behavior.layout_animation_duration(ui.style()));
+                }
+                self.tiles.on_edit(behavior, EditAction::TileDropped);
+                let previous_parent = self.tiles.parent_of(dragged_tile_id);
                 self.move_tile(dragged_tile_id, insertion_point, false);
+                if previous_parent != Some(insertion_point.parent_id) {
+                    self.apply_preferred_share(
+                        behavior,
+                        insertion_point.parent_id,
+                        dragged_tile_id,
+                    );
+                }
+                self.move_selected_tabs_after(dragged_tile_id);
+                dropped = Some(TileDrop {
+                    tile_id: dragged_tile_id,
+                    parent_id: insertion_point.parent_id,
+                    kind: insertion_point.insertion.kind(),
+                    index: insertion_point.insertion.index(),
+                });
             }
-            clear_smooth_preview_rect(ui.ctx(), dragged_tile_id);
+            clear_smooth_preview(ui.ctx(), dragged_tile_id);
         }
+        dropped
     }
 
     /// Simplify and normalize the tree using the given options.
     ///
-    /// This is also called at the start of [`Self::ui`].
-    pub fn simplify(&mut self, options: &SimplificationOptions) {
+    /// This is also called at the start of [`Self::ui`] with [`Behavior::simplification_options`],
+    /// but you can call it yourself at any time with other options,
+    /// e.g. for a "Clean up layout" menu item, or right after deserializing a tree.
+    ///
+    /// Returns what was changed.
+    pub fn simplify(&mut self, options: &SimplificationOptions) -> SimplifyReport {
+        profile_scope!("Tree::simplify");
+        let mut report = SimplifyReport::default();
         if let Some(root) = self.root {
-            match self.tiles.simplify(options, root, None) {
+            match self.tiles.simplify(options, &mut report, root, None) {
                 SimplifyAction::Keep => {}
                 SimplifyAction::Remove => {
                     self.root = None;
@@ -500,22 +1548,94 @@ impl<Pane> Tree<Pane> {
                 }
             }
         }
+
+        for (tile_id, _) in &mut self.auto_hidden {
+            if let SimplifyAction::Replace(new_tile_id) =
+                self.tiles.simplify(options, &mut report, *tile_id, None)
+            {
+                *tile_id = new_tile_id;
+            }
+        }
+        self.auto_hidden
+            .retain(|&(tile_id, _)| self.tiles.get(tile_id).is_some());
+        report
     }
 
     /// Simplify all of the children of the given container tile recursively.
-    pub fn simplify_children_of_tile(&mut self, tile_id: TileId, options: &SimplificationOptions) {
+    ///
+    /// Returns what was changed.
+    pub fn simplify_children_of_tile(
+        &mut self,
+        tile_id: TileId,
+        options: &SimplificationOptions,
+    ) -> SimplifyReport {
+        let mut report = SimplifyReport::default();
         if let Some(Tile::Container(mut container)) = self.tiles.remove(tile_id) {
             let kind = container.kind();
-            container.simplify_children(|child| self.tiles.simplify(options, child, Some(kind)));
+            container.simplify_children(|child| {
+                self.tiles.simplify(options, &mut report, child, Some(kind))
+            });
             self.tiles.insert(tile_id, Tile::Container(container));
         }
+        report
+    }
+
+    /// Give panes a [`crate::Tabs`] container of their own, as decided by the `policy`
+    /// and [`Behavior::wrap_pane_in_tabs`].
+    ///
+    /// Use this to enforce a consistent structure after programmatic edits or imports.
+    /// Panes that already are in a [`crate::Tabs`] container are left alone.
+    ///
+    /// Just like with [`SimplificationOptions::all_panes_must_have_tabs`],
+    /// the new container takes the place (and [`TileId`]) of the pane it wraps,
+    /// and the pane moves to a new [`TileId`].
+    /// Returns the new [`TileId`]s of the wrapped panes.
+    pub fn normalize(
+        &mut self,
+        behavior: &dyn Behavior<Pane>,
+        policy: NormalizePolicy,
+    ) -> Vec<TileId> {
+        let Some(root) = self.root else {
+            return Vec::new();
+        };
+        let max_depth = match policy {
+            NormalizePolicy::AllPanes => usize::MAX,
+            NormalizePolicy::TopLevelPanes => 1,
+        };
+
+        let mut wrapped = Vec::new();
+        self.tiles.wrap_panes_in_tabs(
+            false,
+            root,
+            max_depth,
+            &mut |tile_id, pane| behavior.wrap_pane_in_tabs(tile_id, pane),
+            &mut wrapped,
+        );
+
+        // Keep the focus on the pane, not on its new container:
+        if let Some(&(_, new_id)) = wrapped
+            .iter()
+            .find(|&&(old_id, _)| Some(old_id) == self.focused)
+        {
+            self.focused = Some(new_id);
+        }
+        wrapped.into_iter().map(|(_, new_id)| new_id).collect()
     }
 
     /// Garbage-collect tiles that are no longer reachable from the root tile.
     ///
     /// This is also called by [`Self::ui`], so usually you don't need to call this yourself.
     pub fn gc(&mut self, behavior: &mut dyn Behavior<Pane>) {
-        self.tiles.gc_root(behavior, self.root);
+        profile_scope!("Tree::gc");
+        // Auto-hidden tiles are not reachable from the root, but should still be kept:
+        let roots = self
+            .root
+            .into_iter()
+            .chain(self.auto_hidden.iter().map(|&(tile_id, _)| tile_id));
+        self.tiles.gc_root(behavior, roots);
+        self.auto_hidden
+            .retain(|&(tile_id, _)| self.tiles.get(tile_id).is_some());
+        self.gc_tab_links();
     }
 
     /// Move a tile to a new container, at the specified insertion index.
@@ -580,8 +1700,18 @@ impl<Pane> Tree<Pane> {
             insertion_point.insertion
         );
 
-        if let Some((prev_parent_id, source_index)) = self.remove_tile_id_from_parent(moved_tile_id)
-        {
+        // Dragging an auto-hidden tile into the tree docks it again:
+        self.auto_hidden
+            .retain(|&(tile_id, _)| tile_id != moved_tile_id);
+
+        let previous_place = self.remove_tile_id_from_parent(moved_tile_id);
+        self.tiles.push_event(TreeEvent::TileMoved {
+            tile_id: moved_tile_id,
+            from: previous_place.map(|(parent_id, _)| parent_id),
+            to: insertion_point.parent_id,
+        });
+
+        if let Some((prev_parent_id, source_index)) = previous_place {
             // Check to see if we are moving a tile within the same container:
 
             if prev_parent_id == insertion_point.parent_id {
@@ -633,8 +1763,89 @@ impl<Pane> Tree<Pane> {
         self.tiles.insert_at(insertion_point, moved_tile_id);
     }
 
+    /// Does the given container have [`Behavior::max_children`] already,
+    /// not counting `tile_id` if it is one of them?
+    fn is_container_full(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        container_id: TileId,
+        tile_id: Option<TileId>,
+    ) -> bool {
+        let Some(Tile::Container(container)) = self.tiles.get(container_id) else {
+            return false;
+        };
+        let Some(max_children) = behavior.max_children(&self.tiles, container_id) else {
+            return false;
+        };
+        let is_child = tile_id.is_some_and(|tile_id| container.has_child(tile_id));
+        !is_child && max_children <= container.num_children()
+    }
+
+    /// Should drops into this full container be refused, see [`Behavior::container_overflow`]?
+    fn rejects_drop(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        container_id: TileId,
+        dragged_tile_id: Option<TileId>,
+    ) -> bool {
+        if !self.is_container_full(behavior, container_id, dragged_tile_id) {
+            return false;
+        }
+        self.tiles.get_container(container_id).map(Container::kind) == Some(ContainerKind::Tabs)
+            || behavior.container_overflow(&self.tiles, container_id) == ContainerOverflow::Reject
+    }
+
+    /// Wrap a tile dropped into a full container in [`crate::Tabs`]
+    /// with the child it was dropped next to, see [`Behavior::max_children`].
+    fn fit_insertion(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        insertion_point: InsertionPoint,
+        tile_id: TileId,
+    ) -> InsertionPoint {
+        let Some(container) = self.tiles.get_container(insertion_point.parent_id) else {
+            return insertion_point;
+        };
+        if container.kind() != insertion_point.insertion.kind()
+            || !self.is_container_full(behavior, insertion_point.parent_id, Some(tile_id))
+        {
+            return insertion_point; // Not adding a child to it
+        }
+        let children = container.children_vec();
+        let index = insertion_point
+            .insertion
+            .index()
+            .min(children.len().saturating_sub(1));
+        children.get(index).map_or(insertion_point, |&neighbor| {
+            InsertionPoint::new(neighbor, ContainerInsertion::Tabs(usize::MAX))
+        })
+    }
+
+    /// Give a pane that was dropped into a [`crate::Linear`] its [`Behavior::preferred_share`].
+    fn apply_preferred_share(
+        &mut self,
+        behavior: &dyn Behavior<Pane>,
+        parent_id: TileId,
+        tile_id: TileId,
+    ) {
+        let Some(Tile::Pane(pane)) = self.tiles.get(tile_id) else {
+            return;
+        };
+        let Some(fraction) = behavior.preferred_share(pane) else {
+            return;
+        };
+        if let Some(Tile::Container(Container::Linear(linear))) = self.tiles.get_mut(parent_id) {
+            linear.set_fraction(tile_id, fraction);
+        }
+    }
+
     /// Find the currently dragged tile, if any.
+    ///
+    /// This includes tiles dragged from outside the tree with a [`DraggedTile`] payload.
     pub fn dragged_id(&self, ctx: &egui::Context) -> Option<TileId> {
+        if self.is_locked() {
+            return None;
+        }
         for tile_id in self.tiles.tile_ids() {
             if self.is_root(tile_id) {
                 continue; // not allowed to drag root
@@ -645,6 +1856,7 @@ impl<Pane> Tree<Pane> {
                 // Abort drags on escape:
                 if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
                     ctx.stop_dragging();
+                    egui::DragAndDrop::clear_payload(ctx);
                     return None;
                 }
 
@@ -694,44 +1906,173 @@ impl<Pane> Tree<Pane> {
 
 // ----------------------------------------------------------------------------
 
-/// We store the preview rect in egui temp storage so that it is not serialized,
+/// Cover the corners of `rect` that are outside of the rounded shape with `color`,
+/// so that whatever was painted in the rect looks clipped to that shape.
+///
+/// egui can only clip to rectangles, so this is the next best thing.
+fn paint_corner_masks(
+    painter: &egui::Painter,
+    rect: Rect,
+    rounding: egui::Rounding,
+    color: Color32,
+) {
+    const SEGMENTS: u32 = 8;
+
+    let corners = [
+        (rect.left_top(), rounding.nw, vec2(1.0, 1.0)),
+        (rect.right_top(), rounding.ne, vec2(-1.0, 1.0)),
+        (rect.left_bottom(), rounding.sw, vec2(1.0, -1.0)),
+        (rect.right_bottom(), rounding.se, vec2(-1.0, -1.0)),
+    ];
+
+    let mut mesh = egui::Mesh::default();
+    for (corner, radius, inward) in corners {
+        if radius <= 0.0 {
+            continue;
+        }
+        let center = corner + radius * inward;
+
+        // A fan from the corner to the arc, from the point on one edge to the point on the other:
+        let corner_index = mesh.vertices.len() as u32;
+        mesh.colored_vertex(corner, color);
+        for i in 0..=SEGMENTS {
+            let angle = std::f32::consts::FRAC_PI_2 * i as f32 / SEGMENTS as f32;
+            let offset = vec2(inward.x * angle.cos(), inward.y * angle.sin());
+            mesh.colored_vertex(center - radius * offset, color);
+            if 0 < i {
+                mesh.add_triangle(corner_index, corner_index + i, corner_index + i + 1);
+            }
+        }
+    }
+    painter.add(mesh);
+}
+
+/// The drop preview as it is shown, lagging behind the actual drop target.
+#[derive(Clone, Copy, Debug)]
+struct SmoothPreview {
+    rect: Rect,
+    opacity: f32,
+}
+
+/// We store the preview in egui temp storage so that it is not serialized,
 /// and so that a user could re-create the [`Tree`] each frame and still get smooth previews.
-fn smooth_preview_rect_id(dragged_tile_id: TileId) -> egui::Id {
+fn smooth_preview_id(dragged_tile_id: TileId) -> egui::Id {
     egui::Id::new((dragged_tile_id, "smoothed_preview_rect"))
 }
 
-fn clear_smooth_preview_rect(ctx: &egui::Context, dragged_tile_id: TileId) {
-    let data_id = smooth_preview_rect_id(dragged_tile_id);
-    ctx.data_mut(|data| data.remove::<Rect>(data_id));
+fn clear_smooth_preview(ctx: &egui::Context, dragged_tile_id: TileId) {
+    let data_id = smooth_preview_id(dragged_tile_id);
+    ctx.data_mut(|data| data.remove::<SmoothPreview>(data_id));
 }
 
-/// Take the preview rectangle and smooth it over time.
-fn smooth_preview_rect(ctx: &egui::Context, dragged_tile_id: TileId, new_rect: Rect) -> Rect {
-    let data_id = smooth_preview_rect_id(dragged_tile_id);
+/// Move the shown preview towards the current drop target over time,
+/// fading it in while there is a target, and out when there is none.
+///
+/// Returns `None` when there is nothing to show.
+fn smooth_preview(
+    ctx: &egui::Context,
+    dragged_tile_id: TileId,
+    target: Option<Rect>,
+    move_time: f32,
+    fade_time: f32,
+) -> Option<SmoothPreview> {
+    let data_id = smooth_preview_id(dragged_tile_id);
 
     let dt = ctx.input(|input| input.stable_dt).at_most(0.1);
 
     let mut requires_repaint = false;
 
-    let smoothed = ctx.data_mut(|data| {
-        let smoothed: &mut Rect = data.get_temp_mut_or(data_id, new_rect);
+    let preview = ctx.data_mut(|data| {
+        let mut preview = data.get_temp::<SmoothPreview>(data_id).or_else(|| {
+            // Appear where the first target is, and fade in from there:
+            target.map(|rect| SmoothPreview { rect, opacity: 0.0 })
+        })?;
 
-        let t = egui::emath::exponential_smooth_factor(0.9, 0.05, dt);
+        if let Some(target) = target {
+            let t = if 0.0 < move_time {
+                egui::emath::exponential_smooth_factor(0.9, move_time, dt)
+            } else {
+                1.0
+            };
+
+            preview.rect = preview.rect.lerp_towards(&target, t);
 
-        *smoothed = smoothed.lerp_towards(&new_rect, t);
+            let diff =
+                preview.rect.min.distance(target.min) + preview.rect.max.distance(target.max);
+            if diff < 0.5 {
+                preview.rect = target;
+            } else {
+                requires_repaint = true;
+            }
+        }
 
-        let diff = smoothed.min.distance(new_rect.min) + smoothed.max.distance(new_rect.max);
-        if diff < 0.5 {
-            *smoothed = new_rect;
+        let target_opacity = if target.is_some() { 1.0 } else { 0.0 };
+        let step = if 0.0 < fade_time { dt / fade_time } else { 1.0 };
+        preview.opacity = if preview.opacity < target_opacity {
+            (preview.opacity + step).at_most(target_opacity)
         } else {
+            (preview.opacity - step).at_least(target_opacity)
+        };
+        if preview.opacity != target_opacity {
             requires_repaint = true;
         }
-        *smoothed
+
+        data.insert_temp(data_id, preview);
+        Some(preview)
     });
 
     if requires_repaint {
         ctx.request_repaint();
     }
 
-    smoothed
+    preview.filter(|preview| 0.0 < preview.opacity)
+}
+
+impl<Pane> Tree<Pane>
+where
+    Self: egui::util::id_type_map::SerializableAny,
+{
+    /// Show a tree that is persisted in [`egui::Memory`], keyed by `id`.
+    ///
+    /// On first use the tree is loaded from memory, or created with `default_tree` if there is none.
+    /// Any changes are written back to memory, so if egui persistence is enabled
+    /// (e.g. via `eframe`), the layout survives restarts without any further wiring.
+    ///
+    /// The tree is moved out of memory while it is shown, and back in afterwards,
+    /// so it is not cloned.
+    pub fn show_persisted(
+        ui: &mut Ui,
+        id: impl Into<egui::Id>,
+        default_tree: impl FnOnce() -> Self,
+        behavior: &mut dyn Behavior<Pane>,
+    ) {
+        let id = id.into();
+        let mut tree = ui.data_mut(|data| {
+            std::mem::replace(
+                data.get_persisted_mut_or_insert_with(id, default_tree),
+                Self::empty(id),
+            )
+        });
+        tree.ui(behavior, ui);
+        ui.data_mut(|data| data.insert_persisted(id, tree));
+    }
+}
+
+impl Tree<()> {
+    /// Fill in the panes of a layout created with [`Tree::split_panes`].
+    ///
+    /// `pane` is called with the [`TileId`] of each pane slot.
+    /// Slots for which it returns `None` are removed from the layout.
+    ///
+    /// ```
+    /// use egui_tiles::Tree;
+    ///
+    /// let tree = Tree::new_tabs("my_tree", vec!["hello".to_owned(), "world".to_owned()]);
+    /// let (layout, mut panes) = tree.split_panes(|_, pane| pane.clone());
+    /// let restored = layout.join_panes(|tile_id| panes.remove(&tile_id));
+    /// assert_eq!(tree, restored);
+    /// ```
+    pub fn join_panes<Pane>(self, mut pane: impl FnMut(TileId) -> Option<Pane>) -> Tree<Pane> {
+        self.filter_map_panes(|tile_id, ()| pane(tile_id))
+    }
 }
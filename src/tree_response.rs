@@ -0,0 +1,50 @@
+use crate::{ContainerKind, TileId};
+
+/// What happened in a tree during one frame, returned by [`crate::Tree::ui`].
+///
+/// For a full log of structural changes, see [`crate::Tree::enable_events`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeResponse {
+    /// The docked pane under the mouse pointer, if any.
+    ///
+    /// Panes in auto-hide flyouts and modals are not reported.
+    pub hovered_pane: Option<TileId>,
+
+    /// The pane with keyboard focus at the end of the frame, see [`crate::Tree::focused_pane`].
+    pub focused_pane: Option<TileId>,
+
+    /// The container whose children the user resized this frame, if any.
+    pub resized: Option<TileId>,
+
+    /// The tile the user dropped this frame, if any.
+    pub dropped: Option<TileDrop>,
+
+    /// Were tiles added, removed, or moved since the previous frame?
+    ///
+    /// This includes changes made outside of [`crate::Tree::ui`], e.g. with [`crate::Tree::execute`].
+    pub structure_changed: bool,
+
+    /// Did the tree get less space than its [`crate::Tree::min_size`]?
+    ///
+    /// If so, some tiles were squished below their minimum size.
+    pub over_constrained: bool,
+}
+
+/// A tile dropped by the user, see [`TreeResponse::dropped`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileDrop {
+    /// The tile that was dragged.
+    pub tile_id: TileId,
+
+    /// The container it was dropped into.
+    ///
+    /// If it was dropped next to a pane, this is the new container that took the place
+    /// (and [`TileId`]) of that pane.
+    pub parent_id: TileId,
+
+    /// What kind of container `parent_id` is.
+    pub kind: ContainerKind,
+
+    /// Where among the children of `parent_id` the tile was inserted.
+    pub index: usize,
+}
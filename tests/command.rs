@@ -0,0 +1,371 @@
+use egui::{pos2, Rect};
+use egui_tiles::{
+    Behavior, Command, Container, LinearDir, NextTo, Tile, TileId, Tiles, Tree, UiResponse,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Pane(usize);
+
+struct TestBehavior;
+
+impl Behavior<Pane> for TestBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, pane: &Pane) -> egui::WidgetText {
+        format!("Pane {}", pane.0).into()
+    }
+
+    fn clone_pane(&mut self, pane: &Pane) -> Option<Pane> {
+        Some(pane.clone())
+    }
+}
+
+fn layout(tree: &mut Tree<Pane>) {
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(300.0, 200.0));
+    tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+}
+
+#[test]
+fn test_parse_command() {
+    for command in [
+        Command::FocusLeft,
+        Command::MoveDown,
+        Command::SplitVertical,
+        Command::CloseFocused,
+        Command::ResizeGrow(0.1),
+    ] {
+        assert_eq!(Command::parse(&command.to_string()), Some(command));
+    }
+    assert_eq!(
+        Command::parse("  Resize   SHRINK 25% "),
+        Some(Command::ResizeShrink(0.25))
+    );
+    assert_eq!(Command::parse("resize grow"), None);
+    assert_eq!(Command::parse("jump left"), None);
+}
+
+#[test]
+fn test_focus_and_move() {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane(0));
+    let b = tiles.insert_pane(Pane(1));
+    let c = tiles.insert_pane(Pane(2));
+    let root = tiles.insert_horizontal_tile(vec![a, b, c]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    layout(&mut tree);
+
+    assert!(tree.execute(&mut TestBehavior, Command::FocusRight));
+    assert_eq!(tree.focused_pane(), Some(a), "starts somewhere");
+    assert!(tree.execute(&mut TestBehavior, Command::FocusRight));
+    assert_eq!(tree.focused_pane(), Some(b));
+    assert!(!tree.execute(&mut TestBehavior, Command::FocusUp));
+
+    assert!(tree.execute(&mut TestBehavior, Command::MoveLeft));
+    let Some(Tile::Container(container)) = tree.tiles.get(root) else {
+        panic!("root should be a container");
+    };
+    assert_eq!(container.children_vec(), vec![b, a, c]);
+    assert_eq!(tree.focused_pane(), Some(b));
+}
+
+#[test]
+fn test_split_close_and_resize() {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane(0));
+    let b = tiles.insert_pane(Pane(1));
+    let root = tiles.insert_horizontal_tile(vec![a, b]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    layout(&mut tree);
+    tree.focus_pane(a);
+
+    assert!(tree.execute(&mut TestBehavior, Command::SplitVertical));
+    let copy = tree.focused_pane().expect("the copy gets focus");
+    assert_eq!(tree.tiles.get_pane(&copy), Some(&Pane(0)));
+    let column = tree.tiles.parent_of(a).expect("wrapped");
+    assert_eq!(tree.tiles.parent_of(copy), Some(column));
+    assert!(matches!(
+        tree.tiles.get(column),
+        Some(Tile::Container(Container::Linear(linear))) if linear.dir == LinearDir::Vertical
+    ));
+    layout(&mut tree);
+
+    assert!(tree.execute(&mut TestBehavior, Command::ResizeGrow(0.1)));
+    layout(&mut tree);
+    assert!(
+        tree.tiles.rect(copy).expect("shown").height()
+            > tree.tiles.rect(a).expect("shown").height()
+    );
+
+    assert!(tree.execute(&mut TestBehavior, Command::CloseFocused));
+    assert!(tree.tiles.get(copy).is_none());
+    assert_eq!(tree.focused_pane(), Some(a));
+}
+
+#[test]
+fn test_insert_pane_after() {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane(0));
+    let b = tiles.insert_pane(Pane(1));
+    let root = tiles.insert_horizontal_tile(vec![a, b]);
+    let mut tree = Tree::new("tree", root, tiles);
+
+    // Same kind of parent, so it goes right next to it:
+    let c = tree
+        .insert_pane_after(a, Pane(2), NextTo::Right)
+        .expect("a is in the tree");
+    let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(root) else {
+        panic!("root should be a linear container");
+    };
+    assert_eq!(linear.children, vec![a, c, b]);
+
+    // Otherwise `b` gets wrapped, keeping its id:
+    let d = tree
+        .insert_pane_after(b, Pane(3), NextTo::NextTab)
+        .expect("b is in the tree");
+    let tabs_id = tree.tiles.parent_of(b).expect("b has a parent");
+    let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get(tabs_id) else {
+        panic!("b should be in tabs");
+    };
+    assert_eq!(tabs.children, vec![b, d]);
+    assert_eq!(tabs.active, Some(d));
+    assert_eq!(tree.tiles.parent_of(tabs_id), Some(root));
+    assert_eq!(tree.tiles.get_pane(&b), Some(&Pane(1)));
+}
+
+/// Pane 1 has unsaved changes, and pane 2 can't be closed at all.
+struct GatedBehavior;
+
+impl Behavior<Pane> for GatedBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, pane: &Pane) -> egui::WidgetText {
+        format!("Pane {}", pane.0).into()
+    }
+
+    fn is_tab_closable(&self, tiles: &Tiles<Pane>, tile_id: TileId) -> bool {
+        tiles.get_pane(&tile_id) != Some(&Pane(2))
+    }
+
+    fn on_tab_close(&mut self, tiles: &mut Tiles<Pane>, tile_id: TileId) -> bool {
+        tiles.get_pane(&tile_id) != Some(&Pane(1))
+    }
+}
+
+#[test]
+fn test_close_all_tabs() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..4).map(|i| tiles.insert_pane(Pane(i))).collect();
+    let root = tiles.insert_tab_tile(panes.clone());
+    let mut tree = Tree::new("tree", root, tiles);
+
+    let skipped = tree.close_all_tabs(&mut GatedBehavior, root);
+    assert_eq!(skipped, vec![panes[1], panes[2]]);
+    assert!(tree.tiles.get(panes[0]).is_none());
+    assert!(tree.tiles.get(panes[3]).is_none());
+    let Some(Tile::Container(container)) = tree.tiles.get(root) else {
+        panic!("root should be a container");
+    };
+    assert_eq!(container.children_vec(), skipped);
+}
+
+#[test]
+fn test_close_selected_tabs() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..4).map(|i| tiles.insert_pane(Pane(i))).collect();
+    let root = tiles.insert_tab_tile(panes.clone());
+    let mut tree = Tree::new("tree", root, tiles);
+
+    tree.set_selected_tabs(root, &panes[..3]);
+    assert_eq!(tree.close_selected_tabs(&mut GatedBehavior), 1);
+    assert!(tree.tiles.get(panes[0]).is_none());
+    assert_eq!(
+        tree.tiles.get_pane(&panes[1]),
+        Some(&Pane(1)),
+        "has unsaved changes"
+    );
+    assert_eq!(
+        tree.tiles.get_pane(&panes[2]),
+        Some(&Pane(2)),
+        "not closable"
+    );
+    assert_eq!(
+        tree.tiles.get_pane(&panes[3]),
+        Some(&Pane(3)),
+        "not selected"
+    );
+    assert_eq!(tree.selected_tabs(), None);
+}
+
+#[test]
+fn test_transpose_and_rotate() {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane(0));
+    let b = tiles.insert_pane(Pane(1));
+    let c = tiles.insert_pane(Pane(2));
+    let inner = tiles.insert_vertical_tile(vec![b, c]);
+    let root = tiles.insert_horizontal_tile(vec![a, inner]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let dir = |tree: &Tree<Pane>, tile_id: TileId| match tree.tiles.get_container(tile_id) {
+        Some(Container::Linear(linear)) => (linear.dir, linear.children.clone()),
+        _ => panic!("expected a Linear"),
+    };
+
+    tree.transpose(root, false);
+    assert_eq!(dir(&tree, root), (LinearDir::Vertical, vec![a, inner]));
+    assert_eq!(dir(&tree, inner), (LinearDir::Vertical, vec![b, c]));
+
+    tree.transpose(root, true);
+    assert_eq!(dir(&tree, root), (LinearDir::Horizontal, vec![a, inner]));
+    assert_eq!(dir(&tree, inner), (LinearDir::Horizontal, vec![b, c]));
+
+    // What was on the left ends up on top, what was on top ends up on the right:
+    tree.transpose(inner, false);
+    tree.rotate_clockwise(root);
+    assert_eq!(dir(&tree, root), (LinearDir::Vertical, vec![a, inner]));
+    assert_eq!(dir(&tree, inner), (LinearDir::Horizontal, vec![c, b]));
+
+    layout(&mut tree);
+    let rect = |tile_id: TileId| tree.tiles.rect(tile_id).expect("tile shown");
+    assert!(rect(a).bottom() <= rect(inner).top());
+    assert!(rect(c).right() <= rect(b).left());
+}
+
+#[test]
+fn test_reset_layout() {
+    let mut template_tiles = Tiles::default();
+    let template_panes: Vec<TileId> = (0..3).map(|i| template_tiles.insert_pane(i)).collect();
+    let tabs = template_tiles.insert_tab_tile(vec![template_panes[0], template_panes[1]]);
+    let root = template_tiles.insert_horizontal_tile(vec![tabs, template_panes[2]]);
+    let template = Tree::new("template", root, template_tiles);
+
+    // Rearrange the panes, resize them, and switch tabs:
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..3).map(|i| tiles.insert_pane(Pane(i))).collect();
+    let mut current_tabs = Container::new_tabs(panes.clone());
+    if let Container::Tabs(tabs) = &mut current_tabs {
+        tabs.set_active(panes[2]);
+    }
+    let current_tabs = tiles.insert_container(current_tabs);
+    let mut current_root = Container::new_vertical(vec![current_tabs]);
+    if let Container::Linear(linear) = &mut current_root {
+        linear.shares.set_share(current_tabs, 5.0);
+    }
+    let current_root = tiles.insert_container(current_root);
+    let mut tree = Tree::new("my_tree", current_root, tiles);
+
+    let mut num_created = 0;
+    tree.reset_layout(
+        &template,
+        |pane| pane.0,
+        |&key| {
+            num_created += 1;
+            Pane(key)
+        },
+    );
+    assert_eq!(num_created, 0, "all panes should be kept");
+
+    let mut kept: Vec<usize> = tree
+        .tiles
+        .tiles()
+        .filter_map(|tile| match tile {
+            Tile::Pane(pane) => Some(pane.0),
+            Tile::Container(_) => None,
+        })
+        .collect();
+    kept.sort_unstable();
+    assert_eq!(kept, vec![0, 1, 2]);
+
+    assert_eq!(tree.root, template.root);
+    assert_eq!(
+        tree.tiles.get_container(root),
+        template.tiles.get_container(root)
+    );
+    let Some(Container::Linear(linear)) = tree.tiles.get_container(root) else {
+        panic!("root should be a Linear");
+    };
+    assert_eq!(linear.shares[tabs], 1.0);
+    assert_eq!(linear.shares[template_panes[2]], 1.0);
+    let Some(Container::Tabs(tabs)) = tree.tiles.get_container(tabs) else {
+        panic!("expected a Tabs");
+    };
+    assert_eq!(tabs.active, Some(template_panes[0]));
+}
+
+#[test]
+fn test_duplicate() {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane(0));
+    let b = tiles.insert_pane(Pane(1));
+    let c = tiles.insert_pane(Pane(2));
+    let tabs = tiles.insert_tab_tile(vec![b, c]);
+    let root = tiles.insert_horizontal_tile(vec![a, tabs]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    if let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get_mut(tabs) {
+        tabs.set_active(c);
+    }
+    let children = |tree: &Tree<Pane>, tile_id| {
+        tree.tiles
+            .get_container(tile_id)
+            .expect("a container")
+            .children_vec()
+    };
+
+    // A pane is copied right after it:
+    let a_copy = tree.duplicate(&mut TestBehavior, a).expect("copied");
+    assert_ne!(a_copy, a);
+    assert_eq!(tree.tiles.get_pane(&a_copy), Some(&Pane(0)));
+    assert_eq!(children(&tree, root), vec![a, a_copy, tabs]);
+
+    // A container is copied with everything in it, under new ids:
+    let tabs_copy = tree.duplicate(&mut TestBehavior, tabs).expect("copied");
+    assert_eq!(children(&tree, root), vec![a, a_copy, tabs, tabs_copy]);
+    assert_eq!(
+        children(&tree, tabs),
+        vec![b, c],
+        "the original is untouched"
+    );
+
+    let copied = children(&tree, tabs_copy);
+    assert_eq!(copied.len(), 2);
+    for &copy in &copied {
+        assert!(![a, b, c, tabs, a_copy, tabs_copy].contains(&copy));
+        assert_eq!(tree.tiles.parent_of(copy), Some(tabs_copy));
+    }
+    let panes: Vec<Option<&Pane>> = copied.iter().map(|id| tree.tiles.get_pane(id)).collect();
+    assert_eq!(panes, vec![Some(&Pane(1)), Some(&Pane(2))]);
+    let Some(Container::Tabs(copy)) = tree.tiles.get_container(tabs_copy) else {
+        panic!("expected a Tabs");
+    };
+    assert_eq!(copy.active, Some(copied[1]), "the same tab is active");
+
+    // Nothing is copied unless every pane can be:
+    struct PickyBehavior;
+
+    impl Behavior<Pane> for PickyBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &Pane) -> egui::WidgetText {
+            format!("Pane {}", pane.0).into()
+        }
+
+        fn clone_pane(&mut self, pane: &Pane) -> Option<Pane> {
+            (pane.0 != 2).then(|| pane.clone())
+        }
+    }
+
+    let num_tiles = tree.tiles.len();
+    assert_eq!(tree.duplicate(&mut PickyBehavior, tabs), None);
+    assert_eq!(tree.tiles.len(), num_tiles);
+}
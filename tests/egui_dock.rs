@@ -0,0 +1,57 @@
+#![cfg(feature = "egui_dock")]
+
+use egui_dock::{DockState, NodeIndex, TabIndex};
+use egui_tiles::{Container, LinearDir, Tile, TileId, Tree};
+
+fn tab_names(tree: &Tree<String>, tile_id: TileId) -> (Vec<String>, Option<String>) {
+    let Some(Container::Tabs(tabs)) = tree.tiles.get_container(tile_id) else {
+        panic!("expected a Tabs");
+    };
+    let name = |tile_id: &TileId| match tree.tiles.get(*tile_id) {
+        Some(Tile::Pane(name)) => name.clone(),
+        _ => panic!("expected a pane"),
+    };
+    (
+        tabs.children.iter().map(name).collect(),
+        tabs.active.as_ref().map(name),
+    )
+}
+
+#[test]
+fn test_from_egui_dock() {
+    let mut dock_state = DockState::new(vec!["a", "b"]);
+    let surface = dock_state.main_surface_mut();
+    let [left, right] = surface.split_right(NodeIndex::root(), 0.25, vec!["c"]);
+    surface.split_below(right, 0.5, vec!["d"]);
+    surface.set_active_tab(left, TabIndex(1));
+
+    let tree = Tree::from_egui_dock("my_tree", &dock_state, |tab| (*tab).to_owned());
+
+    let Some(root) = tree.root else {
+        panic!("the tree should not be empty");
+    };
+    let Some(Container::Linear(root_linear)) = tree.tiles.get_container(root) else {
+        panic!("root should be a Linear");
+    };
+    assert_eq!(root_linear.dir, LinearDir::Horizontal);
+    let [tabs, right] = root_linear.children[..] else {
+        panic!("root should have two children");
+    };
+    assert_eq!(root_linear.shares[tabs], 0.5);
+    assert_eq!(root_linear.shares[right], 1.5);
+    assert_eq!(
+        tab_names(&tree, tabs),
+        (vec!["a".to_owned(), "b".to_owned()], Some("b".to_owned()))
+    );
+
+    let Some(Container::Linear(right_linear)) = tree.tiles.get_container(right) else {
+        panic!("the right side should be a Linear");
+    };
+    assert_eq!(right_linear.dir, LinearDir::Vertical);
+    let names: Vec<_> = right_linear
+        .children
+        .iter()
+        .map(|&child| tab_names(&tree, child).0)
+        .collect();
+    assert_eq!(names, vec![vec!["c".to_owned()], vec!["d".to_owned()]]);
+}
@@ -0,0 +1,1614 @@
+#![cfg(feature = "test_support")]
+
+use egui_tiles::{
+    test_support::Harness, Behavior, Container, DraggedTile, EditAction, EditMode, EditPermissions,
+    LinearOverflow, TabSwitchAnimation, Tile, TileId, Tiles, Tree, UiResponse,
+};
+
+struct Pane;
+
+struct TestBehavior;
+
+impl Behavior<Pane> for TestBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+        "Pane".into()
+    }
+}
+
+#[test]
+fn test_click_tab() {
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    let root = tiles.insert_tab_tile(vec![first, second]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(tree.active_tiles().contains(&first));
+
+    assert!(harness.click_tab(&mut tree, &mut TestBehavior, second));
+    assert!(tree.active_tiles().contains(&second));
+    assert!(!tree.active_tiles().contains(&first));
+}
+
+#[test]
+fn test_linked_tabs() {
+    let mut tiles = Tiles::default();
+    let [a1, a2, b1, b2, c1, c2] = std::array::from_fn(|_| tiles.insert_pane(Pane));
+    let left = tiles.insert_tab_tile(vec![a1, a2]);
+    let middle = tiles.insert_tab_tile(vec![b1, b2]);
+    let right = tiles.insert_tab_tile(vec![c1, c2]);
+    let root = tiles.insert_horizontal_tile(vec![left, middle, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    tree.link_tabs([a2, b2, c2]);
+
+    let mut harness = Harness::new(egui::vec2(600.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(tree.active_tiles().contains(&b1));
+    assert!(tree.active_tiles().contains(&c1));
+
+    // Activating one tab activates its partners:
+    assert!(harness.click_tab(&mut tree, &mut TestBehavior, a2));
+    let active = tree.active_tiles();
+    assert!(active.contains(&a2));
+    assert!(active.contains(&b2));
+    assert!(active.contains(&c2));
+
+    // The remaining tabs stay linked after one of them is removed:
+    tree.remove_recursively(c2);
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(tree.linked_tabs(a2).collect::<Vec<_>>(), vec![b2]);
+
+    assert!(harness.click_tab(&mut tree, &mut TestBehavior, a1));
+    assert!(
+        tree.active_tiles().contains(&b2),
+        "unlinked tabs don't affect others"
+    );
+    assert!(harness.click_tab(&mut tree, &mut TestBehavior, b1));
+    assert!(harness.click_tab(&mut tree, &mut TestBehavior, b2));
+    assert!(tree.active_tiles().contains(&a2));
+
+    // A link to a single tab is no link at all:
+    tree.remove_recursively(b2);
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(tree.linked_tabs(a2).count(), 0);
+}
+
+#[test]
+fn test_focus_on_click() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(tree.focused_pane(), None);
+
+    let right_pos = tree.tiles.rect(right).expect("right shown").center();
+    harness.click(&mut tree, &mut TestBehavior, right_pos);
+    assert_eq!(tree.focused_pane(), Some(right));
+    assert_eq!(tree.focused_group(), Some(root));
+
+    let left_pos = tree.tiles.rect(left).expect("left shown").center();
+    harness.click(&mut tree, &mut TestBehavior, left_pos);
+    assert_eq!(tree.focused_pane(), Some(left));
+
+    // The focus goes away with the pane:
+    tree.remove_recursively(left);
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(tree.focused_pane(), None);
+    assert_eq!(tree.focused_group(), None);
+}
+
+#[test]
+fn test_spotlight() {
+    struct SpotlightBehavior {
+        spotlight: bool,
+    }
+
+    impl Behavior<Pane> for SpotlightBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn dim_unfocused_panes(&self) -> bool {
+            self.spotlight
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..3).map(|_| tiles.insert_pane(Pane)).collect();
+    let root = tiles.insert_horizontal_tile(panes.clone());
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut behavior = SpotlightBehavior { spotlight: true };
+    let mut harness = Harness::new(egui::vec2(600.0, 300.0));
+
+    let dimmed = |harness: &Harness, tree: &Tree<Pane>, behavior: &SpotlightBehavior| {
+        let color = behavior.unfocused_pane_overlay_color(&harness.ctx().style().visuals);
+        panes
+            .iter()
+            .copied()
+            .filter(|&pane| {
+                let rect = tree.tiles.rect(pane).expect("shown");
+                harness.shapes().iter().any(|clipped| {
+                    matches!(&clipped.shape, egui::Shape::Rect(shape)
+                        if shape.rect == rect && shape.fill == color)
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Nothing is dimmed while no pane has focus:
+    harness.run(&mut tree, &mut behavior);
+    assert_eq!(dimmed(&harness, &tree, &behavior), vec![]);
+
+    // Everything but the focused pane is:
+    assert!(tree.focus_pane(panes[1]));
+    harness.run(&mut tree, &mut behavior);
+    assert_eq!(dimmed(&harness, &tree, &behavior), vec![panes[0], panes[2]]);
+
+    // …but only while spotlight mode is on:
+    behavior.spotlight = false;
+    harness.run(&mut tree, &mut behavior);
+    assert_eq!(dimmed(&harness, &tree, &behavior), vec![]);
+}
+
+/// Did a clickable widget cover this position last frame?
+fn is_clickable_at(harness: &Harness, pos: egui::Pos2) -> bool {
+    harness.ctx().viewport(|viewport| {
+        viewport.prev_pass.widgets.layers().any(|(_, widgets)| {
+            widgets
+                .iter()
+                .any(|widget| widget.sense.click && widget.interact_rect.contains(pos))
+        })
+    })
+}
+
+#[test]
+fn test_close_all_tabs_button() {
+    struct CloseAllBehavior {
+        closable: bool,
+    }
+
+    impl Behavior<Pane> for CloseAllBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn is_tab_closable(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+            self.closable
+        }
+
+        fn close_all_tabs_button(&self, _tiles: &Tiles<Pane>, _tabs_id: TileId) -> bool {
+            true
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..3).map(|_| tiles.insert_pane(Pane)).collect();
+    let root = tiles.insert_tab_tile(panes.clone());
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut behavior = CloseAllBehavior { closable: false };
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+
+    // The button is the first thing at the right end of the tab bar:
+    let rect = tree.tiles.rect(root).expect("root shown");
+    let tab_bar_height = behavior.tab_bar_height(&harness.ctx().style());
+    let button_pos = egui::pos2(rect.right() - 6.0, rect.top() + 0.5 * tab_bar_height);
+
+    // No button if there is nothing it could close:
+    assert!(!is_clickable_at(&harness, button_pos));
+
+    behavior.closable = true;
+    harness.run(&mut tree, &mut behavior);
+    assert!(is_clickable_at(&harness, button_pos));
+    harness.click(&mut tree, &mut behavior, button_pos);
+
+    for pane in panes {
+        assert!(tree.tiles.get(pane).is_none(), "{pane:?} should be closed");
+    }
+}
+
+#[test]
+fn test_close_all_tabs_button_locked() {
+    struct CloseAllBehavior {
+        tabs_id: TileId,
+        may_close_tabs: bool,
+    }
+
+    impl Behavior<Pane> for CloseAllBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn is_tab_closable(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+            true
+        }
+
+        fn close_all_tabs_button(&self, _tiles: &Tiles<Pane>, _tabs_id: TileId) -> bool {
+            true
+        }
+
+        fn edit_permissions(&self, _tiles: &Tiles<Pane>, tile_id: TileId) -> EditPermissions {
+            EditPermissions {
+                close: tile_id != self.tabs_id || self.may_close_tabs,
+                ..EditPermissions::ALL
+            }
+        }
+    }
+
+    // Either the whole tree is locked, or just the tabs container may not be closed:
+    for (edit_mode, may_close_tabs) in [(EditMode::Locked, true), (EditMode::Normal, false)] {
+        let mut tiles = Tiles::default();
+        let panes: Vec<TileId> = (0..3).map(|_| tiles.insert_pane(Pane)).collect();
+        let root = tiles.insert_tab_tile(panes.clone());
+        let mut tree = Tree::new("my_tree", root, tiles);
+        tree.set_edit_mode(edit_mode);
+        let mut behavior = CloseAllBehavior {
+            tabs_id: root,
+            may_close_tabs,
+        };
+
+        let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+        harness.run(&mut tree, &mut behavior);
+
+        // Where the button would be:
+        let rect = tree.tiles.rect(root).expect("root shown");
+        let tab_bar_height = behavior.tab_bar_height(&harness.ctx().style());
+        let button_pos = egui::pos2(rect.right() - 6.0, rect.top() + 0.5 * tab_bar_height);
+        assert!(
+            !is_clickable_at(&harness, button_pos),
+            "{edit_mode:?}: there should be no close-all button"
+        );
+
+        harness.click(&mut tree, &mut behavior, button_pos);
+        for &pane in &panes {
+            assert!(
+                tree.tiles.get(pane).is_some(),
+                "{edit_mode:?}: {pane:?} should still be open"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_edit_permissions() {
+    struct LockingBehavior {
+        locked: Vec<TileId>,
+    }
+
+    impl Behavior<Pane> for LockingBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn is_tab_closable(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+            true
+        }
+
+        fn edit_permissions(&self, _tiles: &Tiles<Pane>, tile_id: TileId) -> EditPermissions {
+            if self.locked.contains(&tile_id) {
+                EditPermissions {
+                    drag: false,
+                    resize: false,
+                    close: false,
+                    ..EditPermissions::ALL
+                }
+            } else {
+                EditPermissions::ALL
+            }
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane);
+    let b = tiles.insert_pane(Pane);
+    let c = tiles.insert_pane(Pane);
+    let d = tiles.insert_pane(Pane);
+    let tabs = tiles.insert_tab_tile(vec![a, b, d]);
+    let root = tiles.insert_horizontal_tile(vec![tabs, c]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut behavior = LockingBehavior { locked: vec![a, c] };
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+
+    // Resizing needs both neighbors to agree:
+    let width = |tree: &Tree<Pane>| tree.tiles.rect(tabs).expect("tabs shown").width();
+    let width_before = width(&tree);
+    let divider = egui::vec2(50.0, 0.0);
+    assert!(harness.drag_divider(&mut tree, &mut behavior, [tabs, c], divider));
+    assert_eq!(width(&tree), width_before, "c may not be resized");
+
+    behavior.locked.retain(|&tile_id| tile_id != c);
+    assert!(harness.drag_divider(&mut tree, &mut behavior, [tabs, c], divider));
+    assert!(width_before + 25.0 < width(&tree));
+
+    let tab_rect = |harness: &Harness, tree: &Tree<Pane>, tile_id: TileId| {
+        harness
+            .ctx()
+            .read_response(tile_id.egui_id(tree.id()))
+            .expect("tab shown")
+            .rect
+    };
+
+    // Where the close button of a tab is, if it has one:
+    let margin = behavior.tab_title_spacing(&harness.ctx().style().visuals)
+        + 0.5 * behavior.close_button_outer_size();
+    let close_button_pos = |harness: &Harness, tree: &Tree<Pane>, tile_id: TileId| {
+        tab_rect(harness, tree, tile_id).right_center() - egui::vec2(margin, 0.0)
+    };
+    let pos = close_button_pos(&harness, &tree, a);
+    harness.click(&mut tree, &mut behavior, pos);
+    assert!(tree.tiles.get(a).is_some(), "a may not be closed");
+
+    let pos = close_button_pos(&harness, &tree, b);
+    harness.click(&mut tree, &mut behavior, pos);
+    assert!(tree.tiles.get(b).is_none());
+
+    // Dragging a tab by its left end (away from any close button) to the bottom of another pane.
+    // A tab that can't be dragged lets the press through to the tab bar, which drags the
+    // whole tabs container, so lock that as well:
+    behavior.locked.push(tabs);
+    let target = tree.tiles.rect(c).expect("c shown").center_bottom() - egui::vec2(0.0, 10.0);
+    let from = tab_rect(&harness, &tree, a).left_center() + egui::vec2(2.0, 0.0);
+    harness.drag(&mut tree, &mut behavior, from, target);
+    assert_eq!(tree.tiles.parent_of(a), Some(tabs), "a may not be dragged");
+
+    let from = tab_rect(&harness, &tree, d).left_center() + egui::vec2(2.0, 0.0);
+    harness.drag(&mut tree, &mut behavior, from, target);
+    assert_eq!(tree.tiles.parent_of(d), Some(c));
+}
+
+#[test]
+fn test_drag_divider() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    let width_before = tree.tiles.rect(left).expect("left shown").width();
+
+    assert!(harness.drag_divider(
+        &mut tree,
+        &mut TestBehavior,
+        [left, right],
+        egui::vec2(50.0, 0.0)
+    ));
+    let width_after = tree.tiles.rect(left).expect("left shown").width();
+    assert!(width_before + 25.0 < width_after);
+}
+
+#[test]
+fn test_take_edit_summary() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(tree.take_edit_summary().is_empty());
+
+    let divider = egui::vec2(50.0, 0.0);
+    assert!(harness.drag_divider(&mut tree, &mut TestBehavior, [left, right], divider));
+    let summary = tree.take_edit_summary();
+    assert_eq!(summary.actions(), &[EditAction::TileResized]);
+    assert!(summary.layout_changed());
+
+    // Taking the summary resets it:
+    assert!(tree.take_edit_summary().is_empty());
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(tree.take_edit_summary().is_empty());
+}
+
+#[test]
+fn test_parent_of_after_replacing_container() {
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    let third = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![first, second]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    // Builds the index used by `parent_of`:
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(tree.tiles.parent_of(first), Some(root));
+    assert_eq!(tree.tiles.parent_of(third), None);
+
+    // The new container shares `second` with the one it replaces:
+    let tabs = Tile::Container(Container::new_tabs(vec![second, third]));
+    tree.tiles.insert(root, tabs);
+    assert_eq!(tree.tiles.parent_of(first), None);
+    assert_eq!(tree.tiles.parent_of(second), Some(root));
+    assert_eq!(tree.tiles.parent_of(third), Some(root));
+}
+
+#[test]
+fn test_tree_response() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    let response = harness.run(&mut tree, &mut TestBehavior);
+    assert!(!response.structure_changed);
+    assert_eq!(response.dropped, None);
+
+    let right_rect = tree.tiles.rect(right).expect("right shown");
+    harness.hover(&mut tree, &mut TestBehavior, right_rect.center());
+    let response = harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(response.hovered_pane, Some(right));
+    assert!(!response.structure_changed);
+
+    tree.remove_recursively(left);
+    let response = harness.run(&mut tree, &mut TestBehavior);
+    assert!(response.structure_changed);
+}
+
+#[test]
+fn test_drag_handle() {
+    struct HandleBehavior;
+
+    impl Behavior<Pane> for HandleBehavior {
+        fn pane_ui(&mut self, ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+            // Content that uses drags for itself, like a plot:
+            ui.allocate_rect(ui.max_rect(), egui::Sense::drag());
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn pane_drag_handle_height(
+            &self,
+            _style: &egui::Style,
+            _tile_id: TileId,
+            _pane: &Pane,
+        ) -> f32 {
+            20.0
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_vertical_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut HandleBehavior);
+    let left_rect = tree.tiles.rect(left).expect("left shown");
+    let right_rect = tree.tiles.rect(right).expect("right shown");
+    let target = right_rect.right_center() - egui::vec2(10.0, 0.0);
+    let _summary = tree.take_edit_summary();
+
+    // Dragging the pane contents leaves the pane where it is:
+    harness.drag(&mut tree, &mut HandleBehavior, left_rect.center(), target);
+    assert!(!tree.take_edit_summary().contains(&EditAction::TileDropped));
+
+    // Dragging the handle moves it:
+    let handle = left_rect.center_top() + egui::vec2(0.0, 10.0);
+    harness.drag(&mut tree, &mut HandleBehavior, handle, target);
+    assert!(tree.take_edit_summary().contains(&EditAction::TileDropped));
+}
+
+#[test]
+fn test_scroll_resize() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    let width_before = tree.tiles.rect(left).expect("left shown").width();
+    let divider = egui::pos2(
+        tree.tiles.rect(left).expect("left shown").right() + 0.5,
+        150.0,
+    );
+
+    // Without the modifiers, scrolling does nothing:
+    let down = egui::vec2(0.0, -10.0);
+    harness.scroll(
+        &mut tree,
+        &mut TestBehavior,
+        divider,
+        down,
+        egui::Modifiers::NONE,
+    );
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(
+        tree.tiles.rect(left).expect("left shown").width(),
+        width_before
+    );
+
+    harness.scroll(
+        &mut tree,
+        &mut TestBehavior,
+        divider,
+        down,
+        egui::Modifiers::CTRL,
+    );
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(width_before < tree.tiles.rect(left).expect("left shown").width());
+}
+
+#[test]
+fn test_scroll_overflowing_linear() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..3).map(|_| tiles.insert_pane(Pane)).collect();
+    let root = tiles.insert_vertical_tile(panes.clone());
+    let Some(Tile::Container(Container::Linear(linear))) = tiles.get_mut(root) else {
+        panic!("root should be a linear container");
+    };
+    linear.overflow = LinearOverflow::Scroll;
+    for &pane in &panes {
+        linear.set_min_size(pane, Some(150.0));
+    }
+    let mut tree = Tree::new("my_tree", root, tiles);
+    tree.enable_history(10);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    let rects_before: Vec<egui::Rect> = panes
+        .iter()
+        .map(|&pane| tree.tiles.rect(pane).expect("pane laid out"))
+        .collect();
+
+    harness.scroll(
+        &mut tree,
+        &mut TestBehavior,
+        egui::pos2(200.0, 150.0),
+        egui::vec2(0.0, -50.0),
+        egui::Modifiers::NONE,
+    );
+    for _ in 0..10 {
+        harness.run(&mut tree, &mut TestBehavior); // Let the smooth scrolling finish
+    }
+
+    for (&pane, before) in panes.iter().zip(rects_before) {
+        let after = tree.tiles.rect(pane).expect("pane laid out");
+        assert!(
+            after.top() < before.top(),
+            "{pane:?} should have moved up, from {before:?} to {after:?}"
+        );
+        assert_eq!(after.height(), before.height());
+    }
+
+    // Scrolling is not an edit of the layout:
+    assert!(!tree.can_undo());
+}
+
+/// Remembers which panes were shown.
+struct RecordingBehavior {
+    shown: Vec<TileId>,
+}
+
+impl Behavior<Pane> for RecordingBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+        self.shown.push(tile_id);
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+        "Pane".into()
+    }
+}
+
+#[test]
+fn test_cull_nested_off_screen_panes() {
+    // Two columns twice as tall as the screen, inside a scrolling column:
+    let mut tiles = Tiles::default();
+    let mut columns = vec![];
+    let mut top_panes = vec![];
+    let mut bottom_panes = vec![];
+    for _ in 0..2 {
+        let top = tiles.insert_pane(Pane);
+        let bottom = tiles.insert_pane(Pane);
+        columns.push(tiles.insert_vertical_tile(vec![top, bottom]));
+        top_panes.push(top);
+        bottom_panes.push(bottom);
+    }
+    let inner = tiles.insert_horizontal_tile(columns);
+    let below = tiles.insert_pane(Pane);
+    let root = tiles.insert_vertical_tile(vec![inner, below]);
+    let Some(Tile::Container(Container::Linear(linear))) = tiles.get_mut(root) else {
+        panic!("root should be a linear container");
+    };
+    linear.overflow = LinearOverflow::Scroll;
+    linear.set_min_size(inner, Some(600.0));
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut behavior = RecordingBehavior { shown: vec![] };
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+
+    // Only the top halves of the columns are on screen:
+    assert!(tree.tiles.rect(bottom_panes[0]).expect("laid out").top() > 300.0);
+    assert_eq!(behavior.shown, top_panes);
+}
+
+#[test]
+fn test_cull_grid_in_scrolled_linear() {
+    // A grid four screens tall, inside a scrolling column:
+    let mut tiles = Tiles::default();
+    let cells: Vec<TileId> = (0..9).map(|_| tiles.insert_pane(Pane)).collect();
+    let grid = tiles.insert_grid_tile(cells.clone());
+    let below = tiles.insert_pane(Pane);
+    let root = tiles.insert_vertical_tile(vec![grid, below]);
+    let Some(Tile::Container(Container::Linear(linear))) = tiles.get_mut(root) else {
+        panic!("root should be a linear container");
+    };
+    linear.overflow = LinearOverflow::Scroll;
+    linear.set_min_size(grid, Some(1200.0));
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let screen = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 300.0));
+    let mut behavior = RecordingBehavior { shown: vec![] };
+    let mut harness = Harness::new(screen.size());
+
+    // Only the cells on screen are shown, before and after scrolling down by a screen:
+    for scroll in [0.0, 300.0] {
+        if scroll > 0.0 {
+            harness.scroll(
+                &mut tree,
+                &mut behavior,
+                screen.center(),
+                egui::vec2(0.0, -scroll),
+                egui::Modifiers::NONE,
+            );
+            for _ in 0..10 {
+                harness.run(&mut tree, &mut behavior); // Let the smooth scrolling finish
+            }
+        }
+        behavior.shown.clear();
+        harness.run(&mut tree, &mut behavior);
+
+        let on_screen: Vec<TileId> = cells
+            .iter()
+            .copied()
+            .filter(|&cell| screen.intersects(tree.tiles.rect(cell).expect("cell laid out")))
+            .collect();
+        assert!(on_screen.len() < cells.len());
+        assert_eq!(behavior.shown, on_screen, "scrolled by {scroll}");
+    }
+    assert!(tree.tiles.rect(cells[0]).expect("cell laid out").top() < 0.0);
+}
+
+#[test]
+fn test_tab_switch_animation() {
+    struct AnimatedBehavior {
+        animation: TabSwitchAnimation,
+
+        /// The opacity and clip rect of each pane shown.
+        shown: Vec<(TileId, f32, egui::Rect)>,
+    }
+
+    impl Behavior<Pane> for AnimatedBehavior {
+        fn pane_ui(&mut self, ui: &mut egui::Ui, tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+            self.shown.push((tile_id, ui.opacity(), ui.clip_rect()));
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn tab_switch_animation(&self) -> TabSwitchAnimation {
+            self.animation
+        }
+
+        fn tab_switch_duration(&self, _style: &egui::Style) -> f32 {
+            1.0
+        }
+    }
+
+    for animation in [TabSwitchAnimation::Crossfade, TabSwitchAnimation::Slide] {
+        let mut tiles = Tiles::default();
+        let first = tiles.insert_pane(Pane);
+        let second = tiles.insert_pane(Pane);
+        let root = tiles.insert_tab_tile(vec![first, second]);
+        let mut tree = Tree::new("my_tree", root, tiles);
+
+        let mut behavior = AnimatedBehavior {
+            animation,
+            shown: vec![],
+        };
+        let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+        harness.run(&mut tree, &mut behavior);
+        assert!(harness.click_tab(&mut tree, &mut behavior, second));
+        for _ in 0..10 {
+            harness.run(&mut tree, &mut behavior);
+        }
+
+        // Halfway through the switch both tabs are shown:
+        behavior.shown.clear();
+        harness.run(&mut tree, &mut behavior);
+        let shown: Vec<TileId> = behavior
+            .shown
+            .iter()
+            .map(|&(tile_id, ..)| tile_id)
+            .collect();
+        assert_eq!(shown, vec![first, second], "{animation:?}");
+
+        let root_rect = tree.tiles.rect(root).expect("root shown");
+        for &(tile_id, opacity, clip_rect) in &behavior.shown {
+            let rect = tree.tiles.rect(tile_id).expect("pane shown");
+            if animation == TabSwitchAnimation::Crossfade {
+                assert!(
+                    0.0 < opacity && opacity < 1.0,
+                    "{tile_id:?} should be fading, but has opacity {opacity}"
+                );
+            } else {
+                assert_eq!(opacity, 1.0);
+                assert!(
+                    root_rect.contains_rect(clip_rect) && !clip_rect.contains_rect(rect),
+                    "{tile_id:?} at {rect:?} should be clipped to the tabs, not {clip_rect:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_busy_pane_blocks_clicks() {
+    struct ButtonBehavior {
+        busy: bool,
+        clicks: usize,
+    }
+
+    impl Behavior<Pane> for ButtonBehavior {
+        fn pane_ui(&mut self, ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+            let size = ui.available_size();
+            if ui.add_sized(size, egui::Button::new("Click")).clicked() {
+                self.clicks += 1;
+            }
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn is_pane_busy(&self, _tile_id: TileId, _pane: &Pane) -> bool {
+            self.busy
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let pane = tiles.insert_pane(Pane);
+    let root = tiles.insert_vertical_tile(vec![pane]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    let mut behavior = ButtonBehavior {
+        busy: true,
+        clicks: 0,
+    };
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+    let center = tree.tiles.rect(pane).expect("pane shown").center();
+
+    harness.click(&mut tree, &mut behavior, center);
+    assert_eq!(behavior.clicks, 0);
+
+    behavior.busy = false;
+    harness.click(&mut tree, &mut behavior, center);
+    assert_eq!(behavior.clicks, 1);
+}
+
+#[test]
+fn test_drag_and_drop_payload() {
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    let left = tiles.insert_tab_tile(vec![first]);
+    let right = tiles.insert_tab_tile(vec![second]);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(tree.dragged_id(harness.ctx()), None);
+
+    // Some widget outside the tree starts dragging `first`:
+    let payload = DraggedTile {
+        tree_id: tree.id(),
+        tile_id: first,
+    };
+    egui::DragAndDrop::set_payload(harness.ctx(), payload);
+    assert_eq!(tree.dragged_id(harness.ctx()), Some(first));
+
+    let target = tree.tiles.rect(second).expect("second shown").center();
+    harness.drag(&mut tree, &mut TestBehavior, target, target);
+    assert_ne!(tree.tiles.parent_of(first), Some(left));
+    assert_eq!(tree.dragged_id(harness.ctx()), None);
+}
+
+#[test]
+fn test_drop_on_tab_bar_edge_splits() {
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    // Two tabs each, so that neither container is simplified away:
+    let top = vec![first, tiles.insert_pane(Pane)];
+    let top = tiles.insert_tab_tile(top);
+    let bottom = vec![second, tiles.insert_pane(Pane)];
+    let bottom = tiles.insert_tab_tile(bottom);
+    let root = tiles.insert_vertical_tile(vec![top, bottom]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+
+    // The far left of the bottom tab bar, where the first tab would otherwise go:
+    let bottom_rect = tree.tiles.rect(bottom).expect("bottom shown");
+    let tab_bar_height = TestBehavior.tab_bar_height(&egui::Style::default());
+    let target = bottom_rect.left_top() + egui::vec2(2.0, tab_bar_height / 2.0);
+    assert!(harness.drag_tab(&mut tree, &mut TestBehavior, first, target));
+
+    let first_rect = tree.tiles.rect(first).expect("first shown");
+    let second_rect = tree.tiles.rect(second).expect("second shown");
+    assert!(first_rect.right() <= second_rect.left());
+    assert_eq!(first_rect.bottom(), second_rect.bottom());
+}
+
+#[test]
+fn test_on_tile_inserted() {
+    #[derive(Default)]
+    struct InsertBehavior {
+        inserted: Vec<(TileId, Option<TileId>)>,
+    }
+
+    impl Behavior<Pane> for InsertBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn on_tile_inserted(
+            &mut self,
+            _tiles: &mut Tiles<Pane>,
+            tile_id: TileId,
+            parent: Option<TileId>,
+        ) {
+            self.inserted.push((tile_id, parent));
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    let root = tiles.insert_tab_tile(vec![first, second]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    let mut behavior = InsertBehavior::default();
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+    assert_eq!(
+        behavior.inserted,
+        vec![(root, None), (first, Some(root)), (second, Some(root))]
+    );
+
+    // Nothing new, nothing announced:
+    harness.run(&mut tree, &mut behavior);
+    assert_eq!(behavior.inserted.len(), 3);
+
+    let third = tree.tiles.insert_pane(Pane);
+    if let Some(Tile::Container(container)) = tree.tiles.get_mut(root) {
+        container.add_child(third);
+    }
+    harness.run(&mut tree, &mut behavior);
+    assert_eq!(behavior.inserted[3..], [(third, Some(root))]);
+}
+
+#[test]
+fn test_drag_grid_corner() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..4).map(|_| tiles.insert_pane(Pane)).collect();
+    let root = tiles.insert_grid_tile(panes.clone());
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    let before = tree.tiles.rect(panes[0]).expect("pane shown");
+    let last = tree.tiles.rect(panes[3]).expect("pane shown");
+
+    // Where the column gutter crosses the row gutter:
+    let corner = before.right_bottom().lerp(last.left_top(), 0.5);
+    harness.drag(
+        &mut tree,
+        &mut TestBehavior,
+        corner,
+        corner + egui::vec2(40.0, 30.0),
+    );
+
+    let after = tree.tiles.rect(panes[0]).expect("pane shown");
+    assert!(before.width() + 20.0 < after.width());
+    assert!(before.height() + 15.0 < after.height());
+}
+
+#[test]
+fn test_preferred_share_on_drop() {
+    struct ShareBehavior;
+
+    impl Behavior<Pane> for ShareBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn preferred_share(&self, _pane: &Pane) -> Option<f32> {
+            Some(0.25)
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    // A second tab keeps `top` from being simplified away, so `first` has a tab to drag:
+    let top = vec![first, tiles.insert_pane(Pane)];
+    let top = tiles.insert_tab_tile(top);
+    let root = tiles.insert_vertical_tile(vec![top, second]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut ShareBehavior);
+
+    // The right edge of `second` splits it, with the new container taking its id:
+    let second_rect = tree.tiles.rect(second).expect("second shown");
+    let target = second_rect.right_center() - egui::vec2(10.0, 0.0);
+    assert!(harness.drag_tab(&mut tree, &mut ShareBehavior, first, target));
+    assert_eq!(tree.tiles.parent_of(first), Some(second));
+
+    let first_rect = tree.tiles.rect(first).expect("first shown");
+    let split_rect = tree.tiles.rect(second).expect("split shown");
+    assert!(first_rect.width() < 0.3 * split_rect.width());
+    assert!(0.2 * split_rect.width() < first_rect.width());
+}
+
+#[test]
+fn test_max_children_wraps_in_tabs() {
+    struct MaxBehavior {
+        row: TileId,
+    }
+
+    impl Behavior<Pane> for MaxBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn max_children(&self, _tiles: &Tiles<Pane>, container_id: TileId) -> Option<usize> {
+            (container_id == self.row).then_some(2)
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let dragged = tiles.insert_pane(Pane);
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    // A second tab keeps `top` from being simplified away, so `dragged` has a tab to drag:
+    let top = vec![dragged, tiles.insert_pane(Pane)];
+    let top = tiles.insert_tab_tile(top);
+    let row = tiles.insert_horizontal_tile(vec![left, right]);
+    let root = tiles.insert_vertical_tile(vec![top, row]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    let mut behavior = MaxBehavior { row };
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+
+    // Between `left` and `right`:
+    let target = tree.tiles.rect(left).expect("left shown").right_center();
+    assert!(harness.drag_tab(&mut tree, &mut behavior, dragged, target));
+
+    let row_container = tree.tiles.get_container(row).expect("row kept");
+    assert_eq!(row_container.num_children(), 2);
+    let parent = tree
+        .tiles
+        .parent_of(dragged)
+        .expect("dragged is in the tree");
+    assert!(matches!(
+        tree.tiles.get(parent),
+        Some(Tile::Container(Container::Tabs(_)))
+    ));
+}
+
+#[test]
+fn test_filter() {
+    struct Named(&'static str);
+
+    struct FilterBehavior;
+
+    impl Behavior<Named> for FilterBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Named,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &Named) -> egui::WidgetText {
+            pane.0.into()
+        }
+
+        fn hide_filtered_tabs(&self) -> bool {
+            true
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let console = tiles.insert_pane(Named("Console"));
+    let viewer = tiles.insert_pane(Named("Viewer"));
+    let plot = tiles.insert_pane(Named("Plot"));
+    let root = tiles.insert_tab_tile(vec![console, viewer, plot]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    tree.set_filter(Some("view".to_owned()));
+    harness.run(&mut tree, &mut FilterBehavior);
+    assert_eq!(tree.matches_filter(viewer), Some(true));
+    assert_eq!(tree.matches_filter(root), Some(true));
+    assert_eq!(tree.matches_filter(plot), Some(false));
+
+    // Only the matching tab and the active one are shown:
+    harness.run(&mut tree, &mut FilterBehavior);
+    assert!(harness.click_tab(&mut tree, &mut FilterBehavior, viewer));
+    assert!(!harness.click_tab(&mut tree, &mut FilterBehavior, plot));
+
+    tree.set_filter(None);
+    assert_eq!(tree.matches_filter(plot), None);
+    harness.run(&mut tree, &mut FilterBehavior);
+    assert!(harness.click_tab(&mut tree, &mut FilterBehavior, plot));
+}
+
+#[test]
+fn test_pane_visibility_events() {
+    use egui_tiles::{TreeEvent, VisibilityReason};
+
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    let third = tiles.insert_pane(Pane);
+    let tabs = tiles.insert_tab_tile(vec![first, second]);
+    let root = tiles.insert_horizontal_tile(vec![tabs, third]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    tree.enable_events();
+
+    let visibility_events = |tree: &mut Tree<Pane>| -> Vec<TreeEvent> {
+        tree.take_events()
+            .into_iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    TreeEvent::PaneShown { .. } | TreeEvent::PaneHidden { .. }
+                )
+            })
+            .collect()
+    };
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(
+        visibility_events(&mut tree),
+        vec![
+            TreeEvent::PaneShown {
+                tile_id: first,
+                reason: VisibilityReason::Inserted,
+            },
+            TreeEvent::PaneShown {
+                tile_id: third,
+                reason: VisibilityReason::Inserted,
+            },
+        ]
+    );
+
+    // The inactive tab was hidden from the start:
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(harness.click_tab(&mut tree, &mut TestBehavior, second));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(
+        visibility_events(&mut tree),
+        vec![
+            TreeEvent::PaneHidden {
+                tile_id: first,
+                reason: VisibilityReason::TabSwitched,
+            },
+            TreeEvent::PaneShown {
+                tile_id: second,
+                reason: VisibilityReason::TabSwitched,
+            },
+        ]
+    );
+
+    tree.set_visible(third, false);
+    harness.run(&mut tree, &mut TestBehavior);
+    tree.set_visible(third, true);
+    harness.run(&mut tree, &mut TestBehavior);
+    assert_eq!(
+        visibility_events(&mut tree),
+        vec![
+            TreeEvent::PaneHidden {
+                tile_id: third,
+                reason: VisibilityReason::Invisible,
+            },
+            TreeEvent::PaneShown {
+                tile_id: third,
+                reason: VisibilityReason::Invisible,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_history() {
+    use egui_tiles::{Container, EditSummary, TreeEvent};
+
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane);
+    let b = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![a, b]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    tree.enable_history(2);
+    tree.enable_events();
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(!tree.can_undo());
+
+    let mut set_share = |tree: &mut Tree<Pane>, share: f32| {
+        if let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(root) {
+            linear.shares[a] = share;
+        }
+        harness.run(tree, &mut TestBehavior);
+    };
+    let share = |tree: &Tree<Pane>| match tree.tiles.get(root) {
+        Some(Tile::Container(Container::Linear(linear))) => linear.shares[a],
+        _ => panic!("root should be a linear container"),
+    };
+
+    for s in [2.0, 3.0, 4.0] {
+        set_share(&mut tree, s);
+    }
+    let _: Vec<TreeEvent> = tree.take_events();
+    let _: EditSummary = tree.take_edit_summary();
+
+    // Only the last two steps are kept:
+    assert!(tree.undo(&mut TestBehavior));
+    assert_eq!(share(&tree), 3.0);
+    assert!(tree.undo(&mut TestBehavior));
+    assert_eq!(share(&tree), 2.0);
+    assert!(!tree.undo(&mut TestBehavior));
+
+    // Undoing is an edit like any other, and the tiles are not announced again:
+    assert_eq!(
+        tree.take_events(),
+        vec![TreeEvent::LayoutUndone, TreeEvent::LayoutUndone]
+    );
+    assert_eq!(
+        tree.take_edit_summary().actions(),
+        &[EditAction::LayoutUndone]
+    );
+    set_share(&mut tree, 2.0); // the share it already has
+    assert_eq!(tree.take_events(), vec![]);
+
+    assert!(tree.redo(&mut TestBehavior));
+    assert_eq!(share(&tree), 3.0);
+    assert!(tree.can_redo());
+    assert_eq!(tree.take_events(), vec![TreeEvent::LayoutRedone]);
+    assert_eq!(
+        tree.take_edit_summary().actions(),
+        &[EditAction::LayoutRedone]
+    );
+
+    // A new change forgets what was undone:
+    set_share(&mut tree, 5.0);
+    assert!(!tree.can_redo());
+    assert!(tree.undo(&mut TestBehavior));
+    assert_eq!(share(&tree), 3.0);
+
+    // The panes themselves are not part of the history:
+    assert!(tree.tiles.get(a).is_some_and(|tile| tile.is_pane()));
+    assert!(tree.tiles.get(b).is_some_and(|tile| tile.is_pane()));
+}
+
+#[test]
+fn test_history_close_and_add() {
+    use egui_tiles::NextTo;
+
+    struct ClosableBehavior;
+
+    impl Behavior<Pane> for ClosableBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn is_tab_closable(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+            true
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane);
+    let b = tiles.insert_pane(Pane);
+    let c = tiles.insert_pane(Pane);
+    let root = tiles.insert_tab_tile(vec![a, b, c]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    tree.enable_history(10);
+    let mut behavior = ClosableBehavior;
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+
+    let children = |tree: &Tree<Pane>| match tree.tiles.get(root) {
+        Some(Tile::Container(container)) => container.children().copied().collect::<Vec<_>>(),
+        _ => panic!("root should be a container"),
+    };
+    let is_pane = |tree: &Tree<Pane>, tile_id| tree.tiles.get(tile_id).is_some_and(Tile::is_pane);
+
+    // Undoing a close brings the pane back, in its old place:
+    tree.set_selected_tabs(root, &[b]);
+    assert_eq!(tree.close_selected_tabs(&mut behavior), 1);
+    harness.run(&mut tree, &mut behavior);
+    assert!(!is_pane(&tree, b));
+
+    assert!(tree.undo(&mut behavior));
+    assert!(is_pane(&tree, b));
+    assert_eq!(children(&tree), vec![a, b, c]);
+    harness.run(&mut tree, &mut behavior);
+    assert!(
+        is_pane(&tree, b),
+        "the restored pane should survive the next frame"
+    );
+
+    assert!(tree.redo(&mut behavior));
+    assert!(!is_pane(&tree, b));
+    assert!(tree.undo(&mut behavior));
+    assert!(is_pane(&tree, b));
+    harness.run(&mut tree, &mut behavior);
+
+    // Undoing an add takes the pane out, and redoing it puts the same pane back:
+    let d = tree
+        .insert_pane_after(a, Pane, NextTo::NextTab)
+        .expect("a is in the tree");
+    harness.run(&mut tree, &mut behavior);
+    assert_eq!(children(&tree), vec![a, d, b, c]);
+
+    assert!(tree.undo(&mut behavior));
+    assert!(!is_pane(&tree, d));
+    assert_eq!(children(&tree), vec![a, b, c]);
+    harness.run(&mut tree, &mut behavior);
+
+    assert!(tree.redo(&mut behavior));
+    assert!(is_pane(&tree, d));
+    assert_eq!(children(&tree), vec![a, d, b, c]);
+    harness.run(&mut tree, &mut behavior);
+
+    // A new edit after an undo forgets the undone add, pane and all:
+    assert!(tree.undo(&mut behavior));
+    tree.set_selected_tabs(root, &[c]);
+    assert_eq!(tree.close_selected_tabs(&mut behavior), 1);
+    harness.run(&mut tree, &mut behavior);
+    assert!(!tree.can_redo());
+    assert!(!tree.redo(&mut behavior));
+    assert!(!is_pane(&tree, d));
+    assert_eq!(children(&tree), vec![a, b]);
+
+    // …but the close can still be undone:
+    assert!(tree.undo(&mut behavior));
+    assert!(is_pane(&tree, c));
+    assert_eq!(children(&tree), vec![a, b, c]);
+}
+
+#[test]
+fn test_reset_layout_clears_history() {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane);
+    let b = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![a, b]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    tree.enable_history(10);
+    tree.enable_events();
+    tree.tiles.set_metadata(a, "note", "kept");
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut TestBehavior);
+    if let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(root) {
+        linear.shares[a] = 2.0;
+    }
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(tree.can_undo());
+
+    // The template reuses the ids of the old layout for other tiles:
+    let template = Tree::new_tabs("template", vec![(), ()]);
+    let template_root = template.root.expect("template has a root");
+    tree.reset_layout(&template, |_| (), |()| Pane);
+
+    assert!(!tree.can_undo());
+    assert!(!tree.undo(&mut TestBehavior));
+    assert_eq!(tree.root, Some(template_root));
+    assert_eq!(tree.tiles.len(), 3);
+    assert!(!tree.take_events().is_empty(), "recorded events are kept");
+    let first_tab = tree
+        .tiles
+        .get_container(template_root)
+        .expect("root is a container")
+        .children_vec()[0];
+    assert_eq!(
+        tree.tiles.metadata_as::<String>(first_tab, "note"),
+        Some("kept".to_owned()),
+        "metadata moves with its pane"
+    );
+
+    // New changes can be undone, back to the template layout:
+    let second_tab = tree
+        .tiles
+        .get_container(template_root)
+        .expect("root is a container")
+        .children_vec()[1];
+    harness.run(&mut tree, &mut TestBehavior);
+    assert!(harness.click_tab(&mut tree, &mut TestBehavior, second_tab));
+    assert!(tree.active_tiles().contains(&second_tab));
+    assert!(tree.undo(&mut TestBehavior));
+    assert!(tree.active_tiles().contains(&first_tab));
+    assert!(!tree.undo(&mut TestBehavior));
+    assert_eq!(tree.tiles.len(), 3);
+}
+
+#[test]
+fn test_render_inactive_container() {
+    /// Keeps all panes running in the background, and records which ones were shown.
+    #[derive(Default)]
+    struct RenderInactive {
+        shown: Vec<TileId>,
+    }
+
+    impl Behavior<Pane> for RenderInactive {
+        fn pane_ui(&mut self, _ui: &mut egui::Ui, tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+            self.shown.push(tile_id);
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn render_inactive(&self, _tile_id: TileId, _pane: &Pane) -> bool {
+            true
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let active = tiles.insert_pane(Pane);
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let inactive = tiles.insert_horizontal_tile(vec![left, right]);
+    let root = tiles.insert_tab_tile(vec![active, inactive]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let mut behavior = RenderInactive::default();
+    Harness::new(egui::vec2(400.0, 300.0)).run(&mut tree, &mut behavior);
+    assert!(!tree.active_tiles().contains(&inactive));
+
+    // The panes inside the inactive container keep running too:
+    behavior.shown.sort();
+    let mut expected = vec![active, left, right];
+    expected.sort();
+    assert_eq!(behavior.shown, expected);
+}
+
+#[test]
+fn test_ui_deferred() {
+    /// Panics if a deferred pane is shown anyway.
+    struct NoPaneUi;
+
+    impl Behavior<Pane> for NoPaneUi {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            panic!("deferred panes should not be shown by the tree");
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let ctx = egui::Context::default();
+    let raw_input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(400.0, 300.0),
+        )),
+        ..Default::default()
+    };
+    let mut deferred = Vec::new();
+    let _output = ctx.run(raw_input, |ctx| {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none())
+            .show(ctx, |ui| deferred = tree.ui_deferred(&mut NoPaneUi, ui));
+    });
+
+    let rect = |tile_id: TileId| tree.tiles.rect(tile_id).expect("pane laid out");
+    assert_eq!(deferred, vec![(left, rect(left)), (right, rect(right))]);
+    assert!(rect(left).right() <= rect(right).left());
+    assert!(rect(left).width() > 150.0 && rect(right).width() > 150.0);
+}
+
+#[test]
+fn test_modal() {
+    /// Each pane is one big button, recording which pane was clicked.
+    struct ModalBehavior {
+        clicked: Vec<TileId>,
+        close_on_backdrop_click: bool,
+    }
+
+    impl Behavior<Pane> for ModalBehavior {
+        fn pane_ui(&mut self, ui: &mut egui::Ui, tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+            let size = ui.available_size();
+            if ui.add_sized(size, egui::Button::new("Click")).clicked() {
+                self.clicked.push(tile_id);
+            }
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn close_modal_on_backdrop_click(&self) -> bool {
+            self.close_on_backdrop_click
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    let mut behavior = ModalBehavior {
+        clicked: vec![],
+        close_on_backdrop_click: false,
+    };
+
+    let mut harness = Harness::new(egui::vec2(400.0, 300.0));
+    harness.run(&mut tree, &mut behavior);
+    let outside_modal = egui::pos2(20.0, 150.0); // in `left`, outside of the modal rect
+    harness.click(&mut tree, &mut behavior, outside_modal);
+    assert_eq!(behavior.clicked, vec![left]);
+    behavior.clicked.clear();
+
+    tree.enter_modal(right);
+    assert_eq!(tree.modal(), Some(right));
+    harness.run(&mut tree, &mut behavior);
+    let modal_rect = tree.tiles.rect(right).expect("modal shown");
+    assert_eq!(
+        modal_rect.center(),
+        egui::pos2(200.0, 150.0),
+        "centered over the tree"
+    );
+
+    // The rest of the tree ignores input, but the modal gets it:
+    harness.click(&mut tree, &mut behavior, outside_modal);
+    assert_eq!(behavior.clicked, vec![]);
+    assert_eq!(tree.modal(), Some(right), "closing on the backdrop is off");
+    harness.click(&mut tree, &mut behavior, modal_rect.center());
+    assert_eq!(behavior.clicked, vec![right]);
+    behavior.clicked.clear();
+
+    assert_eq!(tree.exit_modal(), Some(right));
+    assert_eq!(tree.modal(), None);
+    harness.run(&mut tree, &mut behavior);
+    assert!(
+        tree.tiles.rect(right).expect("right shown").min.x >= 200.0,
+        "back in place"
+    );
+
+    // Clicking the backdrop closes the modal, without reaching the tree behind it:
+    behavior.close_on_backdrop_click = true;
+    tree.enter_modal(right);
+    harness.run(&mut tree, &mut behavior);
+    harness.click(&mut tree, &mut behavior, outside_modal);
+    assert_eq!(tree.modal(), None);
+    assert_eq!(behavior.clicked, vec![]);
+}
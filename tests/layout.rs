@@ -0,0 +1,617 @@
+use egui::{pos2, Rect};
+use egui_tiles::{
+    Behavior, Container, DividerAction, Edge, LinearOverflow, ShareRedistribution, Tile, TileId,
+    Tiles, Tree, UiResponse,
+};
+
+struct Pane;
+
+struct TestBehavior;
+
+impl Behavior<Pane> for TestBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+        "Pane".into()
+    }
+}
+
+#[test]
+fn test_compute_layout_without_ui() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(101.0, 50.0));
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+
+    assert_eq!(rects[&root], rect);
+    assert_eq!(rects[&left].width(), rects[&right].width());
+    assert!(rects[&left].right() < rects[&right].left());
+    assert_eq!(rects[&left].height(), rect.height());
+}
+
+#[test]
+fn test_layout_snapshot() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(201.0, 100.0));
+    let snapshot = tree.layout_snapshot(&mut TestBehavior, &egui::Style::default(), rect);
+
+    assert_eq!(
+        snapshot,
+        "#3 Horizontal 0,0 201x100\n  #1 Pane \"Pane\" 0,0 100x100\n  #2 Pane \"Pane\" 101,0 100x100\n"
+    );
+}
+
+#[test]
+fn test_format_debug() {
+    let mut tiles = Tiles::default();
+    let files = tiles.insert_pane(Pane);
+    let console = tiles.insert_pane(Pane);
+    let viewer = tiles.insert_pane(Pane);
+    let plot = tiles.insert_pane(Pane);
+    let row = tiles.insert_horizontal_tile(vec![files, console]);
+    let grid = tiles.insert_grid_tile(vec![viewer, plot]);
+    let root = tiles.insert_tab_tile(vec![row, grid]);
+    tiles.set_visible(console, false);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(row) else {
+        panic!("Expected a linear container");
+    };
+    linear.shares[files] = 1.5;
+
+    let text = tree.format_debug(&mut TestBehavior);
+    let expected = format!(
+        "Tree {:?}
+  #7 Tabs, active: #5
+    #5 Horizontal
+      #1 [share 1.50] Pane \"Pane\"
+      #2 [share 1.00, invisible] Pane \"Pane\"
+    #6 Grid (Auto), columns: [], rows: []
+      #3 Pane \"Pane\"
+      #4 Pane \"Pane\"
+",
+        tree.id()
+    );
+    assert_eq!(text, expected);
+}
+
+#[test]
+fn test_title_bars() {
+    struct TitleBarBehavior;
+
+    impl Behavior<Pane> for TitleBarBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn title_bar_height(&self, _style: &egui::Style) -> f32 {
+            20.0
+        }
+
+        fn gap_width(&self, _style: &egui::Style) -> f32 {
+            0.0
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let top = tiles.insert_pane(Pane);
+    let bottom = tiles.insert_pane(Pane);
+    let root = tiles.insert_vertical_tile(vec![top, bottom]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 140.0));
+    let rects = tree.compute_layout(&mut TitleBarBehavior, &egui::Style::default(), rect);
+
+    // Each pane is below its own title bar:
+    assert_eq!(
+        rects[&top],
+        Rect::from_min_max(pos2(0.0, 20.0), pos2(100.0, 70.0))
+    );
+    assert_eq!(
+        rects[&bottom],
+        Rect::from_min_max(pos2(0.0, 90.0), pos2(100.0, 140.0))
+    );
+}
+
+#[test]
+fn test_grid_template_areas() {
+    let mut tiles = Tiles::default();
+    let header = tiles.insert_pane(Pane);
+    let sidebar = tiles.insert_pane(Pane);
+    let main = tiles.insert_pane(Pane);
+    let extra = tiles.insert_pane(Pane);
+    let root = tiles.insert_grid_tile(vec![]);
+    let Some(Tile::Container(Container::Grid(grid))) = tiles.get_mut(root) else {
+        panic!("root should be a grid");
+    };
+    assert!(!grid.set_template_areas(&["header header", "sidebar"]));
+    assert!(grid.set_template_areas(&["header header", "sidebar main", ". main"]));
+    grid.add_child(extra);
+    assert!(grid.set_area_child("header", header));
+    assert!(grid.set_area_child("sidebar", sidebar));
+    assert!(grid.set_area_child("main", main));
+    assert!(!grid.set_area_child("footer", extra));
+    assert_eq!(grid.area_of(main), Some("main"));
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(200.0, 300.0));
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+
+    // The header spans both columns, and main spans the two lower rows:
+    assert_eq!(rects[&header].width(), rect.width());
+    assert_eq!(rects[&sidebar].left(), rects[&extra].left());
+    assert_eq!(rects[&main].top(), rects[&sidebar].top());
+    assert_eq!(rects[&main].bottom(), rects[&extra].bottom());
+    assert!(rects[&sidebar].bottom() < rects[&extra].top());
+
+    // Removing a child frees its area:
+    tree.remove_recursively(main);
+    let Some(Tile::Container(Container::Grid(grid))) = tree.tiles.get(root) else {
+        panic!("root should be a grid");
+    };
+    assert_eq!(grid.area_child("main"), None);
+}
+
+#[test]
+fn test_collapsible_sections() {
+    let mut tiles = Tiles::default();
+    let top = tiles.insert_pane(Pane);
+    let middle = tiles.insert_pane(Pane);
+    let bottom = tiles.insert_pane(Pane);
+    let root = tiles.insert_vertical_tile(vec![top, middle, bottom]);
+    let Some(Tile::Container(Container::Linear(linear))) = tiles.get_mut(root) else {
+        panic!("root should be a linear container");
+    };
+    linear.collapsible = true;
+    linear.set_collapsed(middle, true);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 300.0));
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+
+    // The folded section is just its header, and the others share the rest:
+    assert!(!rects.contains_key(&middle));
+    assert_eq!(rects[&top].height(), rects[&bottom].height());
+    assert_eq!(rects[&bottom].bottom(), rect.bottom());
+    assert!(rects[&top].top() > rect.top());
+}
+
+#[test]
+fn test_drop_indicator_rect() {
+    use egui_tiles::{DropIndicator, DropOperation};
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 50.0));
+    assert_eq!(
+        DropIndicator::FilledRect.rect(rect, DropOperation::SplitLeft, 2.0),
+        rect
+    );
+    assert_eq!(
+        DropIndicator::HalfRect.rect(rect, DropOperation::SplitRight, 2.0),
+        Rect::from_min_max(pos2(50.0, 0.0), pos2(100.0, 50.0))
+    );
+    assert_eq!(
+        DropIndicator::Line.rect(rect, DropOperation::SplitDown, 2.0),
+        Rect::from_min_max(pos2(0.0, 48.0), pos2(100.0, 50.0))
+    );
+}
+
+#[test]
+fn test_share_redistribution() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let middle = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, middle, right]);
+    let Some(Tile::Container(Container::Linear(linear))) = tiles.get_mut(root) else {
+        panic!("root should be a linear container");
+    };
+    linear.share_redistribution = ShareRedistribution::NextSibling;
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    tree.remove_recursively(middle);
+    let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(root) else {
+        panic!("root should be a linear container");
+    };
+    assert_eq!(linear.shares[left], 1.0);
+    assert_eq!(linear.shares[right], 2.0);
+}
+
+#[test]
+fn test_is_shown() {
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    let tabs = tiles.insert_tab_tile(vec![first, second]);
+    let side = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![tabs, side]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    assert!(tree.is_shown(first));
+    assert!(!tree.is_shown(second), "inactive tab");
+    assert!(tree.is_shown(side));
+
+    tree.set_visible(tabs, false);
+    assert!(!tree.is_shown(first), "hidden parent");
+
+    tree.remove_recursively(side);
+    assert!(!tree.is_shown(side), "removed");
+}
+
+#[test]
+fn test_toggle_visibility() {
+    let mut tiles = Tiles::default();
+    let main = tiles.insert_pane(Pane);
+    let console = tiles.insert_pane(Pane);
+    let root = tiles.insert_vertical_tile(vec![main, console]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    if let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(root) {
+        linear.shares[console] = 0.25;
+    }
+
+    let style = egui::Style::default();
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 201.0));
+    let before = tree.compute_layout(&mut TestBehavior, &style, rect);
+
+    // The other tiles take up the space of a hidden one:
+    tree.toggle_visibility(console);
+    assert!(!tree.is_visible(console));
+    assert!(!tree.active_tiles().contains(&console));
+    let hidden = tree.compute_layout(&mut TestBehavior, &style, rect);
+    assert_eq!(hidden[&main], rect);
+    assert!(!hidden.contains_key(&console));
+
+    // …and it comes back with the same size:
+    tree.toggle_visibility(console);
+    assert!(tree.is_visible(console));
+    assert_eq!(tree.compute_layout(&mut TestBehavior, &style, rect), before);
+
+    // Hiding the root hides everything, without losing the tree:
+    tree.toggle_visibility(root);
+    assert!(tree.active_tiles().is_empty());
+    assert!(!tree.is_shown(main));
+    assert!(tree
+        .compute_layout(&mut TestBehavior, &style, rect)
+        .is_empty());
+    assert_eq!(tree.root(), Some(root));
+
+    tree.toggle_visibility(root);
+    assert!(tree.is_shown(main));
+    assert!(tree.is_shown(console));
+    assert_eq!(tree.compute_layout(&mut TestBehavior, &style, rect), before);
+}
+
+#[test]
+fn test_scroll_overflow() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..3).map(|_| tiles.insert_pane(Pane)).collect();
+    let root = tiles.insert_vertical_tile(panes.clone());
+    let Some(Tile::Container(Container::Linear(linear))) = tiles.get_mut(root) else {
+        panic!("root should be a linear container");
+    };
+    linear.overflow = LinearOverflow::Scroll;
+    linear.set_min_size(panes[0], Some(150.0));
+    linear.set_min_size(panes[1], Some(150.0));
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 300.0));
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+
+    // The children keep their minimum sizes, and stick out at the bottom:
+    assert_eq!(rects[&panes[0]].height(), 150.0);
+    assert_eq!(rects[&panes[1]].height(), 150.0);
+    assert_eq!(rects[&panes[2]].height(), TestBehavior.min_size());
+    assert!(rects[&panes[2]].bottom() > rect.bottom());
+}
+
+#[test]
+fn test_layout_follows_behavior_values() {
+    struct SizedBehavior {
+        tab_bar_height: f32,
+        min_size: f32,
+    }
+
+    impl Behavior<Pane> for SizedBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn tab_bar_height(&self, _style: &egui::Style) -> f32 {
+            self.tab_bar_height
+        }
+
+        fn min_size(&self) -> f32 {
+            self.min_size
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let tab = tiles.insert_pane(Pane);
+    let tabbed = tiles.insert_tab_tile(vec![tab]);
+    let panes: Vec<TileId> = (0..3).map(|_| tiles.insert_pane(Pane)).collect();
+    let column = tiles.insert_vertical_tile(panes.clone());
+    let Some(Tile::Container(Container::Linear(linear))) = tiles.get_mut(column) else {
+        panic!("column should be a linear container");
+    };
+    linear.overflow = LinearOverflow::Scroll;
+    let root = tiles.insert_horizontal_tile(vec![tabbed, column]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let style = egui::Style::default();
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(200.0, 300.0));
+    let mut behavior = SizedBehavior {
+        tab_bar_height: 20.0,
+        min_size: 50.0,
+    };
+    let rects = tree.compute_layout(&mut behavior, &style, rect);
+    assert_eq!(rects[&tab].top(), 20.0);
+    assert!(rects[&panes[0]].bottom() < rect.bottom());
+
+    // The cached layout is redone when the behavior changes, without `invalidate_layout`:
+    behavior.tab_bar_height = 40.0;
+    behavior.min_size = 200.0;
+    let rects = tree.compute_layout(&mut behavior, &style, rect);
+    assert_eq!(rects[&tab].top(), 40.0);
+    assert_eq!(rects[&panes[0]].height(), 200.0);
+}
+
+#[test]
+fn test_layout_follows_gaps_and_grid_columns() {
+    struct GapBehavior {
+        inner_gap: f32,
+        num_cols: usize,
+    }
+
+    impl Behavior<Pane> for GapBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut Pane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+            "Pane".into()
+        }
+
+        fn gap_width_at_depth(&self, _style: &egui::Style, depth: usize) -> f32 {
+            if depth == 0 {
+                10.0
+            } else {
+                self.inner_gap
+            }
+        }
+
+        fn grid_auto_column_count(
+            &self,
+            _num_visible_children: usize,
+            _rect: Rect,
+            _gap: f32,
+        ) -> usize {
+            self.num_cols
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let column = tiles.insert_vertical_tile(vec![left, right]);
+    let cells: Vec<TileId> = (0..4).map(|_| tiles.insert_pane(Pane)).collect();
+    let grid = tiles.insert_grid_tile(cells.clone());
+    let root = tiles.insert_horizontal_tile(vec![column, grid]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let style = egui::Style::default();
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(400.0, 200.0));
+    let mut behavior = GapBehavior {
+        inner_gap: 2.0,
+        num_cols: 2,
+    };
+    let rects = tree.compute_layout(&mut behavior, &style, rect);
+    assert_eq!(rects[&right].top() - rects[&left].bottom(), 2.0);
+    assert_eq!(rects[&cells[1]].top(), rects[&cells[0]].top());
+
+    // The cached layout is redone when the behavior changes, without `invalidate_layout`:
+    behavior.inner_gap = 8.0;
+    behavior.num_cols = 1;
+    let rects = tree.compute_layout(&mut behavior, &style, rect);
+    assert_eq!(rects[&right].top() - rects[&left].bottom(), 8.0);
+    assert!(rects[&cells[1]].top() > rects[&cells[0]].bottom());
+}
+
+#[test]
+fn test_min_size() {
+    let style = egui::Style::default();
+    let min = TestBehavior.min_size();
+    let gap = TestBehavior.gap_width(&style);
+    let tab_bar_height = TestBehavior.tab_bar_height(&style);
+
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let tabbed = tiles.insert_tab_tile(vec![right]);
+    let root = tiles.insert_horizontal_tile(vec![left, tabbed]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let min_size = tree.min_size(&TestBehavior, &style);
+    assert_eq!(min_size, egui::vec2(2.0 * min + gap, min + tab_bar_height));
+
+    fn linear(tree: &mut Tree<Pane>, tile_id: TileId) -> &mut egui_tiles::Linear {
+        match tree.tiles.get_mut(tile_id) {
+            Some(Tile::Container(Container::Linear(linear))) => linear,
+            _ => panic!("{tile_id:?} should be a linear container"),
+        }
+    }
+
+    // A child can ask for more room along the linear:
+    linear(&mut tree, root).set_min_size(left, Some(100.0));
+    assert_eq!(tree.min_size(&TestBehavior, &style).x, 100.0 + min + gap);
+
+    // …unless the linear can scroll:
+    linear(&mut tree, root).overflow = LinearOverflow::Scroll;
+    assert_eq!(tree.min_size(&TestBehavior, &style).x, 0.0);
+}
+
+#[test]
+fn test_pixel_snapping() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<TileId> = (0..3).map(|_| tiles.insert_pane(Pane)).collect();
+    let row = tiles.insert_horizontal_tile(panes.clone());
+    let grid_panes: Vec<TileId> = (0..9).map(|_| tiles.insert_pane(Pane)).collect();
+    let grid = tiles.insert_grid_tile(grid_panes.clone());
+    let root = tiles.insert_vertical_tile(vec![row, grid]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0));
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+
+    // Splitting 100 points three ways must not land the edges between pixels:
+    for tile_id in panes.iter().chain(&grid_panes) {
+        let r = rects[tile_id];
+        for edge in [r.left(), r.right(), r.top(), r.bottom()] {
+            assert_eq!(
+                edge,
+                edge.round(),
+                "{tile_id:?} is not pixel-aligned: {r:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_right_to_left() {
+    let mut tiles = Tiles::default();
+    let first = tiles.insert_pane(Pane);
+    let second = tiles.insert_pane(Pane);
+    let third = tiles.insert_pane(Pane);
+    let top = tiles.insert_horizontal_tile(vec![first, second]);
+    let root = tiles.insert_vertical_tile(vec![top, third]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    tree.set_right_to_left(true);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0));
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+
+    // The first child is on the right:
+    assert!(rects[&second].right() < rects[&first].left());
+    assert_eq!(rects[&first].right(), rect.right());
+    assert_eq!(rects[&second].left(), rect.left());
+
+    // Vertical containers are unaffected:
+    assert!(rects[&top].bottom() < rects[&third].top());
+    assert_eq!(rects[&third].width(), rect.width());
+}
+
+#[test]
+fn test_divider_actions() {
+    let mut tiles = Tiles::default();
+    let left = tiles.insert_pane(Pane);
+    let right = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![left, right]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(201.0, 100.0));
+    let style = egui::Style::default();
+    tree.compute_layout(&mut TestBehavior, &style, rect);
+
+    let apply = |tree: &mut Tree<Pane>, action: DividerAction| {
+        let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(root) else {
+            panic!("root should be a Linear");
+        };
+        let mut linear = linear.clone();
+        assert!(linear.apply_divider_action(&tree.tiles, &TestBehavior, action, [left, right]));
+        tree.tiles
+            .insert(root, Tile::Container(Container::Linear(linear)));
+        tree.compute_layout(&mut TestBehavior, &style, rect)
+    };
+
+    let rects = apply(&mut tree, DividerAction::CollapseBefore);
+    assert_eq!(rects[&left].width(), TestBehavior.min_size());
+
+    let rects = apply(&mut tree, DividerAction::Equalize);
+    assert_eq!(rects[&left].width(), rects[&right].width());
+
+    apply(&mut tree, DividerAction::ToggleLock);
+    let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(root) else {
+        panic!("root should be a Linear");
+    };
+    assert!(linear.is_divider_locked([right, left]));
+}
+
+#[test]
+fn test_auto_hide_and_pin() {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane);
+    let b = tiles.insert_pane(Pane);
+    let c = tiles.insert_pane(Pane);
+    let root = tiles.insert_horizontal_tile(vec![a, b, c]);
+    let mut tree = Tree::new("my_tree", root, tiles);
+    let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(300.0, 200.0));
+    let children = |tree: &Tree<Pane>| match tree.tiles.get(root) {
+        Some(Tile::Container(container)) => container.children_vec(),
+        _ => panic!("root should be a container"),
+    };
+
+    tree.auto_hide(root, Edge::Left);
+    assert!(!tree.is_auto_hidden(root), "the root can't be auto-hidden");
+
+    tree.auto_hide(a, Edge::Right);
+    assert!(tree.is_auto_hidden(a));
+    assert_eq!(
+        tree.auto_hidden().collect::<Vec<_>>(),
+        vec![(a, Edge::Right)]
+    );
+    assert_eq!(children(&tree), vec![b, c]);
+
+    // The strip along the edge takes space from the rest of the tree:
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+    assert!(rects[&root].right() < rect.right());
+    assert_eq!(rects[&root].left(), rect.left());
+
+    // Auto-hidden tiles are not reachable from the root, but gc keeps them:
+    tree.gc(&mut TestBehavior);
+    assert!(tree.tiles.get(a).is_some());
+    assert!(tree.is_auto_hidden(a));
+
+    // Pinning docks the tile along the edge it was hidden at:
+    tree.pin(a);
+    assert!(!tree.is_auto_hidden(a));
+    assert_eq!(children(&tree), vec![b, c, a]);
+    let rects = tree.compute_layout(&mut TestBehavior, &egui::Style::default(), rect);
+    assert_eq!(rects[&root], rect);
+
+    // Removed tiles are forgotten:
+    tree.auto_hide(b, Edge::Top);
+    tree.tiles.remove(b);
+    tree.gc(&mut TestBehavior);
+    assert_eq!(tree.auto_hidden().count(), 0);
+}
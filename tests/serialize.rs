@@ -33,6 +33,15 @@ fn create_tree() -> Tree<Pane> {
     Tree::new("my_tree", root, tiles)
 }
 
+/// Like [`create_tree`], with some metadata on the root.
+fn create_tree_with_metadata() -> Tree<Pane> {
+    let mut tree = create_tree();
+    let root = tree.root().expect("tree has a root");
+    tree.tiles.set_metadata(root, "user-created", true);
+    tree.tiles.set_metadata(root, "template-slot", "main");
+    tree
+}
+
 #[test]
 fn test_serialize_json() {
     let original = create_tree();
@@ -48,3 +57,461 @@ fn test_serialize_ron() {
     let restored = ron::from_str(&ron).expect("ron deserialize");
     assert_eq!(original, restored, "RON did not round-trip");
 }
+
+#[test]
+fn test_migrate_unversioned() {
+    let original = create_tree();
+
+    // Simulate a layout saved before the format was versioned:
+    let mut json: serde_json::Value = serde_json::to_value(&original).expect("json serialize");
+    json.as_object_mut()
+        .expect("tree is a json object")
+        .remove("format_version");
+
+    let mut restored: Tree<Pane> = serde_json::from_value(json).expect("json deserialize");
+    assert_eq!(restored.format_version(), 0);
+
+    restored.migrate();
+    assert_eq!(restored.format_version(), egui_tiles::FORMAT_VERSION);
+    assert_eq!(original, restored, "Migration changed the tree");
+}
+
+#[test]
+fn test_split_panes() {
+    let original = create_tree();
+
+    let (layout, panes) = original.split_panes(|_, pane| pane.nr);
+    let layout_json = serde_json::to_string(&layout).expect("json serialize layout");
+    let panes_json = serde_json::to_string(&panes).expect("json serialize panes");
+
+    let layout: Tree<()> = serde_json::from_str(&layout_json).expect("json deserialize layout");
+    let mut panes: std::collections::BTreeMap<egui_tiles::TileId, usize> =
+        serde_json::from_str(&panes_json).expect("json deserialize panes");
+    let restored = layout.join_panes(|tile_id| panes.remove(&tile_id).map(|nr| Pane { nr }));
+
+    assert!(panes.is_empty(), "Not all panes were used");
+    assert_eq!(original, restored, "Split panes did not round-trip");
+}
+
+/// A layout written by `egui_tiles` 0.11, before the format was versioned,
+/// with shares that could be persisted back then.
+const TREE_V0_JSON: &str = include_str!("fixtures/tree_v0.json");
+
+#[test]
+fn test_migrate_v0_fixture() {
+    use egui_tiles::{Container, Tile, TileId};
+
+    let mut tree: Tree<Pane> = serde_json::from_str(TREE_V0_JSON).expect("json deserialize");
+    assert_eq!(tree.format_version(), 0);
+    tree.migrate();
+    assert_eq!(tree.format_version(), egui_tiles::FORMAT_VERSION);
+    assert_eq!(tree.tiles.len(), 5);
+
+    let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(TileId(1)) else {
+        panic!("Expected a linear container at the root");
+    };
+    assert_eq!(linear.shares[TileId(2)], 1.0);
+    assert_eq!(linear.shares[TileId(3)], 1.0);
+
+    let Some(Tile::Container(Container::Grid(grid))) = tree.tiles.get(TileId(3)) else {
+        panic!("Expected a grid container");
+    };
+    assert_eq!(grid.col_shares, vec![1.0, 3.0]);
+    assert_eq!(grid.row_shares, vec![1.0]);
+}
+
+/// A layout written by [`egui_tiles::FORMAT_VERSION`] 1, following the documented schema.
+///
+/// This must keep loading, and keep serializing to the same output,
+/// until the format version is bumped.
+const TREE_V1_JSON: &str = include_str!("fixtures/tree_v1.json");
+
+#[test]
+fn test_schema_v1() {
+    use egui_tiles::{Container, GridLayout, LinearDir, Tile, TileId};
+
+    let tree: Tree<Pane> = serde_json::from_str(TREE_V1_JSON).expect("json deserialize");
+    assert_eq!(tree.format_version(), 1);
+    assert_eq!(tree.root(), Some(TileId(1)));
+    assert_eq!(tree.tiles.len(), 8);
+    assert!(!tree.tiles.is_visible(TileId(5)));
+    assert!(tree.is_auto_hidden(TileId(8)));
+    assert_eq!(tree.tiles.get_pane(&TileId(7)), Some(&Pane { nr: 7 }));
+
+    let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get(TileId(1)) else {
+        panic!("Expected tabs at the root");
+    };
+    assert_eq!(tabs.children, vec![TileId(2), TileId(3)]);
+    assert_eq!(tabs.active, Some(TileId(2)));
+
+    let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(TileId(2)) else {
+        panic!("Expected a linear container");
+    };
+    assert_eq!(linear.dir, LinearDir::Horizontal);
+    assert_eq!(linear.shares[TileId(4)], 1.5);
+
+    let Some(Tile::Container(Container::Grid(grid))) = tree.tiles.get(TileId(3)) else {
+        panic!("Expected a grid container");
+    };
+    assert_eq!(grid.layout, GridLayout::Columns(2));
+    assert_eq!(grid.row_shares, vec![2.0, 1.0]);
+
+    // Writing it back out must produce the same document:
+    let expected: serde_json::Value = serde_json::from_str(TREE_V1_JSON).expect("valid json");
+    let written = serde_json::to_value(&tree).expect("json serialize");
+    assert_eq!(expected, written, "The serialized schema changed");
+}
+
+#[test]
+fn test_schema_defaults() {
+    // Everything but the tiles themselves may be omitted:
+    let json = r#"{
+        "id": 42,
+        "root": 1,
+        "tiles": {
+            "tiles": {
+                "1": { "Container": { "Linear": { "children": [2], "dir": "Vertical" } } },
+                "2": { "Pane": { "nr": 2 } }
+            }
+        }
+    }"#;
+    let mut tree: Tree<Pane> = serde_json::from_str(json).expect("json deserialize");
+    assert_eq!(tree.format_version(), 0);
+    tree.migrate();
+    assert_eq!(tree.tiles.len(), 2);
+    assert_eq!(
+        tree.tiles.get_pane(&egui_tiles::TileId(2)),
+        Some(&Pane { nr: 2 })
+    );
+}
+
+#[test]
+fn test_serialize_ui_state() {
+    let mut original = create_tree();
+    let root = original.root().expect("tree has a root");
+    let Some(egui_tiles::Tile::Container(egui_tiles::Container::Tabs(tabs))) =
+        original.tiles.get(root)
+    else {
+        panic!("Expected tabs at the root");
+    };
+    let last_tab = *tabs.children.last().expect("tabs have children");
+    let first_tab = tabs.children[0];
+
+    original.make_active(|tile_id, _| tile_id == last_tab);
+    original.enter_modal(first_tab);
+
+    let json = serde_json::to_string(&original).expect("json serialize");
+    let restored: Tree<Pane> = serde_json::from_str(&json).expect("json deserialize");
+    assert_eq!(restored.modal(), Some(first_tab));
+    assert_eq!(original, restored, "UI state did not round-trip");
+}
+
+#[test]
+fn test_serialize_auto_hidden() {
+    let mut original = create_tree();
+    let root = original.root().expect("tree has a root");
+    let Some(egui_tiles::Tile::Container(container)) = original.tiles.get(root) else {
+        panic!("Expected a container at the root");
+    };
+    let last_tab = *container.children().last().expect("root has children");
+    original.auto_hide(last_tab, egui_tiles::Edge::Bottom);
+
+    let json = serde_json::to_string(&original).expect("json serialize");
+    let restored: Tree<Pane> = serde_json::from_str(&json).expect("json deserialize");
+    assert_eq!(
+        restored.auto_hidden().collect::<Vec<_>>(),
+        vec![(last_tab, egui_tiles::Edge::Bottom)]
+    );
+    assert_eq!(original, restored, "auto-hidden tiles did not round-trip");
+}
+
+#[test]
+fn test_serialize_tab_links() {
+    let mut original = create_tree();
+    let first = original
+        .tiles
+        .find_pane(&Pane { nr: 0 })
+        .expect("pane is in the tree");
+    let last = original
+        .tiles
+        .find_pane(&Pane { nr: 18 })
+        .expect("pane is in the tree");
+    original.link_tabs([first, last]);
+
+    let json = serde_json::to_string(&original).expect("json serialize");
+    let restored: Tree<Pane> = serde_json::from_str(&json).expect("json deserialize");
+    assert_eq!(restored.linked_tabs(first).collect::<Vec<_>>(), vec![last]);
+    assert_eq!(original, restored, "tab links did not round-trip");
+}
+
+#[test]
+fn test_serialize_metadata() {
+    let original = create_tree_with_metadata();
+    let root = original.root().expect("tree has a root");
+
+    let json = serde_json::to_string(&original).expect("json serialize");
+    let restored: Tree<Pane> = serde_json::from_str(&json).expect("json deserialize");
+    assert_eq!(
+        restored.tiles.metadata_as::<bool>(root, "user-created"),
+        Some(true)
+    );
+    assert_eq!(
+        restored.tiles.metadata_as::<String>(root, "template-slot"),
+        Some("main".to_owned())
+    );
+    assert_eq!(restored.tiles.metadata(root, "missing"), None);
+}
+
+#[test]
+fn test_pane_serde_hooks() {
+    /// A pane that can't be serialized directly.
+    #[derive(Debug, PartialEq)]
+    struct RuntimePane {
+        nr: usize,
+        texture: std::sync::Arc<()>,
+    }
+
+    struct Hooks;
+
+    impl egui_tiles::PaneSerde<RuntimePane> for Hooks {
+        type Saved = Pane;
+
+        fn save_pane(&mut self, pane: &RuntimePane) -> Pane {
+            Pane { nr: pane.nr }
+        }
+
+        fn load_pane(&mut self, saved: Pane) -> Option<RuntimePane> {
+            (saved.nr % 2 == 0).then(|| RuntimePane {
+                nr: saved.nr,
+                texture: Default::default(),
+            })
+        }
+    }
+
+    let original = create_tree().map_panes(|_, pane| RuntimePane {
+        nr: pane.nr,
+        texture: Default::default(),
+    });
+
+    let saved = original.save_panes(&mut Hooks);
+    let json = serde_json::to_string(&saved).expect("json serialize");
+    let saved: Tree<Pane> = serde_json::from_str(&json).expect("json deserialize");
+    let restored: Tree<RuntimePane> = Tree::load_panes(saved, &mut Hooks);
+
+    // Panes that failed to load are dropped:
+    let num_panes = |tree: &Tree<RuntimePane>| tree.tiles.tiles().filter(|t| t.is_pane()).count();
+    assert_eq!(num_panes(&original), 19);
+    assert_eq!(num_panes(&restored), 10);
+}
+
+#[test]
+fn test_layout_description() {
+    use egui_tiles::{ContainerKind, LayoutDescription};
+
+    let json = r#"{
+        "kind": "Horizontal",
+        "groups": [
+            { "size": 1.0, "pane": 1 },
+            { "size": 3.0, "kind": "Tabs", "groups": [{ "pane": 2 }, { "pane": 3 }] }
+        ]
+    }"#;
+    let description: LayoutDescription<usize> =
+        serde_json::from_str(json).expect("json deserialize");
+    assert_eq!(description.kind, ContainerKind::Horizontal);
+
+    let tree = Tree::from_layout_description("my_tree", &description, |&nr| Pane { nr });
+    assert_eq!(tree.tiles.len(), 5);
+
+    let exported = tree
+        .to_layout_description(|pane| pane.nr)
+        .expect("tree is not empty");
+    assert_eq!(
+        description, exported,
+        "Layout description did not round-trip"
+    );
+}
+
+#[test]
+fn test_layout_description_json_round_trip() {
+    use egui_tiles::{ContainerKind, LayoutDescription};
+
+    let description = LayoutDescription::group(
+        ContainerKind::Vertical,
+        vec![
+            LayoutDescription::pane("toolbar".to_owned()).with_size(0.5),
+            LayoutDescription::group(
+                ContainerKind::Grid,
+                vec![
+                    LayoutDescription::pane("a".to_owned()),
+                    LayoutDescription::group(
+                        ContainerKind::Tabs,
+                        vec![
+                            LayoutDescription::pane("b".to_owned()),
+                            LayoutDescription::pane("c".to_owned()),
+                        ],
+                    ),
+                ],
+            )
+            .with_size(2.0),
+        ],
+    );
+
+    let json = serde_json::to_string(&description).expect("json serialize");
+    let restored: LayoutDescription<String> =
+        serde_json::from_str(&json).expect("json deserialize");
+    assert_eq!(description, restored);
+}
+
+#[test]
+fn test_serialize_is_deterministic() {
+    let mut original = create_tree();
+    let hidden: Vec<_> = original.tiles.tile_ids().collect();
+    for &tile_id in hidden.iter().rev().step_by(2) {
+        original.tiles.set_visible(tile_id, false);
+    }
+
+    // Every deserialized tree gets fresh hash maps, with a different iteration order:
+    let json = serde_json::to_string(&original).expect("json serialize");
+    for _ in 0..5 {
+        let restored: Tree<Pane> = serde_json::from_str(&json).expect("json deserialize");
+        let again = serde_json::to_string(&restored).expect("json serialize");
+        assert_eq!(json, again, "Serialization is not deterministic");
+    }
+
+    let value: serde_json::Value = serde_json::from_str(&json).expect("json deserialize");
+    let invisible: Vec<u64> = value["tiles"]["invisible"]
+        .as_array()
+        .expect("invisible is a list")
+        .iter()
+        .map(|tile_id| tile_id.as_u64().expect("tile id is a number"))
+        .collect();
+    assert!(invisible.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_compact_layout() {
+    use egui_tiles::{ContainerKind, LayoutDescription};
+
+    let description = LayoutDescription::group(
+        ContainerKind::Horizontal,
+        vec![
+            LayoutDescription::pane(1).with_size(1.0),
+            LayoutDescription::group(
+                ContainerKind::Tabs,
+                vec![LayoutDescription::pane(2), LayoutDescription::pane(3)],
+            )
+            .with_size(3.0),
+        ],
+    );
+    let tree = Tree::from_layout_description("my_tree", &description, |&nr| Pane { nr });
+
+    let code = tree
+        .to_compact_layout(|pane| pane.nr.to_string())
+        .expect("tree is not empty");
+    assert!(
+        code.bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_'),
+        "Not URL-safe: {code:?}"
+    );
+    let json = serde_json::to_string(&tree).expect("json serialize");
+    assert!(code.len() * 10 < json.len(), "{code:?} is not compact");
+
+    let restored = Tree::from_compact_layout("my_tree", &code, |key| Pane {
+        nr: key.parse().expect("pane key"),
+    })
+    .expect("valid code");
+    let restored = restored
+        .to_layout_description(|pane| pane.nr)
+        .expect("tree is not empty");
+    assert_eq!(restored.kind, ContainerKind::Horizontal);
+    assert_eq!(restored.groups[0].pane, Some(1));
+    assert_eq!(restored.groups[1].kind, ContainerKind::Tabs);
+    assert_eq!(restored.groups[1].groups.len(), 2);
+    let ratio = restored.groups[1].size / restored.groups[0].size;
+    assert!((ratio - 3.0).abs() < 0.05, "ratio: {ratio}");
+
+    // Truncated or garbled links are rejected:
+    assert!(LayoutDescription::decode_compact(&code[..code.len() - 2]).is_none());
+    assert!(LayoutDescription::decode_compact("not a layout!").is_none());
+}
+
+#[test]
+fn test_show_persisted() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use egui_tiles::{Behavior, Tile, TileId, UiResponse};
+
+    static NUM_CLONES: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct CountedPane(usize);
+
+    impl Clone for CountedPane {
+        fn clone(&self) -> Self {
+            NUM_CLONES.fetch_add(1, Ordering::Relaxed);
+            Self(self.0)
+        }
+    }
+
+    struct PersistedBehavior;
+
+    impl Behavior<CountedPane> for PersistedBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut CountedPane,
+        ) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &CountedPane) -> egui::WidgetText {
+            format!("Pane {}", pane.0).into()
+        }
+    }
+
+    let ctx = egui::Context::default();
+    let id = egui::Id::new("persisted");
+    let mut num_created = 0;
+    let mut run_frame = || {
+        let _output = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                Tree::show_persisted(
+                    ui,
+                    id,
+                    || {
+                        num_created += 1;
+                        Tree::new_tabs(id, vec![CountedPane(0), CountedPane(1)])
+                    },
+                    &mut PersistedBehavior,
+                );
+            });
+        });
+    };
+    run_frame();
+
+    // Switch tabs in the stored tree, which should still be there next frame:
+    ctx.data_mut(|data| {
+        let tree: &mut Tree<CountedPane> =
+            data.get_persisted_mut_or_insert_with(id, || panic!("the tree should be stored"));
+        tree.make_active(|_, tile| matches!(tile, Tile::Pane(CountedPane(1))));
+    });
+    run_frame();
+    run_frame();
+
+    assert_eq!(num_created, 1);
+    let tree: Tree<CountedPane> = ctx
+        .data_mut(|data| data.get_persisted(id))
+        .expect("the tree should be stored");
+    let active_panes: Vec<&CountedPane> = tree
+        .active_tiles()
+        .into_iter()
+        .filter_map(|tile_id| tree.tiles.get_pane(&tile_id))
+        .collect();
+    assert_eq!(active_panes, vec![&CountedPane(1)]);
+    assert_eq!(
+        NUM_CLONES.load(Ordering::Relaxed),
+        2,
+        "only by get_persisted above"
+    );
+}
@@ -0,0 +1,122 @@
+use egui_tiles::{
+    Container, ContainerKind, Grid, GridLayout, LinearDir, NormalizePolicy, SimplificationOptions,
+    Tile, TileId, Tiles, Tree,
+};
+
+fn container(tree: &Tree<()>, tile_id: TileId) -> &Container {
+    match tree.tiles.get(tile_id) {
+        Some(Tile::Container(container)) => container,
+        other => panic!("Expected a container, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_join_nested_grids() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<_> = (0..4).map(|_| tiles.insert_pane(())).collect();
+    let inner = tiles.insert_grid_tile(panes.clone());
+    let outer = tiles.insert_grid_tile(vec![inner]);
+    let mut tree = Tree::new("tree", outer, tiles);
+
+    tree.simplify(&SimplificationOptions {
+        join_nested_grids: true,
+        ..SimplificationOptions::OFF
+    });
+
+    assert_eq!(tree.root(), Some(outer));
+    assert!(tree.tiles.get(inner).is_none());
+    assert_eq!(container(&tree, outer).children_vec(), panes);
+}
+
+#[test]
+fn test_convert_single_line_grids() {
+    let mut tiles = Tiles::default();
+    let panes: Vec<_> = (0..3).map(|_| tiles.insert_pane(())).collect();
+    let mut row = Grid::new(panes.clone());
+    row.layout = GridLayout::Columns(3);
+    row.col_shares = vec![1.0, 2.0, 3.0];
+    let root = tiles.insert_container(row);
+    let mut tree = Tree::new("tree", root, tiles);
+
+    tree.simplify(&SimplificationOptions {
+        convert_single_line_grids: true,
+        ..SimplificationOptions::OFF
+    });
+
+    let Container::Linear(linear) = container(&tree, root) else {
+        panic!("Expected a linear container");
+    };
+    assert_eq!(linear.dir, LinearDir::Horizontal);
+    assert_eq!(linear.children, panes);
+    assert_eq!(linear.shares[panes[2]], 3.0);
+
+    // An auto grid may reflow, so it is left alone:
+    let mut tiles = Tiles::default();
+    let panes: Vec<_> = (0..3).map(|_| tiles.insert_pane(())).collect();
+    let root = tiles.insert_grid_tile(panes);
+    let mut tree = Tree::new("tree", root, tiles);
+    tree.simplify(&SimplificationOptions {
+        convert_single_line_grids: true,
+        ..SimplificationOptions::OFF
+    });
+    assert_eq!(container(&tree, root).kind(), ContainerKind::Grid);
+}
+
+#[test]
+fn test_simplify_report() {
+    let mut tiles = Tiles::default();
+    let pane = tiles.insert_pane(());
+    let empty = tiles.insert_tab_tile(vec![]);
+    let single = tiles.insert_tab_tile(vec![pane]);
+    let root = tiles.insert_vertical_tile(vec![empty, single]);
+    let mut tree = Tree::new("tree", root, tiles);
+
+    let report = tree.simplify(&SimplificationOptions {
+        prune_empty_tabs: true,
+        prune_single_child_tabs: true,
+        ..SimplificationOptions::OFF
+    });
+    assert_eq!(report.pruned, vec![empty]);
+    assert_eq!(report.collapsed, vec![single]);
+    assert_eq!(container(&tree, root).children_vec(), vec![pane]);
+
+    let report = tree.simplify(&SimplificationOptions::OFF);
+    assert!(report.is_empty());
+}
+
+#[test]
+fn test_normalize() {
+    struct TestBehavior;
+
+    impl egui_tiles::Behavior<()> for TestBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut (),
+        ) -> egui_tiles::UiResponse {
+            egui_tiles::UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, _pane: &()) -> egui::WidgetText {
+            "Pane".into()
+        }
+    }
+
+    let mut tiles = Tiles::default();
+    let top_level = tiles.insert_pane(());
+    let nested = tiles.insert_pane(());
+    let inner = tiles.insert_horizontal_tile(vec![nested]);
+    let root = tiles.insert_vertical_tile(vec![top_level, inner]);
+    let mut tree = Tree::new("tree", root, tiles);
+
+    let wrapped = tree.normalize(&TestBehavior, NormalizePolicy::TopLevelPanes);
+    assert_eq!(wrapped.len(), 1);
+    assert_eq!(container(&tree, top_level).kind(), ContainerKind::Tabs);
+    assert_eq!(container(&tree, top_level).children_vec(), wrapped);
+    assert!(tree.tiles.get(nested).is_some_and(|tile| tile.is_pane()));
+
+    let wrapped = tree.normalize(&TestBehavior, NormalizePolicy::AllPanes);
+    assert_eq!(wrapped.len(), 1);
+    assert_eq!(container(&tree, nested).kind(), ContainerKind::Tabs);
+}
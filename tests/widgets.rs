@@ -0,0 +1,177 @@
+use egui_tiles::{Behavior, TileId, Tiles, Tree, UiResponse};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Pane(usize);
+
+struct TestBehavior;
+
+impl Behavior<Pane> for TestBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut Pane) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, pane: &Pane) -> egui::WidgetText {
+        format!("Pane {}", pane.0).into()
+    }
+}
+
+fn create_tree() -> Tree<Pane> {
+    let mut tiles = Tiles::default();
+    let a = tiles.insert_pane(Pane(0));
+    let b = tiles.insert_pane(Pane(1));
+    let c = tiles.insert_pane(Pane(2));
+    let tabs = tiles.insert_tab_tile(vec![b, c]);
+    let root = tiles.insert_horizontal_tile(vec![a, tabs]);
+    Tree::new("my_tree", root, tiles)
+}
+
+/// Show a frame of the tree, with `side_ui` in a panel next to it.
+///
+/// Returns everything that was painted.
+fn run_frame(
+    ctx: &egui::Context,
+    tree: &mut Tree<Pane>,
+    mut side_ui: impl FnMut(&mut Tree<Pane>, &mut egui::Ui),
+) -> Vec<egui::epaint::ClippedShape> {
+    let raw_input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(800.0, 600.0),
+        )),
+        ..Default::default()
+    };
+    let output = ctx.run(raw_input, |ctx| {
+        egui::SidePanel::left("side").show(ctx, |ui| side_ui(tree, ui));
+        egui::CentralPanel::default().show(ctx, |ui| {
+            tree.ui(&mut TestBehavior, ui);
+        });
+    });
+    output.shapes
+}
+
+/// The text of all painted text shapes.
+fn texts(shapes: &[egui::epaint::ClippedShape]) -> Vec<String> {
+    shapes
+        .iter()
+        .filter_map(|clipped| match &clipped.shape {
+            egui::Shape::Text(text) => Some(text.galley.text().to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_inspector_ui() {
+    let mut tree = create_tree();
+    let original = tree.clone();
+
+    let ctx = egui::Context::default();
+    let mut shapes = vec![];
+    for _ in 0..3 {
+        shapes = run_frame(&ctx, &mut tree, |tree, ui| {
+            tree.inspector_ui(&mut TestBehavior, ui);
+        });
+    }
+
+    // Every tile is listed:
+    let texts = texts(&shapes);
+    for tile_id in tree.tiles.tile_ids() {
+        let label = format!("{tile_id:?}");
+        assert!(
+            texts.iter().any(|text| text.ends_with(&label)),
+            "{label} should be listed in {texts:?}"
+        );
+    }
+
+    // Just looking doesn't change anything:
+    assert_eq!(tree, original);
+}
+
+#[test]
+fn test_breadcrumbs_ui() {
+    let mut tree = create_tree();
+    let ctx = egui::Context::default();
+    let num_separators = |shapes: &[egui::epaint::ClippedShape]| {
+        texts(shapes).iter().filter(|text| *text == "▸").count()
+    };
+
+    // Nothing to show without focus:
+    let mut clicked = None;
+    let shapes = run_frame(&ctx, &mut tree, |tree, ui| {
+        clicked = tree.breadcrumbs_ui(&mut TestBehavior, ui);
+    });
+    assert_eq!(clicked, None);
+    assert_eq!(num_separators(&shapes), 0);
+
+    // One segment for the root, the tabs, and the focused pane:
+    let focused = tree
+        .tiles
+        .find_pane(&Pane(2))
+        .expect("the pane is in the tree");
+    assert!(tree.focus_pane(focused));
+    let mut shapes = vec![];
+    for _ in 0..2 {
+        shapes = run_frame(&ctx, &mut tree, |tree, ui| {
+            clicked = tree.breadcrumbs_ui(&mut TestBehavior, ui);
+        });
+    }
+    assert_eq!(clicked, None);
+    assert_eq!(num_separators(&shapes), 2);
+    assert!(texts(&shapes).iter().any(|text| text == "Pane 2"));
+    assert_eq!(tree.focused_pane(), Some(focused));
+}
+
+/// Show a frame with a minimap next to the tree.
+///
+/// Returns the shapes painted inside the minimap, and the pane that was clicked.
+fn minimap_frame(
+    ctx: &egui::Context,
+    tree: &mut Tree<Pane>,
+) -> (Vec<egui::epaint::ClippedShape>, Option<TileId>) {
+    let size = egui::vec2(160.0, 120.0);
+    let mut minimap_rect = egui::Rect::NOTHING;
+    let mut clicked = None;
+    let shapes = run_frame(ctx, tree, |tree, ui| {
+        minimap_rect = egui::Rect::from_min_size(ui.cursor().min, size);
+        clicked = tree.minimap_ui(&mut TestBehavior, ui, size);
+    });
+    let shapes = shapes
+        .into_iter()
+        .filter(|clipped| minimap_rect.contains_rect(clipped.clip_rect))
+        .collect();
+    (shapes, clicked)
+}
+
+#[test]
+fn test_minimap_ui() {
+    let mut tree = create_tree();
+    let ctx = egui::Context::default();
+
+    // Nothing to show before the tree has been shown:
+    let (shapes, clicked) = minimap_frame(&ctx, &mut tree);
+    assert_eq!(clicked, None);
+    assert!(texts(&shapes).is_empty());
+
+    // Then every shown pane, but not those behind inactive tabs:
+    let (shapes, clicked) = minimap_frame(&ctx, &mut tree);
+    assert_eq!(clicked, None);
+    assert_eq!(texts(&shapes), ["Pane 0", "Pane 1"]);
+
+    // The focused pane is outlined:
+    let focused = tree
+        .tiles
+        .find_pane(&Pane(1))
+        .expect("the pane is in the tree");
+    assert!(tree.focus_pane(focused));
+    let selection_stroke = ctx.style().visuals.selection.stroke;
+    let (shapes, clicked) = minimap_frame(&ctx, &mut tree);
+    assert_eq!(clicked, None);
+    let num_outlined = shapes
+        .iter()
+        .filter(|clipped| {
+            matches!(&clipped.shape, egui::Shape::Rect(rect) if rect.stroke == selection_stroke)
+        })
+        .count();
+    assert_eq!(num_outlined, 1);
+    assert_eq!(tree.focused_pane(), Some(focused));
+}